@@ -1,13 +1,30 @@
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use importer::{
-    LiftControlCompetitionId, LiftControlRegistry,
+    LiftControlConfig, LiftControlRegistry, LiftControlSpec,
     canonical::{
-        models::CanonicalFormat, transformer::CanonicalTransformer, validator::CanonicalValidator,
+        bulk::{BulkImporter, BulkOptions},
+        dumper::CanonicalDumper,
+        merge::{CanonicalMerger, MergeConflict, session_label},
+        models::{self, CanonicalFormat},
+        pipeline::PreparedImport,
+        upgrade,
+        validator::{CanonicalValidator, ValidationReport},
+        watch::DirectoryWatcher,
     },
-    sources::liftcontrol::{LiftControlClient, LiftControlExporter},
+    sources::html::{HtmlImporter, HtmlSpec, adapter, adapters},
+    sources::liftcontrol::{
+        DEFAULT_BASE_URL, DEFAULT_USER_AGENT, LiftControlClient, LiftControlClientConfig,
+        LiftControlImporter, SessionStatus,
+    },
+    sources::manual::{KnownEntities, ManualEntry, Prompter},
+    sources::metadata::SourceConfig,
+    sources::pdf::{DEFAULT_PDFTOTEXT, PdfImporter, PdfSpec, PdfTextExtractor},
 };
 use sqlx::postgres::PgPoolOptions;
 use std::path::PathBuf;
+use std::time::Duration;
+use storage::repository::competition::CompetitionRepository;
+use storage::services::export;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[derive(Parser)]
@@ -27,10 +44,74 @@ struct Cli {
 
 #[derive(Subcommand)]
 enum Commands {
+    #[command(alias = "liftcontrol")]
     LiftControl {
         #[command(flatten)]
         source: LiftControlSource,
 
+        /// Directory of competition files (TOML or YAML)
+        #[arg(
+            long,
+            env = "LIFTCONTROL_REGISTRY",
+            default_value = "./registry/liftcontrol"
+        )]
+        registry: PathBuf,
+
+        /// Export the sessions listed on the event page instead of the configured ones
+        #[arg(long, conflicts_with = "list")]
+        discover: bool,
+
+        /// Write one file per session instead of merging them
+        #[arg(long)]
+        split_sessions: bool,
+
+        #[command(flatten)]
+        client: LiftControlClientArgs,
+
+        #[arg(long, default_value = "./imports")]
+        output: PathBuf,
+    },
+    /// Read the result tables of a text-based PDF score sheet into a canonical file
+    Pdf {
+        /// PDF score sheet, or its text layer already extracted to a `.txt` file
+        file: PathBuf,
+
+        /// Competition file (TOML or YAML) with the slug and metadata the sheet lacks
+        #[arg(long)]
+        config: PathBuf,
+
+        /// `pdftotext` program used to read the text layer
+        #[arg(long, env = "PDFTOTEXT", default_value = DEFAULT_PDFTOTEXT)]
+        pdftotext: PathBuf,
+
+        #[arg(long, default_value = "./imports")]
+        output: PathBuf,
+    },
+    /// Read the result tables of a saved HTML results page into a canonical file
+    Html {
+        file: PathBuf,
+
+        /// Site adapter picking the results tables, e.g. `generic` or `4lift`
+        #[arg(long, default_value = "generic")]
+        adapter: String,
+
+        /// Competition file (TOML or YAML) with the slug and metadata the page lacks
+        #[arg(long)]
+        config: PathBuf,
+
+        /// Address the page was saved from
+        #[arg(long)]
+        url: Option<String>,
+
+        #[arg(long, default_value = "./imports")]
+        output: PathBuf,
+    },
+    /// Type in the results of a competition, section by section
+    Manual {
+        /// Type without suggestions of known athletes and federations from the database
+        #[arg(long)]
+        offline: bool,
+
         #[arg(long, default_value = "./imports")]
         output: PathBuf,
     },
@@ -39,6 +120,18 @@ enum Commands {
 
         #[arg(long)]
         validate_only: bool,
+
+        /// Import inside a rolled back transaction and print what would change
+        #[arg(long, conflicts_with = "validate_only")]
+        dry_run: bool,
+
+        /// Output format of the validation report or the dry-run diff
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+
+        /// Reject the file when validation reports warnings
+        #[arg(long)]
+        warnings_as_errors: bool,
     },
     BulkImport {
         #[arg(long, default_value = "./imports")]
@@ -46,17 +139,161 @@ enum Commands {
 
         #[arg(long)]
         validate_only: bool,
+
+        /// Output format of the validation reports
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+
+        /// Reject files whose validation reports warnings
+        #[arg(long)]
+        warnings_as_errors: bool,
+
+        /// Re-import files whose content was already imported
+        #[arg(long)]
+        force: bool,
+
+        /// Look for files at every level below the directory
+        #[arg(long)]
+        recursive: bool,
+
+        /// Import only the latest timestamped snapshot of each file series
+        #[arg(long)]
+        latest_only: bool,
+
+        /// Competitions imported at the same time
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u16).range(1..=32))]
+        jobs: u16,
+
+        /// Write a report of every file, as JSON (`.json`) or Markdown (`.md`)
+        #[arg(long)]
+        report: Option<PathBuf>,
+    },
+    /// Import, or only validate, canonical files as they are added or changed
+    Watch {
+        #[arg(long, default_value = "./imports")]
+        directory: PathBuf,
+
+        #[arg(long)]
+        validate_only: bool,
+
+        /// Reject files whose validation reports warnings
+        #[arg(long)]
+        warnings_as_errors: bool,
+
+        /// Re-import files whose content was already imported
+        #[arg(long)]
+        force: bool,
+
+        /// Watch every level below the directory
+        #[arg(long)]
+        recursive: bool,
+
+        /// Seconds between two looks at the directory
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+
+        /// Seconds a file must stay unchanged before it is processed
+        #[arg(long, default_value_t = 2)]
+        debounce: u64,
+    },
+    /// Rebuild the canonical JSON of a stored competition
+    Dump {
+        slug: String,
+
+        /// Output file, printed to stdout when omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Combine the session exports of one competition into a single canonical file
+    Merge {
+        /// Session files, in priority order: the first one wins conflicts
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
+        /// Output file, printed to stdout when omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Print the JSON Schema of the canonical format
+    Schema {
+        /// Output file, printed to stdout when omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Recompute the stored placings of completed competitions
+    Placings {
+        /// Slug of a single competition, every completed competition when omitted
+        slug: Option<String>,
+    },
+    /// Write the open-data bundle (CSV resources and datapackage.json) as a ZIP archive
+    Export {
+        /// Output path, defaults to a dated file name in the current directory
+        #[arg(short, long)]
+        output: Option<PathBuf>,
     },
 }
 
+#[derive(Clone, Copy, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
+}
+
 #[derive(clap::Args)]
 #[group(required = true, multiple = false)]
 struct LiftControlSource {
+    /// Base slug or alias of a competition of the registry
     #[arg(short, long)]
     competition: Option<String>,
 
     #[arg(short, long)]
     list: bool,
+
+    /// Competition file to export, without adding it to the registry
+    #[arg(long)]
+    config: Option<PathBuf>,
+}
+
+#[derive(clap::Args)]
+struct LiftControlClientArgs {
+    #[arg(long, env = "LIFTCONTROL_BASE_URL", default_value = DEFAULT_BASE_URL)]
+    base_url: String,
+
+    #[arg(long, env = "LIFTCONTROL_USER_AGENT", default_value = DEFAULT_USER_AGENT)]
+    user_agent: String,
+
+    /// Request timeout in seconds
+    #[arg(long, default_value_t = 30)]
+    timeout: u64,
+
+    /// Retries after a 429, a 5xx or a network error
+    #[arg(long, default_value_t = 3)]
+    max_retries: u32,
+
+    /// Minimum delay between two requests, in milliseconds
+    #[arg(long, default_value_t = 500)]
+    rate_limit_ms: u64,
+
+    /// Read recorded responses (`<sub_slug>.json`, `<base_slug>.html`) from a directory instead of LiftControl
+    #[arg(long)]
+    fixtures: Option<PathBuf>,
+}
+
+impl LiftControlClientArgs {
+    fn build(self) -> importer::Result<LiftControlClient> {
+        if let Some(dir) = self.fixtures {
+            return Ok(LiftControlClient::from_fixtures(dir));
+        }
+
+        LiftControlClient::new(LiftControlClientConfig {
+            base_url: self.base_url,
+            user_agent: self.user_agent,
+            timeout: Duration::from_secs(self.timeout),
+            max_retries: self.max_retries,
+            min_request_interval: Duration::from_millis(self.rate_limit_ms),
+            ..Default::default()
+        })
+    }
 }
 
 #[tokio::main]
@@ -68,27 +305,131 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let log_level = if cli.verbose { "debug" } else { "info" };
     tracing_subscriber::registry()
         .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| format!("import={},importer={}", log_level, log_level).into()),
+            tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| {
+                format!(
+                    "import={},importer={},storage={}",
+                    log_level, log_level, log_level
+                )
+                .into()
+            }),
         )
-        .with(tracing_subscriber::fmt::layer())
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
         .init();
 
     match cli.command {
-        Commands::LiftControl { source, output } => {
-            handle_liftcontrol_export(source, output).await?;
+        Commands::LiftControl {
+            source,
+            registry,
+            discover,
+            split_sessions,
+            client,
+            output,
+        } => {
+            let client = client.build()?;
+            handle_liftcontrol_export(source, registry, discover, split_sessions, client, output)
+                .await?;
+        }
+        Commands::Pdf {
+            file,
+            config,
+            pdftotext,
+            output,
+        } => {
+            handle_pdf_export(file, config, pdftotext, output).await?;
+        }
+        Commands::Html {
+            file,
+            adapter,
+            config,
+            url,
+            output,
+        } => {
+            handle_html_export(file, adapter, config, url, output).await?;
+        }
+        Commands::Manual { offline, output } => {
+            handle_manual_entry(offline, output, &cli.database_url).await?;
         }
         Commands::Canonical {
             file,
             validate_only,
+            dry_run,
+            format,
+            warnings_as_errors,
         } => {
-            handle_canonical_import(file, validate_only, &cli.database_url).await?;
+            if dry_run {
+                handle_canonical_dry_run(file, format, warnings_as_errors, &cli.database_url)
+                    .await?;
+            } else {
+                handle_canonical_import(
+                    file,
+                    validate_only,
+                    format,
+                    warnings_as_errors,
+                    &cli.database_url,
+                )
+                .await?;
+            }
         }
         Commands::BulkImport {
             directory,
             validate_only,
+            format,
+            warnings_as_errors,
+            force,
+            recursive,
+            latest_only,
+            jobs,
+            report,
+        } => {
+            let options = BulkOptions {
+                recursive,
+                latest_only,
+                jobs: jobs.into(),
+                validate_only,
+                warnings_as_errors,
+                force,
+            };
+            handle_bulk_import(directory, options, format, report, &cli.database_url).await?;
+        }
+        Commands::Watch {
+            directory,
+            validate_only,
+            warnings_as_errors,
+            force,
+            recursive,
+            interval,
+            debounce,
         } => {
-            handle_bulk_import(directory, validate_only, &cli.database_url).await?;
+            let options = BulkOptions {
+                recursive,
+                validate_only,
+                warnings_as_errors,
+                force,
+                ..Default::default()
+            };
+            handle_watch(
+                directory,
+                options,
+                Duration::from_secs(interval),
+                Duration::from_secs(debounce),
+                &cli.database_url,
+            )
+            .await?;
+        }
+        Commands::Dump { slug, output } => {
+            handle_dump(slug, output, &cli.database_url).await?;
+        }
+        Commands::Merge { files, output } => {
+            handle_merge(files, output).await?;
+        }
+        Commands::Schema { output } => {
+            handle_schema(output).await?;
+        }
+        Commands::Placings { slug } => {
+            handle_placings(slug, &cli.database_url).await?;
+        }
+        Commands::Export { output } => {
+            handle_export(output, &cli.database_url).await?;
         }
     }
 
@@ -97,23 +438,42 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
 async fn handle_liftcontrol_export(
     source: LiftControlSource,
+    registry_dir: PathBuf,
+    discover: bool,
+    split_sessions: bool,
+    client: LiftControlClient,
     output: PathBuf,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let registry = LiftControlRegistry::new();
+    let mut spec = if let Some(config_path) = source.config {
+        tracing::info!("Loading competition file: {}", config_path.display());
+        LiftControlSpec::from_config(&LiftControlConfig::from_file(&config_path)?)
+    } else {
+        tracing::info!(
+            "Loading LiftControl registry from: {}",
+            registry_dir.display()
+        );
+        let registry = LiftControlRegistry::from_dir(&registry_dir)?;
 
-    if source.list {
-        list_competitions(&registry);
-        return Ok(());
-    }
+        if source.list {
+            list_competitions(&registry);
+            return Ok(());
+        }
 
-    let comp_name = source
-        .competition
-        .expect("Competition name is required (enforced by clap)");
+        let comp_name = source
+            .competition
+            .expect("Competition name is required (enforced by clap)");
+
+        registry.get_spec(&comp_name).ok_or_else(|| {
+            format!(
+                "Unknown competition '{}'. Use --list to see available competitions.",
+                comp_name
+            )
+        })?
+    };
 
-    let comp_id = parse_competition_id(&comp_name, &registry)?;
-    let spec = registry
-        .get_spec(comp_id)
-        .ok_or_else(|| format!("Competition '{}' not found in registry", comp_id))?;
+    if discover {
+        spec = discover_sessions(&client, spec).await?;
+    }
 
     tracing::info!(
         "Exporting LiftControl competition: {} ({} sessions)",
@@ -121,7 +481,133 @@ async fn handle_liftcontrol_export(
         spec.sub_slugs().len()
     );
 
-    export_to_canonical(&spec, output).await?;
+    export_to_canonical(
+        &LiftControlImporter::new(client),
+        &spec,
+        split_sessions,
+        output,
+    )
+    .await?;
+
+    Ok(())
+}
+
+async fn handle_pdf_export(
+    file: PathBuf,
+    config: PathBuf,
+    pdftotext: PathBuf,
+    output: PathBuf,
+) -> Result<(), Box<dyn std::error::Error>> {
+    tracing::info!("Loading competition file: {}", config.display());
+    let spec = PdfSpec::new(file, SourceConfig::from_file(&config)?);
+
+    let importer = PdfImporter::new(PdfTextExtractor::new(pdftotext));
+    let canonical = importer.export(&spec).await?;
+
+    if let Some(pdf_metadata) = &canonical.pdf_metadata {
+        tracing::info!(
+            "Extraction confidence: {:.0}%",
+            pdf_metadata.extraction_confidence.unwrap_or_default() * 100.0
+        );
+        if let Some(warnings) = &pdf_metadata.warnings {
+            tracing::warn!(
+                "{} cell(s) or row(s) need review, see pdf_metadata.warnings",
+                warnings.len()
+            );
+        }
+    }
+
+    write_source_export(&canonical, &output, spec.slug(), "pdf").await
+}
+
+async fn handle_html_export(
+    file: PathBuf,
+    adapter_name: String,
+    config: PathBuf,
+    url: Option<String>,
+    output: PathBuf,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let adapter = adapter(&adapter_name).ok_or_else(|| {
+        let names = adapters()
+            .iter()
+            .map(|a| a.name())
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("Unknown adapter '{}', available: {}", adapter_name, names)
+    })?;
+
+    tracing::info!("Loading competition file: {}", config.display());
+    let spec = HtmlSpec::new(file, url, SourceConfig::from_file(&config)?);
+
+    let canonical = HtmlImporter::new(adapter).export(&spec).await?;
+
+    write_source_export(&canonical, &output, spec.slug(), "html").await
+}
+
+async fn handle_manual_entry(
+    offline: bool,
+    output: PathBuf,
+    database_url: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let known = if offline {
+        KnownEntities::default()
+    } else {
+        match load_known_entities(database_url).await {
+            Ok(known) => {
+                tracing::info!(
+                    "Suggesting {} known athletes and {} federations",
+                    known.athletes.len(),
+                    known.federations.len()
+                );
+                known
+            }
+            Err(e) => {
+                tracing::warn!("No suggestions, cannot read the database: {}", e);
+                KnownEntities::default()
+            }
+        }
+    };
+
+    let canonical = tokio::task::spawn_blocking(move || {
+        let prompt = Prompter::new(std::io::stdin().lock(), std::io::stdout());
+        ManualEntry::new(prompt, &known).run()
+    })
+    .await??;
+
+    let slug = canonical.competition.slug.clone();
+    write_source_export(&canonical, &output, &slug, "manual").await
+}
+
+async fn load_known_entities(
+    database_url: &str,
+) -> Result<KnownEntities, Box<dyn std::error::Error>> {
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .acquire_timeout(Duration::from_secs(5))
+        .connect(database_url)
+        .await?;
+    Ok(KnownEntities::load(&pool).await?)
+}
+
+/// Writes a document produced from a saved file or a manual entry as
+/// `<output>/<slug>/<timestamp>_<kind>.json`
+async fn write_source_export(
+    canonical: &CanonicalFormat,
+    output: &std::path::Path,
+    slug: &str,
+    kind: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let competition_dir = output.join(slug);
+    tokio::fs::create_dir_all(&competition_dir).await?;
+    let filename = format!(
+        "{}_{}.json",
+        chrono::Utc::now().format("%Y-%m-%dT%H-%M-%S"),
+        kind
+    );
+    write_canonical(canonical, &competition_dir.join(filename)).await?;
+
+    tracing::info!("Review and edit if needed, then import with:");
+    tracing::info!("   cargo run --bin import -- canonical <path-to-json>");
 
     Ok(())
 }
@@ -129,22 +615,33 @@ async fn handle_liftcontrol_export(
 async fn handle_canonical_import(
     file: PathBuf,
     validate_only: bool,
+    format: OutputFormat,
+    warnings_as_errors: bool,
     database_url: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
     tracing::info!("Loading canonical JSON from: {}", file.display());
 
-    let json_content = tokio::fs::read_to_string(&file).await?;
-    let canonical: CanonicalFormat = serde_json::from_str(&json_content)?;
+    tracing::info!("Validating canonical format...");
+    let prepared = read_canonical_file(&file).await?;
 
     tracing::info!(
         "Loaded competition: {} (v{})",
-        canonical.competition.name,
-        canonical.format_version
+        prepared.canonical.competition.name,
+        prepared.canonical.format_version
     );
 
-    tracing::info!("Validating canonical format...");
-    let validation_report = CanonicalValidator::validate(&canonical)?;
-    validation_report.log_warnings();
+    if validate_only && matches!(format, OutputFormat::Json) {
+        let file_report = FileReport {
+            file: file.display().to_string(),
+            report: &prepared.report,
+            error: None,
+        };
+        println!("{}", serde_json::to_string_pretty(&file_report)?);
+    } else {
+        prepared.report.log_warnings();
+    }
+
+    prepared.report.ensure_valid(warnings_as_errors)?;
     tracing::info!("✓ Validation successful!");
 
     if validate_only {
@@ -159,57 +656,124 @@ async fn handle_canonical_import(
 
     tracing::info!(
         "Importing {} categories to database...",
-        canonical.categories.len()
+        prepared.canonical.categories.len()
     );
-    let transformer = CanonicalTransformer::new(&pool);
-    transformer.import_to_database(canonical).await?;
+    prepared.import(&pool).await?;
 
     tracing::info!("✓ Import completed successfully!");
 
     Ok(())
 }
 
+async fn handle_canonical_dry_run(
+    file: PathBuf,
+    format: OutputFormat,
+    warnings_as_errors: bool,
+    database_url: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    tracing::info!("Loading canonical JSON from: {}", file.display());
+
+    let prepared = read_canonical_file(&file).await?;
+    prepared.report.log_warnings();
+    prepared.report.ensure_valid(warnings_as_errors)?;
+
+    tracing::info!("Connecting to database...");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(database_url)
+        .await?;
+
+    let diff = prepared.dry_run(&pool).await?;
+
+    match format {
+        OutputFormat::Text => print!("{}", diff),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&diff)?),
+    }
+
+    tracing::info!("✓ Dry run completed, no changes were written");
+
+    Ok(())
+}
+
 async fn handle_bulk_import(
     directory: PathBuf,
-    validate_only: bool,
+    options: BulkOptions,
+    format: OutputFormat,
+    report_path: Option<PathBuf>,
     database_url: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    let report_format = report_path
+        .as_deref()
+        .map(ReportFormat::from_path)
+        .transpose()?;
+
     tracing::info!(
         "Scanning directory for canonical JSON files: {}",
         directory.display()
     );
 
-    let mut json_files = Vec::new();
-    let mut entries = tokio::fs::read_dir(&directory).await?;
-
-    while let Some(entry) = entries.next_entry().await? {
-        let path = entry.path();
-        if path.is_dir() {
-            let mut sub_entries = tokio::fs::read_dir(&path).await?;
-            while let Some(sub_entry) = sub_entries.next_entry().await? {
-                let sub_path = sub_entry.path();
-                if sub_path.extension().is_some_and(|ext| ext == "json") {
-                    json_files.push(sub_path);
-                }
-            }
-        } else if path.extension().is_some_and(|ext| ext == "json") {
-            json_files.push(path);
-        }
-    }
+    let pool = if !options.validate_only {
+        tracing::info!("Connecting to database...");
+        Some(
+            PgPoolOptions::new()
+                .max_connections(options.jobs.max(4) as u32 + 1)
+                .connect(database_url)
+                .await?,
+        )
+    } else {
+        None
+    };
+
+    let report = BulkImporter::new(options, pool).run(&directory).await?;
 
-    if json_files.is_empty() {
+    if report.files.is_empty() {
         tracing::warn!("No JSON files found in {}", directory.display());
         return Ok(());
     }
 
-    json_files.sort();
-    tracing::info!("Found {} canonical JSON file(s)", json_files.len());
+    if matches!(format, OutputFormat::Json) {
+        println!("{}", serde_json::to_string_pretty(&report.files)?);
+    }
+
+    if let (Some(path), Some(report_format)) = (&report_path, report_format) {
+        let content = match report_format {
+            ReportFormat::Json => serde_json::to_string_pretty(&report)?,
+            ReportFormat::Markdown => report.to_markdown(),
+        };
+        tokio::fs::write(path, content).await?;
+        tracing::info!("Report written to: {}", path.display());
+    }
+
+    let summary = &report.summary;
+    tracing::info!(
+        "Summary: {} imported, {} validated, {} unchanged, {} superseded, {} failed, {} warning(s)",
+        summary.imported,
+        summary.validated,
+        summary.unchanged,
+        summary.superseded,
+        summary.failed,
+        summary.warnings
+    );
+
+    if summary.failed > 0 {
+        return Err(format!("{} file(s) failed to import", summary.failed).into());
+    }
+
+    Ok(())
+}
 
-    let pool = if !validate_only {
+async fn handle_watch(
+    directory: PathBuf,
+    options: BulkOptions,
+    interval: Duration,
+    debounce: Duration,
+    database_url: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let pool = if !options.validate_only {
         tracing::info!("Connecting to database...");
         Some(
             PgPoolOptions::new()
-                .max_connections(5)
+                .max_connections(4)
                 .connect(database_url)
                 .await?,
         )
@@ -217,122 +781,322 @@ async fn handle_bulk_import(
         None
     };
 
-    let mut success_count = 0;
-    let mut error_count = 0;
+    let mut watcher = DirectoryWatcher::new(&directory, options, pool, interval, debounce)?;
+    tokio::select! {
+        result = watcher.run() => result?,
+        _ = tokio::signal::ctrl_c() => tracing::info!("Stopped watching {}", directory.display()),
+    }
 
-    for (idx, file_path) in json_files.iter().enumerate() {
-        tracing::info!(
-            "[{}/{}] Processing: {}",
-            idx + 1,
-            json_files.len(),
-            file_path.display()
-        );
+    Ok(())
+}
 
-        match process_canonical_file(file_path, validate_only, pool.as_ref()).await {
-            Ok(_) => {
-                success_count += 1;
-                tracing::info!("  ✓ Success");
-            }
-            Err(e) => {
-                error_count += 1;
-                tracing::error!("  ✗ Error: {}", e);
-            }
+/// Format of the `bulk-import --report` file, from its extension
+#[derive(Clone, Copy)]
+enum ReportFormat {
+    Json,
+    Markdown,
+}
+
+impl ReportFormat {
+    fn from_path(path: &std::path::Path) -> Result<Self, String> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Ok(Self::Json),
+            Some("md") | Some("markdown") => Ok(Self::Markdown),
+            _ => Err(format!(
+                "{}: unsupported report extension, expected .json or .md",
+                path.display()
+            )),
         }
     }
+}
 
-    tracing::info!(
-        "Summary: {} succeeded, {} failed",
-        success_count,
-        error_count
-    );
+/// Validation outcome of one file, as printed by `--format json`
+#[derive(serde::Serialize)]
+struct FileReport<'a> {
+    file: String,
+    #[serde(flatten)]
+    report: &'a ValidationReport,
+    /// Why the file was rejected or failed to import
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+async fn read_canonical_file(
+    file_path: &std::path::Path,
+) -> Result<PreparedImport, Box<dyn std::error::Error>> {
+    let json_content = tokio::fs::read_to_string(file_path).await?;
+    Ok(PreparedImport::read(
+        &json_content,
+        Some(file_path.display().to_string()),
+    )?)
+}
+
+async fn handle_dump(
+    slug: String,
+    output: Option<PathBuf>,
+    database_url: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    tracing::info!("Connecting to database...");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(database_url)
+        .await?;
 
-    if error_count > 0 {
-        return Err(format!("{} file(s) failed to import", error_count).into());
+    let canonical = CanonicalDumper::new(&pool).dump_competition(&slug).await?;
+    let json = serde_json::to_string_pretty(&canonical)?;
+
+    match output {
+        Some(path) => {
+            tokio::fs::write(&path, json).await?;
+            tracing::info!("Dumped {} to: {}", slug, path.display());
+        }
+        None => println!("{}", json),
     }
 
     Ok(())
 }
 
-async fn process_canonical_file(
-    file_path: &PathBuf,
-    validate_only: bool,
-    pool: Option<&sqlx::PgPool>,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let json_content = tokio::fs::read_to_string(file_path).await?;
-    let canonical: CanonicalFormat = serde_json::from_str(&json_content)?;
+async fn handle_schema(output: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+    let json = serde_json::to_string_pretty(&models::json_schema())?;
+
+    match output {
+        Some(path) => {
+            tokio::fs::write(&path, json).await?;
+            tracing::info!(
+                "Schema of format v{} written to: {}",
+                models::CANONICAL_FORMAT_VERSION,
+                path.display()
+            );
+        }
+        None => println!("{}", json),
+    }
+
+    Ok(())
+}
 
-    let validation_report = CanonicalValidator::validate(&canonical)?;
+async fn handle_placings(
+    slug: Option<String>,
+    database_url: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    tracing::info!("Connecting to database...");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(database_url)
+        .await?;
 
-    if !validation_report.warnings.is_empty() {
-        for warning in &validation_report.warnings {
-            tracing::warn!("  {}", warning);
+    let repo = CompetitionRepository::new(&pool);
+    let placed = match slug {
+        Some(slug) => {
+            let competition = repo.find_by_slug(&slug).await?;
+            let count = repo.recompute_placings(&competition).await?;
+            vec![(slug, count)]
         }
+        None => repo.recompute_all_placings().await?,
+    };
+
+    for (slug, count) in &placed {
+        tracing::info!("  {}: {} participants placed", slug, count);
     }
+    tracing::info!("✓ Placings of {} competitions updated", placed.len());
 
-    if !validate_only && let Some(pool) = pool {
-        let transformer = CanonicalTransformer::new(pool);
-        transformer.import_to_database(canonical).await?;
+    Ok(())
+}
+
+async fn handle_export(
+    output: Option<PathBuf>,
+    database_url: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let output = output
+        .unwrap_or_else(|| PathBuf::from(export::bundle_filename(chrono::Utc::now().date_naive())));
+
+    tracing::info!("Connecting to database...");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(database_url)
+        .await?;
+
+    tracing::info!("Writing export bundle to: {}", output.display());
+    let file = tokio::fs::File::create(&output).await?;
+    let package = export::write_bundle(&pool, file).await?;
+
+    for resource in &package.resources {
+        tracing::info!(
+            "  {} ({} rows, {} bytes)",
+            resource.path,
+            resource.rows,
+            resource.bytes
+        );
     }
+    tracing::info!("✓ Export v{} completed successfully!", package.version);
 
     Ok(())
 }
 
 fn list_competitions(registry: &LiftControlRegistry) {
-    tracing::info!("Available predefined LiftControl competitions:");
+    tracing::info!("Available LiftControl competitions:");
     for comp_id in registry.list_competitions() {
         if let Some(config) = registry.get_config(comp_id) {
-            tracing::info!("  - {} ({} sessions)", comp_id, config.sub_slugs.len());
+            if config.aliases.is_empty() {
+                tracing::info!("  - {} ({} sessions)", comp_id, config.sub_slugs.len());
+            } else {
+                tracing::info!(
+                    "  - {} ({} sessions, aliases: {})",
+                    comp_id,
+                    config.sub_slugs.len(),
+                    config.aliases.join(", ")
+                );
+            }
         }
     }
 }
 
-fn parse_competition_id(
-    comp_name: &str,
-    _registry: &LiftControlRegistry,
-) -> Result<LiftControlCompetitionId, String> {
-    comp_name.parse::<LiftControlCompetitionId>().map_err(|_| {
-        format!(
-            "Unknown competition '{}'. Use --list to see available competitions.",
-            comp_name
-        )
-    })
+async fn discover_sessions(
+    client: &LiftControlClient,
+    spec: LiftControlSpec,
+) -> Result<LiftControlSpec, Box<dyn std::error::Error>> {
+    tracing::info!("Discovering sessions of event: {}", spec.base_slug());
+    let sessions = client.discover_sessions(spec.base_slug()).await?;
+
+    if sessions.is_empty() {
+        return Err(format!("No session found for event '{}'", spec.base_slug()).into());
+    }
+
+    for session in &sessions {
+        tracing::info!(
+            "  - {} [{}] {} ({} athletes)",
+            session.sub_slug,
+            session.status,
+            session.name,
+            session.athlete_count
+        );
+        if session.status == SessionStatus::Open {
+            tracing::warn!(
+                "Session {} is still open, results may change",
+                session.sub_slug
+            );
+        }
+    }
+
+    for sub_slug in spec.sub_slugs() {
+        if !sessions.iter().any(|s| &s.sub_slug == sub_slug) {
+            tracing::warn!(
+                "Configured session {} was not found on the event page",
+                sub_slug
+            );
+        }
+    }
+    for session in &sessions {
+        if !spec.sub_slugs().contains(&session.sub_slug) {
+            tracing::warn!(
+                "Session {} is missing from the competition file",
+                session.sub_slug
+            );
+        }
+    }
+
+    let sub_slugs = sessions
+        .into_iter()
+        .filter(|s| s.status != SessionStatus::Empty)
+        .map(|s| s.sub_slug)
+        .collect();
+
+    Ok(spec.with_sub_slugs(sub_slugs))
 }
 
 async fn export_to_canonical(
-    spec: &importer::LiftControlSpec,
+    importer: &LiftControlImporter,
+    spec: &LiftControlSpec,
+    split_sessions: bool,
     output_dir: PathBuf,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let client = LiftControlClient::new();
+    let competition_dir = output_dir.join(spec.base_slug());
+    tokio::fs::create_dir_all(&competition_dir).await?;
+    let timestamp = chrono::Utc::now().format("%Y-%m-%dT%H-%M-%S");
 
+    let mut sessions = Vec::new();
     for sub_slug in spec.sub_slugs() {
         let sub_slug = sub_slug.trim();
         if sub_slug.is_empty() {
             continue;
         }
 
-        tracing::info!("Fetching data for sub-slug: {}", sub_slug);
-        let api_response = client.fetch_live_general_table(sub_slug).await?;
+        let canonical = importer.export_session(spec, sub_slug).await?;
 
-        tracing::info!("Competition status: {}", api_response.contest.status);
+        if split_sessions {
+            let filename = format!("{}_{}_liftcontrol.json", timestamp, sub_slug);
+            write_canonical(&canonical, &competition_dir.join(filename)).await?;
+        } else {
+            sessions.push((sub_slug.to_string(), canonical));
+        }
+    }
 
-        let exporter =
-            LiftControlExporter::new(spec.base_slug().to_string(), spec.metadata().clone());
-        let canonical = exporter.to_canonical(api_response)?;
+    if !sessions.is_empty() {
+        tracing::info!("Merging {} sessions", sessions.len());
+        let merged = CanonicalMerger::merge(sessions)?;
+        log_merge_conflicts(&merged.conflicts);
 
-        let competition_dir = output_dir.join(spec.base_slug());
-        tokio::fs::create_dir_all(&competition_dir).await?;
+        let filename = format!("{}_liftcontrol.json", timestamp);
+        write_canonical(&merged.canonical, &competition_dir.join(filename)).await?;
+    }
 
-        let timestamp = chrono::Utc::now().format("%Y-%m-%dT%H-%M-%S");
-        let filename = format!("{}_{}_liftcontrol.json", timestamp, sub_slug);
-        let filepath = competition_dir.join(&filename);
+    tracing::info!("Review and edit if needed, then import with:");
+    tracing::info!("   cargo run --bin import -- canonical <path-to-json>");
 
-        let json = serde_json::to_string_pretty(&canonical)?;
-        tokio::fs::write(&filepath, json).await?;
+    Ok(())
+}
+
+async fn write_canonical(
+    canonical: &CanonicalFormat,
+    path: &std::path::Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let json = serde_json::to_string_pretty(canonical)?;
+    tokio::fs::write(path, json).await?;
+    tracing::info!("Exported to: {}", path.display());
+    Ok(())
+}
 
-        tracing::info!("Exported to: {}", filepath.display());
+async fn handle_merge(
+    files: Vec<PathBuf>,
+    output: Option<PathBuf>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut sessions = Vec::new();
+    for file in &files {
+        tracing::info!("Loading session file: {}", file.display());
+        let canonical = upgrade::parse(&tokio::fs::read_to_string(file).await?)?;
+
+        let label = session_label(&canonical).unwrap_or_else(|| {
+            file.file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default()
+        });
+        sessions.push((label, canonical));
+    }
+
+    let merged = CanonicalMerger::merge(sessions)?;
+    log_merge_conflicts(&merged.conflicts);
+
+    let report = CanonicalValidator::validate(&merged.canonical)?;
+    report.log_warnings();
+
+    match output {
+        Some(path) => write_canonical(&merged.canonical, &path).await?,
+        None => println!("{}", serde_json::to_string_pretty(&merged.canonical)?),
     }
-    tracing::info!("Review and edit if needed, then import with:");
-    tracing::info!("   cargo run --bin import -- canonical <path-to-json>");
 
     Ok(())
 }
+
+fn log_merge_conflicts(conflicts: &[MergeConflict]) {
+    if conflicts.is_empty() {
+        tracing::info!("✓ Merged without conflicts");
+        return;
+    }
+
+    tracing::warn!(
+        "{} conflicts, the first session's values were kept:",
+        conflicts.len()
+    );
+    for conflict in conflicts {
+        tracing::warn!("  {}", conflict);
+    }
+}
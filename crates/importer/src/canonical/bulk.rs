@@ -0,0 +1,541 @@
+//! Importing a directory of canonical files: discovery, choice of the snapshots to
+//! load, a bounded number of competitions at a time and the report of every file
+
+use super::pipeline::PreparedImport;
+use super::validator::ValidationReport;
+use crate::{ImporterError, Result};
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use sqlx::PgPool;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use storage::repository::import_batch::ImportBatchRepository;
+use tokio::task::JoinSet;
+
+/// Attempts of a file failing on a row another import inserted at the same time
+const CONCURRENT_IMPORT_ATTEMPTS: usize = 3;
+
+#[derive(Debug, Clone, Copy)]
+pub struct BulkOptions {
+    /// Walk every level below the directory instead of the directory and its children
+    pub recursive: bool,
+    /// Import only the latest snapshot of each series, see [`latest_snapshots`]
+    pub latest_only: bool,
+    /// Competitions imported at the same time; the files of one competition are
+    /// always imported one after the other
+    pub jobs: usize,
+    pub validate_only: bool,
+    pub warnings_as_errors: bool,
+    /// Re-import files whose content was already imported
+    pub force: bool,
+}
+
+impl Default for BulkOptions {
+    fn default() -> Self {
+        Self {
+            recursive: false,
+            latest_only: false,
+            jobs: 1,
+            validate_only: false,
+            warnings_as_errors: false,
+            force: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileStatus {
+    Imported,
+    /// Valid, not imported because of `validate_only`
+    Validated,
+    /// Its content was already imported
+    Unchanged,
+    /// Left out by `latest_only` for a later snapshot
+    Superseded,
+    Failed,
+}
+
+impl FileStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FileStatus::Imported => "imported",
+            FileStatus::Validated => "validated",
+            FileStatus::Unchanged => "unchanged",
+            FileStatus::Superseded => "superseded",
+            FileStatus::Failed => "failed",
+        }
+    }
+}
+
+/// What happened to one file
+#[derive(Debug, Serialize)]
+pub struct FileOutcome {
+    pub file: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub competition: Option<String>,
+    pub status: FileStatus,
+    #[serde(flatten)]
+    pub report: ValidationReport,
+    /// Why the file was rejected or failed to import
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl FileOutcome {
+    fn new(file: &Path, status: FileStatus) -> Self {
+        Self {
+            file: file.display().to_string(),
+            competition: None,
+            status,
+            report: ValidationReport::default(),
+            error: None,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct BulkSummary {
+    pub imported: usize,
+    pub validated: usize,
+    pub unchanged: usize,
+    pub superseded: usize,
+    pub failed: usize,
+    pub warnings: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkReport {
+    pub directory: String,
+    pub summary: BulkSummary,
+    /// Sorted by file path
+    pub files: Vec<FileOutcome>,
+}
+
+impl BulkReport {
+    pub fn new(directory: &Path, mut files: Vec<FileOutcome>) -> Self {
+        files.sort_by(|a, b| a.file.cmp(&b.file));
+
+        let mut summary = BulkSummary::default();
+        for outcome in &files {
+            match outcome.status {
+                FileStatus::Imported => summary.imported += 1,
+                FileStatus::Validated => summary.validated += 1,
+                FileStatus::Unchanged => summary.unchanged += 1,
+                FileStatus::Superseded => summary.superseded += 1,
+                FileStatus::Failed => summary.failed += 1,
+            }
+            summary.warnings += outcome.report.warnings().count();
+        }
+
+        Self {
+            directory: directory.display().to_string(),
+            summary,
+            files,
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::new();
+        self.write_markdown(&mut markdown)
+            .expect("writing to a String cannot fail");
+        markdown
+    }
+
+    fn write_markdown(&self, out: &mut impl fmt::Write) -> fmt::Result {
+        let summary = &self.summary;
+
+        writeln!(out, "# Bulk import of `{}`\n", self.directory)?;
+        writeln!(
+            out,
+            "| Imported | Validated | Unchanged | Superseded | Failed | Warnings |"
+        )?;
+        writeln!(out, "|---|---|---|---|---|---|")?;
+        writeln!(
+            out,
+            "| {} | {} | {} | {} | {} | {} |\n",
+            summary.imported,
+            summary.validated,
+            summary.unchanged,
+            summary.superseded,
+            summary.failed,
+            summary.warnings
+        )?;
+
+        writeln!(out, "| File | Competition | Status | Warnings |")?;
+        writeln!(out, "|---|---|---|---|")?;
+        for outcome in &self.files {
+            writeln!(
+                out,
+                "| `{}` | {} | {} | {} |",
+                outcome.file,
+                outcome.competition.as_deref().unwrap_or("-"),
+                outcome.status.as_str(),
+                outcome.report.warnings().count()
+            )?;
+        }
+
+        let failed = self
+            .files
+            .iter()
+            .filter(|outcome| outcome.status == FileStatus::Failed)
+            .collect::<Vec<_>>();
+        if !failed.is_empty() {
+            writeln!(out, "\n## Failures\n")?;
+            for outcome in failed {
+                writeln!(
+                    out,
+                    "- `{}`: {}",
+                    outcome.file,
+                    outcome.error.as_deref().unwrap_or("unknown error")
+                )?;
+            }
+        }
+
+        let warned = self
+            .files
+            .iter()
+            .filter(|outcome| outcome.report.warnings().next().is_some())
+            .collect::<Vec<_>>();
+        if !warned.is_empty() {
+            writeln!(out, "\n## Warnings")?;
+            for outcome in warned {
+                writeln!(out, "\n### `{}`\n", outcome.file)?;
+                for warning in outcome.report.warnings() {
+                    writeln!(out, "- {}", warning)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Finds the `*.json` files of the directory and of its direct subdirectories, or of
+/// every level with `recursive`. Hidden entries are skipped.
+pub fn discover(directory: &Path, recursive: bool) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut pending = vec![(directory.to_path_buf(), 0usize)];
+
+    while let Some((dir, depth)) = pending.pop() {
+        let entries = std::fs::read_dir(&dir).map_err(|e| read_error(&dir, e))?;
+        for entry in entries {
+            let path = entry.map_err(|e| read_error(&dir, e))?.path();
+            if path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+            {
+                continue;
+            }
+
+            if path.is_dir() {
+                if recursive || depth == 0 {
+                    pending.push((path, depth + 1));
+                }
+            } else if path.extension().is_some_and(|ext| ext == "json") {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+fn read_error(path: &Path, e: std::io::Error) -> ImporterError {
+    ImporterError::ImportError(format!("Cannot read {}: {}", path.display(), e))
+}
+
+/// Splits the files into the latest snapshot of each series and the older snapshots.
+///
+/// Files are written as `<competition>/<timestamp>_<name>.json`: the snapshots of a
+/// series share their directory and `<name>`, e.g. every `*_liftcontrol.json` export
+/// of a competition, or every export of one of its sessions. Files without a
+/// timestamp are a series of their own.
+pub fn latest_snapshots(files: Vec<PathBuf>) -> (Vec<PathBuf>, Vec<PathBuf>) {
+    let mut series: BTreeMap<(PathBuf, String), Vec<PathBuf>> = BTreeMap::new();
+    for file in files {
+        let key = (
+            file.parent().map(Path::to_path_buf).unwrap_or_default(),
+            series_name(&file),
+        );
+        series.entry(key).or_default().push(file);
+    }
+
+    let mut latest = Vec::new();
+    let mut superseded = Vec::new();
+    for mut snapshots in series.into_values() {
+        // The timestamp prefix sorts chronologically
+        snapshots.sort();
+        if let Some(last) = snapshots.pop() {
+            latest.push(last);
+        }
+        superseded.extend(snapshots);
+    }
+
+    latest.sort();
+    superseded.sort();
+    (latest, superseded)
+}
+
+fn series_name(file: &Path) -> String {
+    let name = file
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    match name.split_once('_') {
+        Some((timestamp, rest))
+            if NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%dT%H-%M-%S").is_ok() =>
+        {
+            rest.to_string()
+        }
+        _ => name,
+    }
+}
+
+/// Imports the files found in a directory, each in its own transaction
+pub struct BulkImporter {
+    options: BulkOptions,
+    pool: Option<PgPool>,
+}
+
+impl BulkImporter {
+    /// Without a pool, files are only validated
+    pub fn new(options: BulkOptions, pool: Option<PgPool>) -> Self {
+        Self { options, pool }
+    }
+
+    pub async fn run(&self, directory: &Path) -> Result<BulkReport> {
+        let files = discover(directory, self.options.recursive)?;
+        let (files, superseded) = if self.options.latest_only {
+            latest_snapshots(files)
+        } else {
+            (files, Vec::new())
+        };
+        tracing::info!(
+            "Found {} canonical JSON file(s) to process, {} older snapshot(s) left out",
+            files.len(),
+            superseded.len()
+        );
+
+        let mut outcomes = superseded
+            .iter()
+            .map(|file| FileOutcome::new(file, FileStatus::Superseded))
+            .collect::<Vec<_>>();
+        outcomes.extend(self.run_files(files).await?);
+
+        Ok(BulkReport::new(directory, outcomes))
+    }
+
+    /// Processes the files of each directory in order, with up to `jobs` directories
+    /// (competitions) at a time
+    pub async fn run_files(&self, files: Vec<PathBuf>) -> Result<Vec<FileOutcome>> {
+        let mut competitions: BTreeMap<PathBuf, Vec<PathBuf>> = BTreeMap::new();
+        for file in files {
+            let dir = file.parent().map(Path::to_path_buf).unwrap_or_default();
+            competitions.entry(dir).or_default().push(file);
+        }
+
+        let mut outcomes = Vec::new();
+        let mut running = JoinSet::new();
+        for files in competitions.into_values() {
+            if running.len() >= self.options.jobs.max(1)
+                && let Some(done) = running.join_next().await
+            {
+                outcomes.extend(done.map_err(join_error)?);
+            }
+
+            let options = self.options;
+            let pool = self.pool.clone();
+            running.spawn(async move {
+                let mut outcomes = Vec::new();
+                for file in files {
+                    outcomes.push(process_file(&file, options, pool.as_ref()).await);
+                }
+                outcomes
+            });
+        }
+        while let Some(done) = running.join_next().await {
+            outcomes.extend(done.map_err(join_error)?);
+        }
+
+        Ok(outcomes)
+    }
+}
+
+fn join_error(e: tokio::task::JoinError) -> ImporterError {
+    ImporterError::ImportError(format!("Import task failed: {}", e))
+}
+
+async fn process_file(file: &Path, options: BulkOptions, pool: Option<&PgPool>) -> FileOutcome {
+    let mut outcome = FileOutcome::new(file, FileStatus::Failed);
+
+    let prepared = match tokio::fs::read_to_string(file).await {
+        Ok(content) => PreparedImport::read(&content, Some(file.display().to_string())),
+        Err(e) => Err(read_error(file, e)),
+    };
+    let mut prepared = match prepared {
+        Ok(prepared) => prepared,
+        Err(e) => {
+            tracing::error!("✗ {}: {}", file.display(), e);
+            outcome.error = Some(e.to_string());
+            return outcome;
+        }
+    };
+    outcome.competition = Some(prepared.canonical.competition.slug.clone());
+    for warning in prepared.report.warnings() {
+        tracing::warn!("  {}: {}", file.display(), warning);
+    }
+
+    let valid = prepared.report.ensure_valid(options.warnings_as_errors);
+    outcome.report = std::mem::take(&mut prepared.report);
+    if let Err(e) = valid {
+        tracing::error!("✗ {}: {}", file.display(), e);
+        outcome.error = Some(e.to_string());
+        return outcome;
+    }
+
+    let result = match pool {
+        Some(pool) if !options.validate_only => import(&prepared, options.force, pool).await,
+        _ => Ok(FileStatus::Validated),
+    };
+    match result {
+        Ok(status) => {
+            match status {
+                FileStatus::Unchanged => {
+                    tracing::info!("- {}: already imported, skipped", file.display())
+                }
+                _ => tracing::info!("✓ {}: {}", file.display(), status.as_str()),
+            }
+            outcome.status = status;
+        }
+        Err(e) => {
+            tracing::error!("✗ {}: {}", file.display(), e);
+            outcome.error = Some(e.to_string());
+        }
+    }
+
+    outcome
+}
+
+async fn import(prepared: &PreparedImport, force: bool, pool: &PgPool) -> Result<FileStatus> {
+    if !force
+        && ImportBatchRepository::new(pool)
+            .exists_with_hash(&prepared.origin.content_hash)
+            .await?
+    {
+        return Ok(FileStatus::Unchanged);
+    }
+
+    // Two competitions imported at the same time may both insert a new athlete,
+    // category or federation: the second transaction fails on the unique index and
+    // succeeds once retried, now finding the row.
+    let mut attempt = 1;
+    loop {
+        match prepared.import(pool).await {
+            Ok(()) => return Ok(FileStatus::Imported),
+            Err(ImporterError::StorageError(e))
+                if e.is_unique_violation() && attempt < CONCURRENT_IMPORT_ATTEMPTS =>
+            {
+                tracing::debug!(
+                    "Retrying {} after: {}",
+                    prepared.canonical.competition.slug,
+                    e
+                );
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Copies of the snapshots committed for Annecy 4 Lift 2025, two of the same session
+    fn bulk_fixtures() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures/bulk")
+    }
+
+    fn paths(names: &[&str]) -> Vec<PathBuf> {
+        names.iter().map(PathBuf::from).collect()
+    }
+
+    #[test]
+    fn test_latest_snapshots() {
+        let (latest, superseded) = latest_snapshots(paths(&[
+            "imports/annecy/2025-12-07T14-05-30_annecy-matin-39_liftcontrol.json",
+            "imports/annecy/2025-12-07T15-18-10_annecy-matin-39_liftcontrol.json",
+            "imports/annecy/2025-12-07T15-18-11_annecy-apres-midi-40_liftcontrol.json",
+            "imports/paris/2025-01-10T10-00-00_pdf.json",
+            "imports/paris/2025-02-01T09-30-00_pdf.json",
+            "imports/paris/results.json",
+        ]));
+
+        assert_eq!(
+            latest,
+            paths(&[
+                "imports/annecy/2025-12-07T15-18-10_annecy-matin-39_liftcontrol.json",
+                "imports/annecy/2025-12-07T15-18-11_annecy-apres-midi-40_liftcontrol.json",
+                "imports/paris/2025-02-01T09-30-00_pdf.json",
+                "imports/paris/results.json",
+            ])
+        );
+        assert_eq!(
+            superseded,
+            paths(&[
+                "imports/annecy/2025-12-07T14-05-30_annecy-matin-39_liftcontrol.json",
+                "imports/paris/2025-01-10T10-00-00_pdf.json",
+            ])
+        );
+    }
+
+    #[test]
+    fn test_discover() {
+        let files = discover(&bulk_fixtures(), false).unwrap();
+        assert_eq!(files.len(), 3);
+        assert!(
+            files
+                .iter()
+                .all(|f| f.parent().unwrap().ends_with("annecy-4-lift-2025"))
+        );
+
+        let fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures");
+        assert!(
+            discover(&fixtures, false).unwrap().len() < discover(&fixtures, true).unwrap().len()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validate_only_run() {
+        let options = BulkOptions {
+            latest_only: true,
+            jobs: 4,
+            validate_only: true,
+            ..Default::default()
+        };
+
+        let report = BulkImporter::new(options, None)
+            .run(&bulk_fixtures())
+            .await
+            .unwrap();
+
+        assert_eq!(report.summary.validated, 2);
+        assert_eq!(report.summary.superseded, 1);
+        assert_eq!(report.summary.failed, 0);
+        assert_eq!(report.files.len(), 3);
+        assert!(
+            report
+                .files
+                .iter()
+                .all(|f| f.competition.is_some() || f.status == FileStatus::Superseded)
+        );
+
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("| 0 | 2 | 0 | 1 | 0 |"));
+        assert!(markdown.contains("| annecy-4-lift-2025 | validated |"));
+    }
+}
@@ -0,0 +1,436 @@
+use super::models::*;
+use crate::Result;
+use chrono::{NaiveDate, Utc};
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use storage::error::StorageError;
+use uuid::Uuid;
+
+/// Rebuilds the canonical format of a stored competition, so that data edited
+/// through the API can be reviewed and committed back as JSON. Re-importing the
+/// output through `CanonicalLoader` leaves the database unchanged.
+pub struct CanonicalDumper<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> CanonicalDumper<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn dump_competition(&self, slug: &str) -> Result<CanonicalFormat> {
+        let competition = sqlx::query_as!(
+            CompetitionRow,
+            r#"
+            SELECT
+                c.competition_id,
+                c.name,
+                c.slug,
+                c.status,
+                c.venue,
+                c.city,
+                c.country,
+                c.start_date,
+                c.end_date,
+                c.number_of_judge,
+                f.name as federation_name,
+                f.abbreviation as federation_abbreviation,
+                f.country as federation_country
+            FROM competitions c
+            JOIN federations f ON f.federation_id = c.federation_id
+            WHERE c.slug = $1
+            "#,
+            slug
+        )
+        .fetch_optional(self.pool)
+        .await?
+        .ok_or(StorageError::NotFound)?;
+
+        let movements = sqlx::query_as!(
+            MovementRow,
+            r#"
+            SELECT movement_name, is_required, display_order
+            FROM competition_movements
+            WHERE competition_id = $1
+            ORDER BY display_order NULLS LAST, movement_name
+            "#,
+            competition.competition_id
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        let participants = sqlx::query_as!(
+            ParticipantRow,
+            r#"
+            SELECT
+                cp.participant_id,
+                cat.category_id,
+                cat.name as category_name,
+                cat.gender as category_gender,
+                cat.weight_class_min,
+                cat.weight_class_max,
+                a.first_name,
+                a.last_name,
+                a.gender,
+                a.country,
+                a.nationality,
+                a.birth_year,
+                cp.bodyweight,
+                cp.lot_number,
+                cp.is_disqualified,
+                cp.disqualified_reason
+            FROM competition_participants cp
+            JOIN categories cat ON cat.category_id = cp.category_id
+            JOIN athletes a ON a.athlete_id = cp.athlete_id
+            WHERE cp.competition_id = $1
+            ORDER BY cat.gender, cat.weight_class_max NULLS LAST, cat.weight_class_min, cat.name,
+                     a.last_name, a.first_name
+            "#,
+            competition.competition_id
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        let attempts = sqlx::query_as!(
+            AttemptRow,
+            r#"
+            SELECT
+                l.participant_id,
+                l.movement_name,
+                l.equipment_setting,
+                at.attempt_number as "attempt_number?",
+                at.weight as "weight?: Decimal",
+                at.is_successful as "is_successful?",
+                at.no_rep_reason,
+                at.kind as "kind?"
+            FROM lifts l
+            JOIN competition_participants cp ON cp.participant_id = l.participant_id
+            LEFT JOIN competition_movements cm
+                ON cm.competition_id = cp.competition_id AND cm.movement_name = l.movement_name
+            LEFT JOIN attempts at ON at.lift_id = l.lift_id
+            WHERE cp.competition_id = $1
+            ORDER BY l.participant_id, cm.display_order NULLS LAST, l.movement_name, at.attempt_number
+            "#,
+            competition.competition_id
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        build_canonical(competition, movements, participants, &attempts)
+    }
+}
+
+struct CompetitionRow {
+    competition_id: Uuid,
+    name: String,
+    slug: String,
+    status: String,
+    venue: Option<String>,
+    city: Option<String>,
+    country: Option<String>,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    number_of_judge: Option<i16>,
+    federation_name: String,
+    federation_abbreviation: Option<String>,
+    federation_country: Option<String>,
+}
+
+struct MovementRow {
+    movement_name: String,
+    is_required: bool,
+    display_order: Option<i32>,
+}
+
+struct ParticipantRow {
+    participant_id: Uuid,
+    category_id: Uuid,
+    category_name: String,
+    category_gender: String,
+    weight_class_min: Option<Decimal>,
+    weight_class_max: Option<Decimal>,
+    first_name: String,
+    last_name: String,
+    gender: String,
+    country: String,
+    nationality: Option<String>,
+    birth_year: Option<i16>,
+    bodyweight: Option<Decimal>,
+    lot_number: Option<i32>,
+    is_disqualified: bool,
+    disqualified_reason: Option<String>,
+}
+
+/// One attempt of a lift, the attempt columns are empty for a lift without attempts
+struct AttemptRow {
+    participant_id: Uuid,
+    movement_name: String,
+    equipment_setting: Option<String>,
+    attempt_number: Option<i16>,
+    weight: Option<Decimal>,
+    is_successful: Option<bool>,
+    no_rep_reason: Option<String>,
+    kind: Option<String>,
+}
+
+/// Builds the document from the stored rows, participants sorted by category and
+/// attempts by participant. Competitions created through the API may have no
+/// country, which the canonical format requires: the dump fails for them.
+fn build_canonical(
+    competition: CompetitionRow,
+    movements: Vec<MovementRow>,
+    participants: Vec<ParticipantRow>,
+    attempts: &[AttemptRow],
+) -> Result<CanonicalFormat> {
+    let country = competition.country.ok_or_else(|| {
+        StorageError::ConstraintViolation(format!(
+            "Competition '{}' has no country, set one before dumping it to the canonical format",
+            competition.slug
+        ))
+    })?;
+
+    let movements = movements
+        .into_iter()
+        .enumerate()
+        .map(|(idx, m)| MovementData {
+            name: m.movement_name,
+            order: m.display_order.unwrap_or(idx as i32 + 1) as i16,
+            is_required: Some(m.is_required),
+        })
+        .collect::<Vec<_>>();
+
+    Ok(CanonicalFormat {
+        format_version: CANONICAL_FORMAT_VERSION.to_string(),
+        source: SourceMetadata {
+            r#type: SourceType::Database,
+            url: None,
+            extracted_at: Utc::now(),
+            extractor: "osl-database-dump".to_string(),
+            original_filename: None,
+        },
+        competition: CompetitionData {
+            name: competition.name,
+            slug: competition.slug,
+            federation: FederationData {
+                name: competition.federation_name,
+                slug: None,
+                abbreviation: competition.federation_abbreviation,
+                country: competition.federation_country,
+            },
+            start_date: competition.start_date,
+            end_date: competition.end_date,
+            venue: competition.venue,
+            city: competition.city,
+            country,
+            number_of_judges: competition.number_of_judge,
+            status: Some(competition.status),
+        },
+        movements,
+        categories: build_categories(participants, attempts),
+        liftcontrol_metadata: None,
+        pdf_metadata: None,
+    })
+}
+
+fn build_categories(
+    participants: Vec<ParticipantRow>,
+    attempts: &[AttemptRow],
+) -> Vec<CategoryData> {
+    let mut categories: Vec<(Uuid, CategoryData)> = Vec::new();
+
+    for participant in participants {
+        if categories.last().map(|(id, _)| *id) != Some(participant.category_id) {
+            categories.push((
+                participant.category_id,
+                CategoryData {
+                    name: participant.category_name.clone(),
+                    gender: participant.category_gender.clone(),
+                    weight_class_min: participant.weight_class_min,
+                    weight_class_max: participant.weight_class_max,
+                    athletes: Vec::new(),
+                },
+            ));
+        }
+
+        let mut lifts: Vec<LiftData> = Vec::new();
+        for row in attempts
+            .iter()
+            .filter(|a| a.participant_id == participant.participant_id)
+        {
+            if lifts.last().map(|l| &l.movement) != Some(&row.movement_name) {
+                lifts.push(LiftData {
+                    movement: row.movement_name.clone(),
+                    attempts: Vec::new(),
+                    equipment_setting: row.equipment_setting.clone(),
+                });
+            }
+
+            if let (Some(lift), Some(attempt_number), Some(weight), Some(is_successful)) = (
+                lifts.last_mut(),
+                row.attempt_number,
+                row.weight,
+                row.is_successful,
+            ) {
+                lift.attempts.push(AttemptData {
+                    attempt_number,
+                    weight,
+                    is_successful,
+                    no_rep_reason: row.no_rep_reason.clone(),
+                    kind: row
+                        .kind
+                        .as_deref()
+                        .map(AttemptKind::from_db)
+                        .unwrap_or_default(),
+                });
+            }
+        }
+
+        let gender =
+            (participant.gender != participant.category_gender).then_some(participant.gender);
+
+        if let Some((_, category)) = categories.last_mut() {
+            category.athletes.push(AthleteData {
+                first_name: participant.first_name,
+                last_name: participant.last_name,
+                gender,
+                country: participant.country,
+                nationality: participant.nationality,
+                // Birth dates are not published, the year keeps the date stored
+                birth_date: None,
+                birth_year: participant.birth_year,
+                bodyweight: participant.bodyweight,
+                lot_number: participant.lot_number,
+                is_disqualified: Some(participant.is_disqualified),
+                disqualified_reason: participant.disqualified_reason,
+                lifts,
+                liftcontrol_athlete_metadata: None,
+                source_session: None,
+            });
+        }
+    }
+
+    categories.into_iter().map(|(_, c)| c).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canonical::validator::CanonicalValidator;
+
+    fn competition(country: Option<&str>) -> CompetitionRow {
+        CompetitionRow {
+            competition_id: Uuid::new_v4(),
+            name: "Annecy 4 Lift 2025".to_string(),
+            slug: "annecy-4-lift-2025".to_string(),
+            status: "completed".to_string(),
+            venue: None,
+            city: Some("Annecy".to_string()),
+            country: country.map(str::to_string),
+            start_date: NaiveDate::from_ymd_opt(2025, 12, 7).unwrap(),
+            end_date: NaiveDate::from_ymd_opt(2025, 12, 7).unwrap(),
+            number_of_judge: Some(3),
+            federation_name: "4 Lift".to_string(),
+            federation_abbreviation: Some("4L".to_string()),
+            federation_country: Some("France".to_string()),
+        }
+    }
+
+    fn movements() -> Vec<MovementRow> {
+        ["Muscle-up", "Squat"]
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| MovementRow {
+                movement_name: name.to_string(),
+                is_required: true,
+                display_order: Some(idx as i32 + 1),
+            })
+            .collect()
+    }
+
+    fn participant(category_id: Uuid, first_name: &str, gender: &str) -> ParticipantRow {
+        ParticipantRow {
+            participant_id: Uuid::new_v4(),
+            category_id,
+            category_name: "-80".to_string(),
+            category_gender: "M".to_string(),
+            weight_class_min: None,
+            weight_class_max: Some(Decimal::from(80)),
+            first_name: first_name.to_string(),
+            last_name: "Meunier".to_string(),
+            gender: gender.to_string(),
+            country: "France".to_string(),
+            nationality: None,
+            birth_year: Some(1995),
+            bodyweight: Some(Decimal::new(746, 1)),
+            lot_number: Some(3),
+            is_disqualified: false,
+            disqualified_reason: None,
+        }
+    }
+
+    fn attempts(participant_id: Uuid) -> Vec<AttemptRow> {
+        let attempt = |movement: &str, number: i16, weight, is_successful, kind: &str| AttemptRow {
+            participant_id,
+            movement_name: movement.to_string(),
+            equipment_setting: None,
+            attempt_number: Some(number),
+            weight: Some(weight),
+            is_successful: Some(is_successful),
+            no_rep_reason: (!is_successful).then(|| "Profondeur".to_string()),
+            kind: Some(kind.to_string()),
+        };
+
+        vec![
+            attempt("Muscle-up", 1, Decimal::from(10), true, "regular"),
+            attempt("Muscle-up", 2, Decimal::from(15), true, "regular"),
+            attempt("Muscle-up", 3, Decimal::from(20), false, "regular"),
+            attempt("Squat", 1, Decimal::from(140), true, "regular"),
+            attempt("Squat", 2, Decimal::from(150), true, "regular"),
+            attempt("Squat", 3, Decimal::from(155), true, "regular"),
+            attempt("Squat", 4, Decimal::from(160), false, "record"),
+        ]
+    }
+
+    #[test]
+    fn test_dump_is_valid_canonical() {
+        let category_id = Uuid::new_v4();
+        let hugo = participant(category_id, "Hugo", "M");
+        let camille = participant(category_id, "Camille", "F");
+        let mut rows = attempts(hugo.participant_id);
+        rows.extend(attempts(camille.participant_id));
+
+        let canonical = build_canonical(
+            competition(Some("France")),
+            movements(),
+            vec![hugo, camille],
+            &rows,
+        )
+        .unwrap();
+
+        CanonicalValidator::validate(&canonical).unwrap();
+        assert_eq!(canonical.competition.country, "France");
+        assert_eq!(canonical.categories.len(), 1);
+
+        let athletes = &canonical.categories[0].athletes;
+        assert_eq!(athletes[0].gender, None);
+        assert_eq!(athletes[1].gender.as_deref(), Some("F"));
+        assert_eq!(athletes[0].lifts.len(), 2);
+        assert_eq!(athletes[0].lifts[1].attempts[3].kind, AttemptKind::Record);
+
+        // The document read back is the same
+        let json = serde_json::to_string(&canonical).unwrap();
+        let read: CanonicalFormat = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&read).unwrap(), json);
+    }
+
+    #[test]
+    fn test_dump_without_country_fails() {
+        let error = build_canonical(competition(None), movements(), Vec::new(), &[]).unwrap_err();
+
+        assert!(
+            error
+                .to_string()
+                .contains("'annecy-4-lift-2025' has no country")
+        );
+    }
+}
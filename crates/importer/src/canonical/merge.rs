@@ -0,0 +1,653 @@
+use super::models::*;
+use crate::{ImporterError, Result};
+use serde::Serialize;
+use std::fmt;
+use storage::models::NormalizedAthleteName;
+
+/// A difference between two session exports that the merge had to settle.
+/// The value of the first session wins.
+#[derive(Debug, Clone, Serialize)]
+pub struct MergeConflict {
+    /// What the conflict is about, e.g. `competition.end_date` or `athlete John Smith`
+    pub subject: String,
+    pub message: String,
+}
+
+impl fmt::Display for MergeConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.subject, self.message)
+    }
+}
+
+#[derive(Debug)]
+pub struct MergeResult {
+    pub canonical: CanonicalFormat,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+/// Label identifying the session a document was exported from, the last segment
+/// of its source URL (`.../contest/annecy-4-lift-2025-dimanche-matin`)
+pub fn session_label(canonical: &CanonicalFormat) -> Option<String> {
+    canonical
+        .source
+        .url
+        .as_deref()
+        .and_then(|url| url.trim_end_matches('/').rsplit('/').next())
+        .filter(|segment| !segment.is_empty())
+        .map(str::to_string)
+        .or_else(|| canonical.source.original_filename.clone())
+}
+
+/// Combines the exports of the sessions of one competition into a single document.
+/// Categories are unioned, an athlete listed in several sessions is kept once with
+/// the lifts of all of them, and each athlete records its `source_session`.
+pub struct CanonicalMerger {
+    conflicts: Vec<MergeConflict>,
+}
+
+impl CanonicalMerger {
+    /// `sessions` pairs each document with the label of its session, in priority order
+    pub fn merge(sessions: Vec<(String, CanonicalFormat)>) -> Result<MergeResult> {
+        let mut sessions = sessions.into_iter();
+        let (first_label, mut merged) = sessions.next().ok_or_else(|| {
+            ImporterError::ValidationError("Nothing to merge, no document given".to_string())
+        })?;
+
+        let mut merger = Self {
+            conflicts: Vec::new(),
+        };
+
+        merged.format_version = CANONICAL_FORMAT_VERSION.to_string();
+        let categories = std::mem::take(&mut merged.categories);
+        merger.add_categories(&mut merged, &first_label, categories);
+
+        for (label, document) in sessions {
+            if document.competition.slug != merged.competition.slug {
+                return Err(ImporterError::ValidationError(format!(
+                    "Session '{}' belongs to competition '{}', not '{}'",
+                    label, document.competition.slug, merged.competition.slug
+                )));
+            }
+
+            merger.merge_competition(&mut merged.competition, &document.competition, &label);
+            merger.merge_source(&mut merged, &document);
+            merger.merge_movements(&mut merged.movements, document.movements, &label);
+            merger.add_categories(&mut merged, &label, document.categories);
+        }
+
+        Ok(MergeResult {
+            canonical: merged,
+            conflicts: merger.conflicts,
+        })
+    }
+
+    fn conflict(&mut self, subject: impl Into<String>, message: String) {
+        self.conflicts.push(MergeConflict {
+            subject: subject.into(),
+            message,
+        });
+    }
+
+    fn merge_competition(
+        &mut self,
+        merged: &mut CompetitionData,
+        other: &CompetitionData,
+        label: &str,
+    ) {
+        let mut check = |field: &str, kept: String, other: String| {
+            if kept != other {
+                self.conflict(
+                    format!("competition.{}", field),
+                    format!("'{}' kept, session {} has '{}'", kept, label, other),
+                );
+            }
+        };
+
+        check("name", merged.name.clone(), other.name.clone());
+        check(
+            "federation",
+            merged.federation.name.clone(),
+            other.federation.name.clone(),
+        );
+        check(
+            "start_date",
+            merged.start_date.to_string(),
+            other.start_date.to_string(),
+        );
+        check(
+            "end_date",
+            merged.end_date.to_string(),
+            other.end_date.to_string(),
+        );
+        check(
+            "venue",
+            merged.venue.clone().unwrap_or_default(),
+            other.venue.clone().unwrap_or_default(),
+        );
+        check(
+            "city",
+            merged.city.clone().unwrap_or_default(),
+            other.city.clone().unwrap_or_default(),
+        );
+        check("country", merged.country.clone(), other.country.clone());
+        check(
+            "number_of_judges",
+            format!("{:?}", merged.number_of_judges),
+            format!("{:?}", other.number_of_judges),
+        );
+    }
+
+    /// The merged document no longer comes from a single URL, and keeps the
+    /// LiftControl contest of every session
+    fn merge_source(&mut self, merged: &mut CanonicalFormat, other: &CanonicalFormat) {
+        if merged.source.r#type.as_str() != other.source.r#type.as_str() {
+            self.conflict(
+                "source.type",
+                format!(
+                    "'{}' kept, a session comes from '{}'",
+                    merged.source.r#type.as_str(),
+                    other.source.r#type.as_str()
+                ),
+            );
+        }
+
+        merged.source.extracted_at = merged.source.extracted_at.max(other.source.extracted_at);
+        if merged.source.url != other.source.url {
+            merged.source.url = None;
+        }
+        if merged.source.original_filename != other.source.original_filename {
+            merged.source.original_filename = None;
+        }
+        match (
+            &mut merged.liftcontrol_metadata,
+            &other.liftcontrol_metadata,
+        ) {
+            (Some(metadata), Some(other)) => {
+                for contest_id in other.contest_ids() {
+                    if !metadata.contest_ids().any(|id| id == contest_id) {
+                        metadata.merged_contest_ids.push(contest_id);
+                    }
+                }
+            }
+            (None, Some(other)) => merged.liftcontrol_metadata = Some(other.clone()),
+            (_, None) => {}
+        }
+    }
+
+    fn merge_movements(
+        &mut self,
+        merged: &mut Vec<MovementData>,
+        movements: Vec<MovementData>,
+        label: &str,
+    ) {
+        for movement in movements {
+            match merged.iter().find(|m| m.name == movement.name) {
+                Some(existing) if existing.order != movement.order => self.conflict(
+                    format!("movement {}", movement.name),
+                    format!(
+                        "order {} kept, session {} has {}",
+                        existing.order, label, movement.order
+                    ),
+                ),
+                Some(_) => {}
+                None => merged.push(movement),
+            }
+        }
+        merged.sort_by_key(|m| m.order);
+    }
+
+    fn add_categories(
+        &mut self,
+        merged: &mut CanonicalFormat,
+        label: &str,
+        categories: Vec<CategoryData>,
+    ) {
+        for mut category in categories {
+            let athletes = std::mem::take(&mut category.athletes);
+
+            let index = match merged
+                .categories
+                .iter()
+                .position(|c| c.name == category.name && c.gender == category.gender)
+            {
+                Some(index) => {
+                    let existing = &merged.categories[index];
+                    if existing.weight_class_min != category.weight_class_min
+                        || existing.weight_class_max != category.weight_class_max
+                    {
+                        let subject = format!("category {}", category.name);
+                        let message = format!(
+                            "weight class {:?}-{:?} kept, session {} has {:?}-{:?}",
+                            existing.weight_class_min,
+                            existing.weight_class_max,
+                            label,
+                            category.weight_class_min,
+                            category.weight_class_max
+                        );
+                        self.conflict(subject, message);
+                    }
+                    index
+                }
+                None => {
+                    merged.categories.push(category);
+                    merged.categories.len() - 1
+                }
+            };
+
+            for mut athlete in athletes {
+                athlete
+                    .source_session
+                    .get_or_insert_with(|| label.to_string());
+                self.add_athlete(merged, index, athlete, label);
+            }
+        }
+    }
+
+    fn add_athlete(
+        &mut self,
+        merged: &mut CanonicalFormat,
+        category_index: usize,
+        athlete: AthleteData,
+        label: &str,
+    ) {
+        let category_name = merged.categories[category_index].name.clone();
+        let gender = athlete
+            .gender
+            .clone()
+            .unwrap_or_else(|| merged.categories[category_index].gender.clone());
+        let name = NormalizedAthleteName::new(&athlete.first_name, &athlete.last_name);
+        let registrations = athlete
+            .liftcontrol_athlete_metadata
+            .iter()
+            .flat_map(|m| m.athlete_ids())
+            .collect::<Vec<_>>();
+
+        // The same LiftControl registration first, then the same name, gender and
+        // country: namesakes from another country or of another gender are kept apart
+        let position = find_athlete(&merged.categories, |_, a| {
+            a.liftcontrol_athlete_metadata
+                .as_ref()
+                .is_some_and(|m| m.athlete_ids().any(|id| registrations.contains(&id)))
+        })
+        .or_else(|| {
+            find_athlete(&merged.categories, |c, a| {
+                a.gender.as_deref().unwrap_or(&c.gender) == gender
+                    && a.country == athlete.country
+                    && NormalizedAthleteName::new(&a.first_name, &a.last_name) == name
+            })
+        });
+
+        let Some((existing_category, existing_index)) = position else {
+            merged.categories[category_index].athletes.push(athlete);
+            return;
+        };
+        let existing = &mut merged.categories[existing_category].athletes[existing_index];
+
+        let subject = format!("athlete {} {}", athlete.first_name, athlete.last_name);
+        let mut conflicts = Vec::new();
+
+        if existing_category != category_index {
+            conflicts.push(format!(
+                "listed in category {} by session {}, kept in its first category",
+                category_name, label
+            ));
+        }
+
+        match (existing.bodyweight, athlete.bodyweight) {
+            (None, Some(bodyweight)) => existing.bodyweight = Some(bodyweight),
+            (Some(kept), Some(other)) if kept != other => conflicts.push(format!(
+                "bodyweight {} kept, session {} has {}",
+                kept, label, other
+            )),
+            _ => {}
+        }
+
+        match (existing.lot_number, athlete.lot_number) {
+            (None, Some(lot_number)) => existing.lot_number = Some(lot_number),
+            (Some(kept), Some(other)) if kept != other => conflicts.push(format!(
+                "lot number {} kept, session {} has {}",
+                kept, label, other
+            )),
+            _ => {}
+        }
+
+        match (
+            &mut existing.liftcontrol_athlete_metadata,
+            &athlete.liftcontrol_athlete_metadata,
+        ) {
+            (Some(metadata), Some(other)) => {
+                for athlete_id in other.athlete_ids() {
+                    if !metadata.athlete_ids().any(|id| id == athlete_id) {
+                        metadata.merged_athlete_ids.push(athlete_id);
+                    }
+                }
+            }
+            (None, Some(other)) => existing.liftcontrol_athlete_metadata = Some(other.clone()),
+            (_, None) => {}
+        }
+
+        if athlete.is_disqualified == Some(true) && existing.is_disqualified != Some(true) {
+            conflicts.push(format!("disqualified in session {} only", label));
+        }
+
+        for lift in athlete.lifts {
+            match existing
+                .lifts
+                .iter_mut()
+                .find(|l| l.movement == lift.movement)
+            {
+                None => existing.lifts.push(lift),
+                Some(kept) if kept.attempts.is_empty() => *kept = lift,
+                Some(kept) if !lift.attempts.is_empty() && !same_attempts(kept, &lift) => conflicts
+                    .push(format!(
+                        "{} attempts differ, session {} ignored",
+                        lift.movement, label
+                    )),
+                Some(_) => {}
+            }
+        }
+
+        for message in conflicts {
+            self.conflict(subject.clone(), message);
+        }
+    }
+}
+
+/// Category and athlete indexes of the first merged athlete matching
+fn find_athlete(
+    categories: &[CategoryData],
+    matches: impl Fn(&CategoryData, &AthleteData) -> bool,
+) -> Option<(usize, usize)> {
+    categories.iter().enumerate().find_map(|(i, category)| {
+        category
+            .athletes
+            .iter()
+            .position(|athlete| matches(category, athlete))
+            .map(|j| (i, j))
+    })
+}
+
+fn same_attempts(a: &LiftData, b: &LiftData) -> bool {
+    a.attempts.len() == b.attempts.len()
+        && a.attempts.iter().zip(&b.attempts).all(|(x, y)| {
+            x.attempt_number == y.attempt_number
+                && x.weight == y.weight
+                && x.is_successful == y.is_successful
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, Utc};
+    use rust_decimal::Decimal;
+
+    fn document(url: &str, categories: Vec<CategoryData>) -> CanonicalFormat {
+        CanonicalFormat {
+            format_version: CANONICAL_FORMAT_VERSION.to_string(),
+            source: SourceMetadata {
+                r#type: SourceType::LiftControl,
+                url: Some(url.to_string()),
+                extracted_at: Utc::now(),
+                extractor: "liftcontrol-api-v1".to_string(),
+                original_filename: None,
+            },
+            competition: CompetitionData {
+                name: "Annecy 4 Lift 2025".to_string(),
+                slug: "annecy-4-lift-2025".to_string(),
+                federation: FederationData {
+                    name: "4Lift".to_string(),
+                    slug: None,
+                    abbreviation: Some("4L".to_string()),
+                    country: Some("FR".to_string()),
+                },
+                start_date: NaiveDate::from_ymd_opt(2025, 11, 1).unwrap(),
+                end_date: NaiveDate::from_ymd_opt(2025, 11, 2).unwrap(),
+                venue: None,
+                city: None,
+                country: "France".to_string(),
+                number_of_judges: Some(3),
+                status: None,
+            },
+            movements: vec![MovementData {
+                name: "Squat".to_string(),
+                order: 1,
+                is_required: Some(true),
+            }],
+            categories,
+            liftcontrol_metadata: None,
+            pdf_metadata: None,
+        }
+    }
+
+    fn category(name: &str, athletes: Vec<AthleteData>) -> CategoryData {
+        CategoryData {
+            name: name.to_string(),
+            gender: "M".to_string(),
+            weight_class_min: None,
+            weight_class_max: None,
+            athletes,
+        }
+    }
+
+    fn athlete(first_name: &str, last_name: &str, squat: &[i64]) -> AthleteData {
+        AthleteData {
+            first_name: first_name.to_string(),
+            last_name: last_name.to_string(),
+            gender: None,
+            country: "FR".to_string(),
+            nationality: None,
+            birth_date: None,
+            birth_year: None,
+            bodyweight: Some(Decimal::from(80)),
+            lot_number: None,
+            is_disqualified: Some(false),
+            disqualified_reason: None,
+            lifts: vec![LiftData {
+                movement: "Squat".to_string(),
+                attempts: squat
+                    .iter()
+                    .enumerate()
+                    .map(|(i, weight)| AttemptData {
+                        attempt_number: i as i16 + 1,
+                        weight: Decimal::from(*weight),
+                        is_successful: true,
+                        no_rep_reason: None,
+                        kind: AttemptKind::Regular,
+                    })
+                    .collect(),
+                equipment_setting: None,
+            }],
+            liftcontrol_athlete_metadata: None,
+            source_session: None,
+        }
+    }
+
+    #[test]
+    fn test_merge_sessions() {
+        let mut morning = document(
+            "https://app.liftcontrol.com/contest/annecy-matin",
+            vec![category(
+                "-80",
+                vec![athlete("Hugo", "Meunier", &[100, 110, 120])],
+            )],
+        );
+        let mut afternoon = document(
+            "https://app.liftcontrol.com/contest/annecy-apres-midi",
+            vec![
+                category("-80", vec![athlete("Jean", "Dupont", &[90])]),
+                category("+87", vec![athlete("Paul", "Martin", &[150])]),
+            ],
+        );
+        morning.liftcontrol_metadata = Some(LiftControlMetadata {
+            contest_id: 39,
+            merged_contest_ids: Vec::new(),
+        });
+        afternoon.liftcontrol_metadata = Some(LiftControlMetadata {
+            contest_id: 40,
+            merged_contest_ids: Vec::new(),
+        });
+
+        let sessions = [morning, afternoon]
+            .into_iter()
+            .map(|d| (session_label(&d).unwrap(), d))
+            .collect();
+        let result = CanonicalMerger::merge(sessions).unwrap();
+
+        assert!(result.conflicts.is_empty());
+        assert!(result.canonical.source.url.is_none());
+        let metadata = result.canonical.liftcontrol_metadata.as_ref().unwrap();
+        assert_eq!(metadata.contest_ids().collect::<Vec<_>>(), vec![39, 40]);
+        assert_eq!(result.canonical.categories.len(), 2);
+        assert_eq!(result.canonical.categories[0].athletes.len(), 2);
+
+        let dupont = &result.canonical.categories[0].athletes[1];
+        assert_eq!(dupont.source_session.as_deref(), Some("annecy-apres-midi"));
+    }
+
+    #[test]
+    fn test_duplicate_athlete_is_merged_and_conflicts_reported() {
+        let morning = document(
+            "https://app.liftcontrol.com/contest/annecy-matin",
+            vec![category(
+                "-80",
+                vec![athlete("Hugo", "Meunier", &[100, 110, 120])],
+            )],
+        );
+        let mut rerun = athlete("HUGO", "MEUNIER", &[100, 110, 125]);
+        rerun.bodyweight = Some(Decimal::from(81));
+        let mut afternoon = document(
+            "https://app.liftcontrol.com/contest/annecy-apres-midi",
+            vec![category("-80", vec![rerun])],
+        );
+        afternoon.competition.end_date = NaiveDate::from_ymd_opt(2025, 11, 3).unwrap();
+
+        let result = CanonicalMerger::merge(vec![
+            ("matin".to_string(), morning),
+            ("apres-midi".to_string(), afternoon),
+        ])
+        .unwrap();
+
+        let athletes = &result.canonical.categories[0].athletes;
+        assert_eq!(athletes.len(), 1);
+        assert_eq!(athletes[0].source_session.as_deref(), Some("matin"));
+        assert_eq!(athletes[0].lifts[0].attempts[2].weight, Decimal::from(120));
+
+        let subjects = result
+            .conflicts
+            .iter()
+            .map(|c| c.subject.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            subjects,
+            vec![
+                "competition.end_date",
+                "athlete HUGO MEUNIER",
+                "athlete HUGO MEUNIER",
+            ]
+        );
+    }
+
+    fn registered(mut athlete: AthleteData, athlete_id: i32) -> AthleteData {
+        athlete.liftcontrol_athlete_metadata = Some(LiftControlAthleteMetadata {
+            athlete_id,
+            reglage_dips: None,
+            reglage_squat: None,
+            merged_athlete_ids: Vec::new(),
+        });
+        athlete
+    }
+
+    #[test]
+    fn test_namesakes_are_kept_apart() {
+        let morning = document(
+            "https://app.liftcontrol.com/contest/annecy-matin",
+            vec![category(
+                "-80",
+                vec![registered(athlete("Hugo", "Meunier", &[100]), 317)],
+            )],
+        );
+        let mut belgian = registered(athlete("Hugo", "Meunier", &[140]), 402);
+        belgian.country = "BE".to_string();
+        let mut woman = registered(athlete("Hugo", "Meunier", &[90]), 403);
+        woman.gender = Some("F".to_string());
+        let afternoon = document(
+            "https://app.liftcontrol.com/contest/annecy-apres-midi",
+            vec![category("-80", vec![belgian, woman])],
+        );
+
+        let result = CanonicalMerger::merge(vec![
+            ("matin".to_string(), morning),
+            ("apres-midi".to_string(), afternoon),
+        ])
+        .unwrap();
+
+        assert!(result.conflicts.is_empty());
+        let registrations = result.canonical.categories[0]
+            .athletes
+            .iter()
+            .map(|a| a.liftcontrol_athlete_metadata.as_ref().unwrap().athlete_id)
+            .collect::<Vec<_>>();
+        assert_eq!(registrations, vec![317, 402, 403]);
+    }
+
+    #[test]
+    fn test_registrations_of_every_session_are_kept() {
+        let morning = document(
+            "https://app.liftcontrol.com/contest/annecy-matin",
+            vec![category(
+                "-80",
+                vec![
+                    athlete("Hugo", "Meunier", &[100]),
+                    registered(athlete("Jean", "Dupont", &[90]), 318),
+                ],
+            )],
+        );
+        // Registered again in the afternoon, once under another spelling
+        let afternoon = document(
+            "https://app.liftcontrol.com/contest/annecy-apres-midi",
+            vec![category(
+                "-80",
+                vec![
+                    registered(athlete("Hugo", "Meunier", &[]), 401),
+                    registered(athlete("Jean", "Dupond", &[]), 318),
+                    registered(athlete("Jean", "Dupont", &[]), 402),
+                ],
+            )],
+        );
+
+        let result = CanonicalMerger::merge(vec![
+            ("matin".to_string(), morning),
+            ("apres-midi".to_string(), afternoon),
+        ])
+        .unwrap();
+
+        let athletes = &result.canonical.categories[0].athletes;
+        assert_eq!(athletes.len(), 2);
+        let registrations = |athlete: &AthleteData| {
+            athlete
+                .liftcontrol_athlete_metadata
+                .as_ref()
+                .unwrap()
+                .athlete_ids()
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(registrations(&athletes[0]), vec![401]);
+        assert_eq!(registrations(&athletes[1]), vec![318, 402]);
+    }
+
+    #[test]
+    fn test_merge_rejects_other_competition() {
+        let annecy = document("https://app.liftcontrol.com/contest/annecy-matin", vec![]);
+        let mut paris = document("https://app.liftcontrol.com/contest/paris", vec![]);
+        paris.competition.slug = "paris-open-2026".to_string();
+
+        let error = CanonicalMerger::merge(vec![
+            ("annecy".to_string(), annecy),
+            ("paris".to_string(), paris),
+        ])
+        .unwrap_err();
+
+        assert!(error.to_string().contains("paris-open-2026"));
+    }
+}
@@ -1,3 +1,8 @@
-pub mod models;
-pub mod transformer;
+pub mod bulk;
+pub mod dumper;
+pub mod merge;
+pub mod pipeline;
 pub mod validator;
+pub mod watch;
+
+pub use storage::canonical::{diff, loader, models, upgrade};
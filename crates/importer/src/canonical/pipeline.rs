@@ -0,0 +1,73 @@
+//! The steps shared by every way a canonical document reaches the database: the
+//! `import canonical` command, bulk imports and submissions uploaded through the API
+
+use super::diff::ImportDiff;
+use super::loader::{CanonicalLoader, ImportOrigin};
+use super::models::CanonicalFormat;
+use super::upgrade;
+use super::validator::{CanonicalValidator, ValidationReport};
+use crate::Result;
+use sqlx::PgPool;
+
+/// A canonical document read from its raw content, upgraded to the current version
+/// and checked by the validator
+#[derive(Debug)]
+pub struct PreparedImport {
+    pub canonical: CanonicalFormat,
+    pub origin: ImportOrigin,
+    pub report: ValidationReport,
+}
+
+impl PreparedImport {
+    /// Fails only when the content is not a canonical document; validation issues
+    /// are left in `report` for the caller to decide on
+    pub fn read(content: &str, file_path: Option<String>) -> Result<Self> {
+        let canonical = upgrade::parse(content)?;
+        let report = CanonicalValidator::check(&canonical);
+
+        Ok(Self {
+            canonical,
+            origin: ImportOrigin::new(file_path, content.as_bytes()),
+            report,
+        })
+    }
+
+    /// Runs the import in a rolled back transaction and reports what it would change
+    pub async fn dry_run(&self, pool: &PgPool) -> Result<ImportDiff> {
+        Ok(CanonicalLoader::new(pool)
+            .dry_run(self.canonical.clone(), &self.origin)
+            .await?)
+    }
+
+    pub async fn import(&self, pool: &PgPool) -> Result<()> {
+        Ok(CanonicalLoader::new(pool)
+            .import_to_database(self.canonical.clone(), &self.origin)
+            .await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_validates() {
+        let content = include_str!(
+            "../../../../imports/annecy-4-lift-2025/2025-12-07T15-18-10_annecy-4-lift-2025-dimanche-matin-39_liftcontrol.json"
+        );
+        let prepared = PreparedImport::read(content, Some("annecy.json".to_string())).unwrap();
+
+        assert_eq!(prepared.canonical.competition.slug, "annecy-4-lift-2025");
+        assert_eq!(prepared.origin.file_path.as_deref(), Some("annecy.json"));
+        assert_eq!(
+            prepared.origin.content_hash,
+            ImportOrigin::hash(content.as_bytes())
+        );
+        assert!(!prepared.report.is_failure(false));
+    }
+
+    #[test]
+    fn test_read_rejects_other_documents() {
+        assert!(PreparedImport::read(r#"{"name": "not canonical"}"#, None).is_err());
+    }
+}
@@ -1,204 +1,920 @@
-use super::models::CanonicalFormat;
+use super::models::{
+    AthleteData, AttemptKind, CANONICAL_FORMAT_VERSION, CanonicalFormat, CategoryData, LiftData,
+};
+use crate::movement_mapper::CanonicalMovement;
 use crate::{ImporterError, Result};
-use std::collections::HashSet;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use storage::models::{Birth, NormalizedAthleteName};
 use tracing::warn;
 
 pub struct CanonicalValidator;
 
 impl CanonicalValidator {
+    /// Validates the document and fails with every error folded into one
+    /// [`ImporterError::ValidationError`]. Use [`CanonicalValidator::check`] to get the
+    /// structured issues instead.
     pub fn validate(canonical: &CanonicalFormat) -> Result<ValidationReport> {
+        let report = Self::check(canonical);
+        report.ensure_valid(false)?;
+        Ok(report)
+    }
+
+    /// Runs every check and returns all issues found, errors and warnings alike
+    pub fn check(canonical: &CanonicalFormat) -> ValidationReport {
         let mut report = ValidationReport::default();
 
-        if canonical.format_version != "1.0.0" {
-            report.errors.push(format!(
-                "Unsupported format version: {}. Expected 1.0.0",
-                canonical.format_version
-            ));
+        if canonical.format_version != CANONICAL_FORMAT_VERSION {
+            report.error(
+                "unsupported_format_version",
+                "/format_version",
+                format!(
+                    "Unsupported format version: {}. Expected {}, read the file through canonical::upgrade",
+                    canonical.format_version, CANONICAL_FORMAT_VERSION
+                ),
+            );
         }
 
         if canonical.competition.name.is_empty() {
-            report
-                .errors
-                .push("Competition name is required".to_string());
+            report.error(
+                "missing_field",
+                "/competition/name",
+                "Competition name is required",
+            );
         }
         if canonical.competition.slug.is_empty() {
-            report
-                .errors
-                .push("Competition slug is required".to_string());
+            report.error(
+                "missing_field",
+                "/competition/slug",
+                "Competition slug is required",
+            );
         }
         if canonical.competition.country.is_empty() {
-            report
-                .errors
-                .push("Competition country is required".to_string());
+            report.error(
+                "missing_field",
+                "/competition/country",
+                "Competition country is required",
+            );
         }
         if canonical.competition.end_date < canonical.competition.start_date {
-            report
-                .errors
-                .push("Competition end_date must be >= start_date".to_string());
+            report.error(
+                "invalid_dates",
+                "/competition/end_date",
+                "Competition end_date must be >= start_date",
+            );
         }
 
         if canonical.competition.federation.name.is_empty() {
-            report
-                .errors
-                .push("Federation name is required".to_string());
+            report.error(
+                "missing_field",
+                "/competition/federation/name",
+                "Federation name is required",
+            );
         }
 
         if canonical.competition.venue.is_none() {
-            report
-                .warnings
-                .push("Competition venue is not specified".to_string());
+            report.warning(
+                "missing_field",
+                "/competition/venue",
+                "Competition venue is not specified",
+            );
         }
         if canonical.competition.city.is_none() {
-            report
-                .warnings
-                .push("Competition city is not specified".to_string());
+            report.warning(
+                "missing_field",
+                "/competition/city",
+                "Competition city is not specified",
+            );
         }
         if canonical.competition.number_of_judges.is_none() {
-            report
-                .warnings
-                .push("Number of judges is not specified".to_string());
+            report.warning(
+                "missing_field",
+                "/competition/number_of_judges",
+                "Number of judges is not specified",
+            );
         }
 
         if canonical.movements.is_empty() {
-            report
-                .errors
-                .push("At least one movement is required".to_string());
+            report.error(
+                "no_movements",
+                "/movements",
+                "At least one movement is required",
+            );
         }
 
         let mut movement_names = HashSet::new();
-        for movement in &canonical.movements {
+        for (m, movement) in canonical.movements.iter().enumerate() {
+            let path = format!("/movements/{}", m);
+
             if movement.name.is_empty() {
-                report
-                    .errors
-                    .push("Movement name cannot be empty".to_string());
+                report.error(
+                    "missing_field",
+                    format!("{}/name", path),
+                    "Movement name cannot be empty",
+                );
             }
             if movement.order < 1 {
-                report.errors.push(format!(
-                    "Movement '{}' has invalid order: {}. Order must be >= 1",
-                    movement.name, movement.order
-                ));
+                report.error(
+                    "invalid_movement_order",
+                    format!("{}/order", path),
+                    format!(
+                        "Movement '{}' has invalid order: {}. Order must be >= 1",
+                        movement.name, movement.order
+                    ),
+                );
             }
             if !movement_names.insert(&movement.name) {
-                report
-                    .errors
-                    .push(format!("Duplicate movement name: '{}'", movement.name));
+                report.error(
+                    "duplicate_movement",
+                    format!("{}/name", path),
+                    format!("Duplicate movement name: '{}'", movement.name),
+                );
             }
         }
 
         if canonical.categories.is_empty() {
-            report
-                .errors
-                .push("At least one category is required".to_string());
+            report.error(
+                "no_categories",
+                "/categories",
+                "At least one category is required",
+            );
         }
 
-        for category in &canonical.categories {
+        let mut athlete_categories: HashMap<NormalizedAthleteName, &str> = HashMap::new();
+        let mut lot_numbers: HashMap<i32, String> = HashMap::new();
+
+        for (c, category) in canonical.categories.iter().enumerate() {
+            let category_path = format!("/categories/{}", c);
+
             if category.name.is_empty() {
-                report
-                    .errors
-                    .push("Category name cannot be empty".to_string());
+                report.error(
+                    "missing_field",
+                    format!("{}/name", category_path),
+                    "Category name cannot be empty",
+                );
             }
             if category.gender != "M" && category.gender != "F" {
-                report.errors.push(format!(
-                    "Invalid gender in category '{}': '{}'. Must be 'M' or 'F'",
-                    category.name, category.gender
-                ));
+                report.error(
+                    "invalid_gender",
+                    format!("{}/gender", category_path),
+                    format!(
+                        "Invalid gender in category '{}': '{}'. Must be 'M' or 'F'",
+                        category.name, category.gender
+                    ),
+                );
             }
 
             if category.athletes.is_empty() {
-                report
-                    .warnings
-                    .push(format!("Category '{}' has no athletes", category.name));
+                report.warning(
+                    "empty_category",
+                    format!("{}/athletes", category_path),
+                    format!("Category '{}' has no athletes", category.name),
+                );
             }
 
             for (idx, athlete) in category.athletes.iter().enumerate() {
+                let athlete_path = format!("{}/athletes/{}", category_path, idx);
                 let athlete_label =
                     format!("{}. {} {}", idx + 1, athlete.first_name, athlete.last_name);
 
                 if athlete.first_name.is_empty() {
-                    report.errors.push(format!(
-                        "Athlete in category '{}' has empty first_name",
-                        category.name
-                    ));
+                    report.error(
+                        "missing_field",
+                        format!("{}/first_name", athlete_path),
+                        format!(
+                            "Athlete in category '{}' has empty first_name",
+                            category.name
+                        ),
+                    );
                 }
                 if athlete.last_name.is_empty() {
-                    report.errors.push(format!(
-                        "Athlete in category '{}' has empty last_name",
-                        category.name
-                    ));
+                    report.error(
+                        "missing_field",
+                        format!("{}/last_name", athlete_path),
+                        format!(
+                            "Athlete in category '{}' has empty last_name",
+                            category.name
+                        ),
+                    );
                 }
                 if athlete.country.is_empty() {
-                    report
-                        .errors
-                        .push(format!("Athlete '{}' has empty country", athlete_label));
+                    report.error(
+                        "missing_field",
+                        format!("{}/country", athlete_path),
+                        format!("Athlete '{}' has empty country", athlete_label),
+                    );
+                }
+
+                let name = NormalizedAthleteName::new(&athlete.first_name, &athlete.last_name);
+                if let Some(first_category) = athlete_categories.insert(name, &category.name) {
+                    let message = if first_category == category.name {
+                        format!(
+                            "Athlete '{}' is listed twice in category '{}'",
+                            athlete_label, category.name
+                        )
+                    } else {
+                        format!(
+                            "Athlete '{}' is listed in categories '{}' and '{}'",
+                            athlete_label, first_category, category.name
+                        )
+                    };
+                    report.error("duplicate_athlete", athlete_path.clone(), message);
                 }
 
                 if athlete.bodyweight.is_none() {
-                    report
-                        .warnings
-                        .push(format!("Athlete '{}' is missing bodyweight", athlete_label));
+                    report.warning(
+                        "missing_field",
+                        format!("{}/bodyweight", athlete_path),
+                        format!("Athlete '{}' is missing bodyweight", athlete_label),
+                    );
+                }
+                if let Some(lot_number) = athlete.lot_number {
+                    if lot_number < 1 {
+                        report.error(
+                            "invalid_lot_number",
+                            format!("{}/lot_number", athlete_path),
+                            format!(
+                                "Athlete '{}' has lot number {}. Must be 1 or more",
+                                athlete_label, lot_number
+                            ),
+                        );
+                    } else if let Some(holder) =
+                        lot_numbers.insert(lot_number, athlete_label.clone())
+                    {
+                        report.warning(
+                            "duplicate_lot_number",
+                            format!("{}/lot_number", athlete_path),
+                            format!(
+                                "Athlete '{}' has lot number {} of '{}'",
+                                athlete_label, lot_number, holder
+                            ),
+                        );
+                    }
+                }
+
+                check_bodyweight(
+                    &mut report,
+                    &athlete_path,
+                    &athlete_label,
+                    athlete,
+                    category,
+                );
+                check_birth(
+                    &mut report,
+                    &athlete_path,
+                    &athlete_label,
+                    athlete,
+                    canonical.competition.start_date,
+                );
+
+                if athlete.is_disqualified == Some(true)
+                    && athlete
+                        .disqualified_reason
+                        .as_deref()
+                        .is_none_or(|reason| reason.trim().is_empty())
+                {
+                    report.warning(
+                        "missing_disqualified_reason",
+                        format!("{}/disqualified_reason", athlete_path),
+                        format!(
+                            "Athlete '{}' is disqualified without a reason",
+                            athlete_label
+                        ),
+                    );
                 }
 
                 if athlete.lifts.is_empty() {
-                    report
-                        .warnings
-                        .push(format!("Athlete '{}' has no lifts", athlete_label));
+                    report.warning(
+                        "no_lifts",
+                        format!("{}/lifts", athlete_path),
+                        format!("Athlete '{}' has no lifts", athlete_label),
+                    );
                 }
 
-                for lift in &athlete.lifts {
+                for (l, lift) in athlete.lifts.iter().enumerate() {
+                    let lift_path = format!("{}/lifts/{}", athlete_path, l);
+
                     if !movement_names.contains(&lift.movement) {
-                        report.errors.push(format!(
-                            "Athlete '{}' has lift for unknown movement: '{}'",
-                            athlete_label, lift.movement
-                        ));
+                        report.error(
+                            "unknown_movement",
+                            format!("{}/movement", lift_path),
+                            format!(
+                                "Athlete '{}' has lift for unknown movement: '{}'",
+                                athlete_label, lift.movement
+                            ),
+                        );
                     }
 
                     if lift.attempts.is_empty() {
-                        report.errors.push(format!(
-                            "Athlete '{}' has lift '{}' with no attempts",
-                            athlete_label, lift.movement
-                        ));
+                        report.error(
+                            "no_attempts",
+                            format!("{}/attempts", lift_path),
+                            format!(
+                                "Athlete '{}' has lift '{}' with no attempts",
+                                athlete_label, lift.movement
+                            ),
+                        );
                     }
 
-                    for attempt in &lift.attempts {
-                        if attempt.attempt_number < 1 || attempt.attempt_number > 3 {
-                            report.errors.push(format!(
-                                "Athlete '{}', movement '{}': invalid attempt_number {}. Must be 1-3",
-                                athlete_label, lift.movement, attempt.attempt_number
-                            ));
+                    for (a, attempt) in lift.attempts.iter().enumerate() {
+                        let attempt_path = format!("{}/attempts/{}", lift_path, a);
+
+                        let valid_number = match attempt.kind {
+                            AttemptKind::Regular => (1..=3).contains(&attempt.attempt_number),
+                            AttemptKind::Record => {
+                                attempt.attempt_number == AttemptKind::RECORD_ATTEMPT_NUMBER
+                            }
+                        };
+                        if !valid_number {
+                            report.error(
+                                "invalid_attempt_number",
+                                format!("{}/attempt_number", attempt_path),
+                                format!(
+                                    "Athlete '{}', movement '{}': invalid attempt_number {} for a {} attempt. Must be 1-3, or 4 for a record attempt",
+                                    athlete_label,
+                                    lift.movement,
+                                    attempt.attempt_number,
+                                    attempt.kind.as_str()
+                                ),
+                            );
                         }
                         if attempt.weight.is_sign_negative() {
-                            report.errors.push(format!(
-                                "Athlete '{}', movement '{}', attempt {}: negative weight",
-                                athlete_label, lift.movement, attempt.attempt_number
-                            ));
+                            report.error(
+                                "negative_weight",
+                                format!("{}/weight", attempt_path),
+                                format!(
+                                    "Athlete '{}', movement '{}', attempt {}: negative weight",
+                                    athlete_label, lift.movement, attempt.attempt_number
+                                ),
+                            );
                         }
                     }
+
+                    check_attempt_sequence(&mut report, &lift_path, &athlete_label, lift);
                 }
             }
         }
 
-        if !report.errors.is_empty() {
-            Err(ImporterError::ValidationError(format!(
-                "Validation failed with {} error(s): {}",
-                report.errors.len(),
-                report.errors.join("; ")
-            )))
-        } else {
-            Ok(report)
+        report
+    }
+}
+
+fn check_bodyweight(
+    report: &mut ValidationReport,
+    athlete_path: &str,
+    athlete_label: &str,
+    athlete: &AthleteData,
+    category: &CategoryData,
+) {
+    let Some(bodyweight) = athlete.bodyweight else {
+        return;
+    };
+
+    let below = category
+        .weight_class_min
+        .is_some_and(|min| bodyweight <= min);
+    let above = category
+        .weight_class_max
+        .is_some_and(|max| bodyweight > max);
+    if below || above {
+        report.warning(
+            "bodyweight_outside_category",
+            format!("{}/bodyweight", athlete_path),
+            format!(
+                "Athlete '{}' weighs {} kg, outside category '{}'",
+                athlete_label, bodyweight, category.name
+            ),
+        );
+    }
+}
+
+/// Checks that the birth date and year agree and give a plausible age at the
+/// competition
+fn check_birth(
+    report: &mut ValidationReport,
+    athlete_path: &str,
+    athlete_label: &str,
+    athlete: &AthleteData,
+    competition_date: NaiveDate,
+) {
+    let birth = match Birth::new(athlete.birth_date, athlete.birth_year) {
+        Ok(birth) => birth,
+        Err(e) => {
+            report.error(
+                "inconsistent_birth",
+                format!("{}/birth_year", athlete_path),
+                format!("Athlete '{}': {}", athlete_label, e),
+            );
+            return;
+        }
+    };
+    if birth == Birth::default() {
+        return;
+    }
+
+    let field = if birth.date().is_some() {
+        "birth_date"
+    } else {
+        "birth_year"
+    };
+    match birth.age_on(competition_date) {
+        Some(age) if age >= 0 => {
+            if !(MIN_PLAUSIBLE_AGE..=MAX_PLAUSIBLE_AGE).contains(&age) {
+                report.warning(
+                    "implausible_age",
+                    format!("{}/{}", athlete_path, field),
+                    format!("Athlete '{}' is {} at the competition", athlete_label, age),
+                );
+            }
+        }
+        _ => report.error(
+            "born_after_competition",
+            format!("{}/{}", athlete_path, field),
+            format!("Athlete '{}' is born after the competition", athlete_label),
+        ),
+    }
+}
+
+const MIN_PLAUSIBLE_AGE: i32 = 10;
+const MAX_PLAUSIBLE_AGE: i32 = 90;
+
+/// Checks the attempts of a lift in attempt number order: numbers are unique,
+/// weights of regular attempts do not go down, a miss is not followed by a make
+/// at a lower weight, and weights stay in a plausible range for the movement
+fn check_attempt_sequence(
+    report: &mut ValidationReport,
+    lift_path: &str,
+    athlete_label: &str,
+    lift: &LiftData,
+) {
+    let mut attempts = lift.attempts.iter().enumerate().collect::<Vec<_>>();
+    attempts.sort_by_key(|(_, a)| a.attempt_number);
+
+    for pair in attempts.windows(2) {
+        let ((_, previous), (a, attempt)) = (pair[0], pair[1]);
+
+        if previous.attempt_number == attempt.attempt_number {
+            report.error(
+                "duplicate_attempt_number",
+                format!("{}/attempts/{}/attempt_number", lift_path, a),
+                format!(
+                    "Athlete '{}', movement '{}': attempt {} is listed twice",
+                    athlete_label, lift.movement, attempt.attempt_number
+                ),
+            );
+            continue;
+        }
+
+        if attempt.kind.is_regular() && attempt.weight < previous.weight {
+            report.warning(
+                "decreasing_weight",
+                format!("{}/attempts/{}/weight", lift_path, a),
+                format!(
+                    "Athlete '{}', movement '{}': attempt {} ({} kg) is lighter than attempt {} ({} kg)",
+                    athlete_label,
+                    lift.movement,
+                    attempt.attempt_number,
+                    attempt.weight,
+                    previous.attempt_number,
+                    previous.weight
+                ),
+            );
+        }
+    }
+
+    for (i, &(a, attempt)) in attempts.iter().enumerate() {
+        let missed_heavier = attempts[..i]
+            .iter()
+            .find(|(_, prior)| !prior.is_successful && prior.weight > attempt.weight);
+        if let (true, true, Some((_, missed))) = (
+            attempt.kind.is_regular(),
+            attempt.is_successful,
+            missed_heavier,
+        ) {
+            report.warning(
+                "success_after_heavier_miss",
+                format!("{}/attempts/{}", lift_path, a),
+                format!(
+                    "Athlete '{}', movement '{}': attempt {} made at {} kg after missing {} kg",
+                    athlete_label,
+                    lift.movement,
+                    attempt.attempt_number,
+                    attempt.weight,
+                    missed.weight
+                ),
+            );
+        }
+    }
+
+    if let Some(max) = CanonicalMovement::from_name(&lift.movement).map(plausible_max_weight) {
+        for (a, attempt) in attempts.iter().filter(|(_, a)| a.weight > max) {
+            report.warning(
+                "implausible_weight",
+                format!("{}/attempts/{}/weight", lift_path, a),
+                format!(
+                    "Athlete '{}', movement '{}', attempt {}: {} kg is implausible (over {} kg)",
+                    athlete_label, lift.movement, attempt.attempt_number, attempt.weight, max
+                ),
+            );
         }
     }
 }
 
-#[derive(Debug, Default)]
+/// Added weight above which an attempt is most likely a typo, well over the records
+fn plausible_max_weight(movement: CanonicalMovement) -> Decimal {
+    match movement {
+        CanonicalMovement::MuscleUp => Decimal::from(80),
+        CanonicalMovement::PullUp => Decimal::from(150),
+        CanonicalMovement::Dips => Decimal::from(220),
+        CanonicalMovement::Squat => Decimal::from(400),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// One problem found in a canonical document
+#[derive(Debug, Clone, Serialize)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    /// Stable identifier of the check, e.g. `duplicate_athlete`
+    pub code: &'static str,
+    /// JSON pointer to the offending value, e.g. `/categories/2/athletes/5/lifts/0`
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.message, self.path)
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
 pub struct ValidationReport {
-    pub errors: Vec<String>,
-    pub warnings: Vec<String>,
+    pub issues: Vec<ValidationIssue>,
 }
 
 impl ValidationReport {
+    fn push(
+        &mut self,
+        severity: Severity,
+        code: &'static str,
+        path: impl Into<String>,
+        message: impl Into<String>,
+    ) {
+        self.issues.push(ValidationIssue {
+            severity,
+            code,
+            path: path.into(),
+            message: message.into(),
+        });
+    }
+
+    fn error(&mut self, code: &'static str, path: impl Into<String>, message: impl Into<String>) {
+        self.push(Severity::Error, code, path, message);
+    }
+
+    fn warning(&mut self, code: &'static str, path: impl Into<String>, message: impl Into<String>) {
+        self.push(Severity::Warning, code, path, message);
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues.iter().filter(|i| i.severity == Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues
+            .iter()
+            .filter(|i| i.severity == Severity::Warning)
+    }
+
+    /// Whether the document must be rejected, counting warnings as errors when asked to
+    pub fn is_failure(&self, warnings_as_errors: bool) -> bool {
+        if warnings_as_errors {
+            !self.issues.is_empty()
+        } else {
+            self.errors().next().is_some()
+        }
+    }
+
+    /// Fails with a [`ImporterError::ValidationError`] listing the failing issues
+    pub fn ensure_valid(&self, warnings_as_errors: bool) -> Result<()> {
+        if !self.is_failure(warnings_as_errors) {
+            return Ok(());
+        }
+
+        let failing = self
+            .issues
+            .iter()
+            .filter(|i| warnings_as_errors || i.severity == Severity::Error)
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        Err(ImporterError::ValidationError(format!(
+            "Validation failed with {} error(s): {}",
+            failing.len(),
+            failing.join("; ")
+        )))
+    }
+
     pub fn log_warnings(&self) {
-        for warning in &self.warnings {
+        for warning in self.warnings() {
             warn!("{}", warning);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canonical::models::*;
+    use chrono::{NaiveDate, Utc};
+
+    fn document(categories: Vec<CategoryData>) -> CanonicalFormat {
+        CanonicalFormat {
+            format_version: CANONICAL_FORMAT_VERSION.to_string(),
+            source: SourceMetadata {
+                r#type: SourceType::Manual,
+                url: None,
+                extracted_at: Utc::now(),
+                extractor: "manual".to_string(),
+                original_filename: None,
+            },
+            competition: CompetitionData {
+                name: "Annecy 4 Lift 2025".to_string(),
+                slug: "annecy-4-lift-2025".to_string(),
+                federation: FederationData {
+                    name: "4Lift".to_string(),
+                    slug: None,
+                    abbreviation: None,
+                    country: None,
+                },
+                start_date: NaiveDate::from_ymd_opt(2025, 11, 1).unwrap(),
+                end_date: NaiveDate::from_ymd_opt(2025, 11, 1).unwrap(),
+                venue: Some("Gymnase".to_string()),
+                city: Some("Annecy".to_string()),
+                country: "France".to_string(),
+                number_of_judges: Some(3),
+                status: None,
+            },
+            movements: vec![MovementData {
+                name: "Squat".to_string(),
+                order: 1,
+                is_required: Some(true),
+            }],
+            categories,
+            liftcontrol_metadata: None,
+            pdf_metadata: None,
+        }
+    }
+
+    fn category(name: &str, athletes: Vec<AthleteData>) -> CategoryData {
+        CategoryData {
+            name: name.to_string(),
+            gender: "M".to_string(),
+            weight_class_min: Some(Decimal::from(73)),
+            weight_class_max: Some(Decimal::from(80)),
+            athletes,
+        }
+    }
+
+    fn athlete(first_name: &str, squat: &[(i16, i64, bool)]) -> AthleteData {
+        AthleteData {
+            first_name: first_name.to_string(),
+            last_name: "Smith".to_string(),
+            gender: None,
+            country: "FR".to_string(),
+            nationality: None,
+            birth_date: None,
+            birth_year: None,
+            bodyweight: Some(Decimal::from(78)),
+            lot_number: None,
+            is_disqualified: Some(false),
+            disqualified_reason: None,
+            lifts: vec![LiftData {
+                movement: "Squat".to_string(),
+                attempts: squat
+                    .iter()
+                    .map(|&(attempt_number, weight, is_successful)| AttemptData {
+                        attempt_number,
+                        weight: Decimal::from(weight),
+                        is_successful,
+                        no_rep_reason: None,
+                        kind: AttemptKind::for_attempt_number(attempt_number),
+                    })
+                    .collect(),
+                equipment_setting: None,
+            }],
+            liftcontrol_athlete_metadata: None,
+            source_session: None,
+        }
+    }
+
+    fn warnings(report: &ValidationReport) -> Vec<(&str, &str)> {
+        report
+            .warnings()
+            .map(|issue| (issue.code, issue.path.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_clean_results_have_no_warnings() {
+        let canonical = document(vec![category(
+            "-80",
+            vec![athlete(
+                "John",
+                &[(1, 150, true), (2, 160, false), (3, 160, true)],
+            )],
+        )]);
+
+        let report = CanonicalValidator::validate(&canonical).unwrap();
+        assert!(report.issues.is_empty(), "{:?}", report.issues);
+    }
+
+    #[test]
+    fn test_duplicate_athlete_across_categories() {
+        let canonical = document(vec![
+            category("-80", vec![athlete("John", &[(1, 150, true)])]),
+            category("-73", vec![athlete("JOHN", &[(1, 150, true)])]),
+        ]);
+
+        let err = CanonicalValidator::validate(&canonical).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("listed in categories '-80' and '-73'")
+        );
+    }
+
+    #[test]
+    fn test_repeated_attempt_number() {
+        let canonical = document(vec![category(
+            "-80",
+            vec![athlete("John", &[(1, 150, true), (1, 155, true)])],
+        )]);
+
+        let err = CanonicalValidator::validate(&canonical).unwrap_err();
+        assert!(err.to_string().contains("attempt 1 is listed twice"));
+    }
+
+    #[test]
+    fn test_record_attempt() {
+        let mut john = athlete("John", &[(1, 150, true), (2, 160, false), (4, 155, true)]);
+        let report =
+            CanonicalValidator::check(&document(vec![category("-80", vec![john.clone()])]));
+        assert!(report.errors().next().is_none());
+        assert!(warnings(&report).is_empty());
+
+        john.lifts[0].attempts[1].kind = AttemptKind::Record;
+        john.lifts[0].attempts[2].kind = AttemptKind::Regular;
+        let report = CanonicalValidator::check(&document(vec![category("-80", vec![john])]));
+        let errors = report
+            .errors()
+            .map(|issue| (issue.code, issue.path.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            errors,
+            vec![
+                (
+                    "invalid_attempt_number",
+                    "/categories/0/athletes/0/lifts/0/attempts/1/attempt_number"
+                ),
+                (
+                    "invalid_attempt_number",
+                    "/categories/0/athletes/0/lifts/0/attempts/2/attempt_number"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_attempt_sequence_warnings() {
+        let canonical = document(vec![category(
+            "-80",
+            vec![athlete(
+                "John",
+                &[(1, 160, false), (2, 150, true), (3, 450, true)],
+            )],
+        )]);
+
+        let report = CanonicalValidator::validate(&canonical).unwrap();
+        let warnings = warnings(&report);
+        assert!(warnings.contains(&(
+            "decreasing_weight",
+            "/categories/0/athletes/0/lifts/0/attempts/1/weight"
+        )));
+        assert!(warnings.contains(&(
+            "success_after_heavier_miss",
+            "/categories/0/athletes/0/lifts/0/attempts/1"
+        )));
+        assert!(warnings.contains(&(
+            "implausible_weight",
+            "/categories/0/athletes/0/lifts/0/attempts/2/weight"
+        )));
+    }
+
+    #[test]
+    fn test_bodyweight_and_disqualification_warnings() {
+        let mut john = athlete("John", &[(1, 150, true)]);
+        john.bodyweight = Some(Decimal::from(85));
+        john.is_disqualified = Some(true);
+
+        let report =
+            CanonicalValidator::validate(&document(vec![category("-80", vec![john])])).unwrap();
+        let warnings = warnings(&report);
+        assert!(warnings.contains(&(
+            "bodyweight_outside_category",
+            "/categories/0/athletes/0/bodyweight"
+        )));
+        assert!(warnings.contains(&(
+            "missing_disqualified_reason",
+            "/categories/0/athletes/0/disqualified_reason"
+        )));
+    }
+
+    #[test]
+    fn test_birth_checks() {
+        let mut john = athlete("John", &[(1, 150, true)]);
+        john.birth_date = NaiveDate::from_ymd_opt(2005, 6, 15);
+        john.birth_year = Some(2004);
+        let mut bob = athlete("Bob", &[(1, 150, true)]);
+        bob.birth_year = Some(2020);
+        let mut tom = athlete("Tom", &[(1, 150, true)]);
+        tom.birth_year = Some(2026);
+        let mut max = athlete("Max", &[(1, 150, true)]);
+        max.birth_date = NaiveDate::from_ymd_opt(1990, 1, 31);
+
+        let report =
+            CanonicalValidator::check(&document(vec![category("-80", vec![john, bob, tom, max])]));
+        let errors = report
+            .errors()
+            .map(|issue| (issue.code, issue.path.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            errors,
+            vec![
+                ("inconsistent_birth", "/categories/0/athletes/0/birth_year"),
+                (
+                    "born_after_competition",
+                    "/categories/0/athletes/2/birth_year"
+                ),
+            ]
+        );
+        assert_eq!(
+            warnings(&report),
+            vec![("implausible_age", "/categories/0/athletes/1/birth_year")]
+        );
+    }
+
+    #[test]
+    fn test_lot_number_checks() {
+        let mut john = athlete("John", &[(1, 150, true)]);
+        john.lot_number = Some(3);
+        let mut bob = athlete("Bob", &[(1, 150, true)]);
+        bob.lot_number = Some(0);
+        let mut tom = athlete("Tom", &[(1, 150, true)]);
+        tom.lot_number = Some(3);
+
+        let report =
+            CanonicalValidator::check(&document(vec![category("-80", vec![john, bob, tom])]));
+        let errors = report
+            .errors()
+            .map(|issue| (issue.code, issue.path.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            errors,
+            vec![("invalid_lot_number", "/categories/0/athletes/1/lot_number")]
+        );
+        assert_eq!(
+            warnings(&report),
+            vec![(
+                "duplicate_lot_number",
+                "/categories/0/athletes/2/lot_number"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_warnings_as_errors() {
+        let mut john = athlete("John", &[(1, 150, true)]);
+        john.bodyweight = None;
+        let report = CanonicalValidator::check(&document(vec![category("-80", vec![john])]));
+
+        assert!(!report.is_failure(false));
+        assert!(report.is_failure(true));
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["issues"][0]["severity"], "warning");
+        assert_eq!(json["issues"][0]["code"], "missing_field");
+        assert_eq!(
+            json["issues"][0]["path"],
+            "/categories/0/athletes/0/bodyweight"
+        );
+    }
+}
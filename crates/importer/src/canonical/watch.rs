@@ -0,0 +1,229 @@
+//! Watching a directory of canonical files: new and changed files are processed
+//! like a bulk import once they stopped changing
+
+use super::bulk::{BulkImporter, BulkOptions, discover};
+use crate::Result;
+use sqlx::PgPool;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// What tells a file changed: its modification time and size
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileStamp {
+    pub modified: Option<SystemTime>,
+    pub len: u64,
+}
+
+impl FileStamp {
+    pub fn of(path: &Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+        Some(Self {
+            modified: metadata.modified().ok(),
+            len: metadata.len(),
+        })
+    }
+}
+
+/// Keeps the stamp of every file seen and hands out the files whose new stamp has
+/// not changed for the debounce delay, so that a file still being written or saved
+/// several times in a row is processed once
+#[derive(Debug)]
+pub struct ChangeTracker {
+    debounce: Duration,
+    processed: BTreeMap<PathBuf, FileStamp>,
+    /// Changed files, with their last stamp and when it was first seen
+    pending: BTreeMap<PathBuf, (FileStamp, Instant)>,
+}
+
+impl ChangeTracker {
+    /// The files already there are taken as processed
+    pub fn new(debounce: Duration, existing: Vec<(PathBuf, FileStamp)>) -> Self {
+        Self {
+            debounce,
+            processed: existing.into_iter().collect(),
+            pending: BTreeMap::new(),
+        }
+    }
+
+    /// Records the files found at `now` and returns the ones ready to be processed
+    pub fn update(&mut self, files: Vec<(PathBuf, FileStamp)>, now: Instant) -> Vec<PathBuf> {
+        let mut seen = BTreeMap::new();
+        for (path, stamp) in files {
+            if self.processed.get(&path) != Some(&stamp) {
+                match self.pending.get(&path) {
+                    Some((pending, _)) if *pending == stamp => {}
+                    _ => {
+                        self.pending.insert(path.clone(), (stamp, now));
+                    }
+                }
+            }
+            seen.insert(path, stamp);
+        }
+
+        // Deleted files are forgotten, a file written again later is then new
+        self.processed.retain(|path, _| seen.contains_key(path));
+        self.pending.retain(|path, _| seen.contains_key(path));
+
+        let ready = self
+            .pending
+            .iter()
+            .filter(|(_, (_, since))| now.duration_since(*since) >= self.debounce)
+            .map(|(path, _)| path.clone())
+            .collect::<Vec<_>>();
+        for path in &ready {
+            if let Some((stamp, _)) = self.pending.remove(path) {
+                self.processed.insert(path.clone(), stamp);
+            }
+        }
+
+        ready
+    }
+}
+
+/// Polls a directory and imports, or only validates, its new and changed files
+pub struct DirectoryWatcher {
+    directory: PathBuf,
+    recursive: bool,
+    interval: Duration,
+    tracker: ChangeTracker,
+    importer: BulkImporter,
+}
+
+impl DirectoryWatcher {
+    /// Fails when the directory cannot be read; its current files are left alone
+    pub fn new(
+        directory: &Path,
+        options: BulkOptions,
+        pool: Option<PgPool>,
+        interval: Duration,
+        debounce: Duration,
+    ) -> Result<Self> {
+        let existing = scan(directory, options.recursive)?;
+        tracing::info!(
+            "Watching {} ({} existing file(s) left alone)",
+            directory.display(),
+            existing.len()
+        );
+
+        Ok(Self {
+            directory: directory.to_path_buf(),
+            recursive: options.recursive,
+            interval,
+            tracker: ChangeTracker::new(debounce, existing),
+            importer: BulkImporter::new(options, pool),
+        })
+    }
+
+    /// Runs until the task is cancelled; every processed file is logged on one line
+    pub async fn run(&mut self) -> Result<()> {
+        let mut ticks = tokio::time::interval(self.interval);
+        ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            ticks.tick().await;
+
+            let files = match scan(&self.directory, self.recursive) {
+                Ok(files) => files,
+                Err(e) => {
+                    tracing::warn!("{}", e);
+                    continue;
+                }
+            };
+            let ready = self.tracker.update(files, Instant::now());
+            if !ready.is_empty() {
+                self.importer.run_files(ready).await?;
+            }
+        }
+    }
+}
+
+fn scan(directory: &Path, recursive: bool) -> Result<Vec<(PathBuf, FileStamp)>> {
+    Ok(discover(directory, recursive)?
+        .into_iter()
+        .filter_map(|path| FileStamp::of(&path).map(|stamp| (path, stamp)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stamp(len: u64) -> FileStamp {
+        FileStamp {
+            modified: None,
+            len,
+        }
+    }
+
+    #[test]
+    fn test_change_tracker() {
+        let debounce = Duration::from_secs(2);
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let a = PathBuf::from("imports/a.json");
+        let b = PathBuf::from("imports/b.json");
+
+        let mut tracker = ChangeTracker::new(debounce, vec![(a.clone(), stamp(10))]);
+
+        // Existing and unchanged
+        assert!(
+            tracker
+                .update(vec![(a.clone(), stamp(10))], at(0))
+                .is_empty()
+        );
+
+        // New file, still being written
+        let files = |a_len, b_len| vec![(a.clone(), stamp(a_len)), (b.clone(), stamp(b_len))];
+        assert!(tracker.update(files(10, 5), at(1)).is_empty());
+        assert!(tracker.update(files(10, 8), at(2)).is_empty());
+        assert!(tracker.update(files(10, 8), at(3)).is_empty());
+        assert_eq!(tracker.update(files(10, 8), at(4)), vec![b.clone()]);
+        assert!(tracker.update(files(10, 8), at(10)).is_empty());
+
+        // Changed file
+        assert!(tracker.update(files(12, 8), at(11)).is_empty());
+        assert_eq!(tracker.update(files(12, 8), at(13)), vec![a.clone()]);
+
+        // Deleted then written again
+        assert!(
+            tracker
+                .update(vec![(a.clone(), stamp(12))], at(14))
+                .is_empty()
+        );
+        assert!(tracker.update(files(12, 8), at(15)).is_empty());
+        assert_eq!(tracker.update(files(12, 8), at(17)), vec![b]);
+    }
+
+    #[tokio::test]
+    async fn test_watcher_leaves_existing_files() {
+        let imports = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../imports");
+        let options = BulkOptions {
+            validate_only: true,
+            ..Default::default()
+        };
+        let mut watcher = DirectoryWatcher::new(
+            &imports,
+            options,
+            None,
+            Duration::from_millis(10),
+            Duration::ZERO,
+        )
+        .unwrap();
+
+        let files = scan(&imports, false).unwrap();
+        assert!(!files.is_empty());
+        assert!(watcher.tracker.update(files, Instant::now()).is_empty());
+
+        assert!(
+            DirectoryWatcher::new(
+                &imports.join("missing"),
+                options,
+                None,
+                Duration::from_secs(1),
+                Duration::ZERO,
+            )
+            .is_err()
+        );
+    }
+}
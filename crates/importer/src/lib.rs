@@ -10,6 +10,6 @@ pub use traits::{CompetitionImporter, ImportContext};
 
 // Re-export LiftControl types
 pub use sources::liftcontrol::{
-    CompetitionConfig as LiftControlConfig, CompetitionId as LiftControlCompetitionId,
-    LiftControlImporter, LiftControlRegistry, LiftControlSpec,
+    CompetitionConfig as LiftControlConfig, LiftControlImporter, LiftControlRegistry,
+    LiftControlSpec,
 };
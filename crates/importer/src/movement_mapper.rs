@@ -15,6 +15,13 @@ impl CanonicalMovement {
             Self::Squat => "Squat",
         }
     }
+
+    /// Parses a canonical movement name as written by [`CanonicalMovement::as_str`]
+    pub fn from_name(name: &str) -> Option<Self> {
+        [Self::MuscleUp, Self::PullUp, Self::Dips, Self::Squat]
+            .into_iter()
+            .find(|movement| movement.as_str().eq_ignore_ascii_case(name))
+    }
 }
 
 pub trait MovementMapper {
@@ -0,0 +1,79 @@
+use super::dom::{HtmlCell, HtmlTable};
+
+/// Knows how one publishing site lays out its results pages: which tables hold
+/// results, where their category is written and how a missed attempt is marked.
+/// Reading the rows themselves is shared by all sites.
+pub trait SiteAdapter: Send + Sync {
+    /// Name given to `import html --adapter`
+    fn name(&self) -> &'static str;
+
+    /// Whether a table of the page holds results
+    fn is_results_table(&self, table: &HtmlTable) -> bool;
+
+    /// Title naming the category of a results table, e.g. `Men -80 kg`
+    fn category_title<'t>(&self, table: &'t HtmlTable) -> Option<&'t str> {
+        table.caption.as_deref().or(table.heading.as_deref())
+    }
+
+    /// Whether an attempt cell is a miss by its styling, on top of the `x27.5`
+    /// and `(27.5)` notations understood for every site
+    fn is_failed_attempt(&self, cell: &HtmlCell) -> bool {
+        cell.struck
+    }
+}
+
+/// Any page with one table per category, named by its `<caption>` or the heading
+/// above it. Misses are struck through or carry a `fail`, `failed`, `miss` or
+/// `no-lift` class.
+pub struct GenericAdapter;
+
+impl SiteAdapter for GenericAdapter {
+    fn name(&self) -> &'static str {
+        "generic"
+    }
+
+    fn is_results_table(&self, _table: &HtmlTable) -> bool {
+        true
+    }
+
+    fn is_failed_attempt(&self, cell: &HtmlCell) -> bool {
+        cell.struck
+            || ["fail", "failed", "miss", "no-lift"]
+                .iter()
+                .any(|class| cell.has_class(class))
+    }
+}
+
+/// Results pages of the 4Lift federation: `<table class="resultats">` under an
+/// `<h3>` such as `Hommes -80kg`, misses marked with the `nul` class
+pub struct FourLiftAdapter;
+
+impl SiteAdapter for FourLiftAdapter {
+    fn name(&self) -> &'static str {
+        "4lift"
+    }
+
+    fn is_results_table(&self, table: &HtmlTable) -> bool {
+        table.has_class("resultats")
+    }
+
+    fn category_title<'t>(&self, table: &'t HtmlTable) -> Option<&'t str> {
+        table.heading.as_deref()
+    }
+
+    fn is_failed_attempt(&self, cell: &HtmlCell) -> bool {
+        cell.has_class("nul")
+    }
+}
+
+/// Every adapter available on the command line
+pub fn adapters() -> Vec<Box<dyn SiteAdapter>> {
+    vec![Box::new(GenericAdapter), Box::new(FourLiftAdapter)]
+}
+
+/// Finds an adapter by its name, ignoring case
+pub fn adapter(name: &str) -> Option<Box<dyn SiteAdapter>> {
+    adapters()
+        .into_iter()
+        .find(|adapter| adapter.name().eq_ignore_ascii_case(name))
+}
@@ -0,0 +1,239 @@
+//! Pulls the tables out of a saved results page. Pages are parsed with an HTML5
+//! parser, so unclosed cells and rows, stray markup and scripts are read the way a
+//! browser reads them.
+
+use scraper::{ElementRef, Html, Node};
+
+/// A `<table>` of the page with the text around it that may name its category
+#[derive(Debug, Clone, Default)]
+pub struct HtmlTable {
+    pub id: Option<String>,
+    pub classes: Vec<String>,
+    pub caption: Option<String>,
+    /// Text of the last `<h1>`-`<h6>` before the table
+    pub heading: Option<String>,
+    pub rows: Vec<HtmlRow>,
+}
+
+impl HtmlTable {
+    pub fn has_class(&self, class: &str) -> bool {
+        self.classes.iter().any(|c| c == class)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct HtmlRow {
+    pub cells: Vec<HtmlCell>,
+}
+
+impl HtmlRow {
+    /// Rows made of `<th>` cells only, or found in the `<thead>`
+    pub fn is_header(&self) -> bool {
+        !self.cells.is_empty() && self.cells.iter().all(|c| c.header)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HtmlCell {
+    /// Text content, whitespace collapsed
+    pub text: String,
+    pub classes: Vec<String>,
+    pub colspan: usize,
+    /// `<th>`, or any cell of the `<thead>`
+    pub header: bool,
+    /// The text is struck through with `<s>`, `<del>` or `<strike>`
+    pub struck: bool,
+}
+
+impl HtmlCell {
+    pub fn has_class(&self, class: &str) -> bool {
+        self.classes.iter().any(|c| c == class)
+    }
+}
+
+/// Returns every table of the page in document order. Nested tables are read
+/// as separate tables, their rows and text left out of the enclosing table.
+pub fn parse_tables(html: &str) -> Vec<HtmlTable> {
+    let document = Html::parse_document(html);
+    let mut tables = Vec::new();
+    let mut heading: Option<String> = None;
+
+    for element in document.root_element().descendent_elements() {
+        match element.value().name() {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => heading = Some(text_of(element).0),
+            "table" => tables.push(read_table(element, heading.clone())),
+            _ => {}
+        }
+    }
+
+    tables
+}
+
+fn read_table(table: ElementRef<'_>, heading: Option<String>) -> HtmlTable {
+    let own = |element: &ElementRef<'_>| enclosing_table(*element) == Some(table);
+
+    let caption = table
+        .descendent_elements()
+        .find(|e| e.value().name() == "caption" && own(e))
+        .map(|caption| text_of(caption).0);
+
+    let rows = table
+        .descendent_elements()
+        .filter(|e| e.value().name() == "tr" && own(e))
+        .map(|row| {
+            let in_thead = row
+                .parent()
+                .and_then(ElementRef::wrap)
+                .is_some_and(|parent| parent.value().name() == "thead");
+            let cells = row
+                .child_elements()
+                .filter(|cell| matches!(cell.value().name(), "td" | "th"))
+                .map(|cell| read_cell(cell, in_thead))
+                .collect();
+            HtmlRow { cells }
+        })
+        .collect();
+
+    HtmlTable {
+        id: table.value().id().map(str::to_string),
+        classes: classes(table),
+        caption,
+        heading,
+        rows,
+    }
+}
+
+fn read_cell(cell: ElementRef<'_>, in_thead: bool) -> HtmlCell {
+    let (text, struck) = text_of(cell);
+
+    HtmlCell {
+        text,
+        classes: classes(cell),
+        colspan: cell
+            .attr("colspan")
+            .and_then(|c| c.trim().parse().ok())
+            .filter(|c| *c > 0)
+            .unwrap_or(1),
+        header: cell.value().name() == "th" || in_thead,
+        struck,
+    }
+}
+
+/// The table a row or caption belongs to, the closest one around it
+fn enclosing_table(element: ElementRef<'_>) -> Option<ElementRef<'_>> {
+    element
+        .ancestors()
+        .filter_map(ElementRef::wrap)
+        .find(|e| e.value().name() == "table")
+}
+
+fn classes(element: ElementRef<'_>) -> Vec<String> {
+    element.value().classes().map(str::to_string).collect()
+}
+
+/// Text of an element with its whitespace collapsed, without the text of nested
+/// tables and scripts, and whether part of it is struck through
+fn text_of(element: ElementRef<'_>) -> (String, bool) {
+    fn walk(element: ElementRef<'_>, text: &mut String, struck: &mut bool) {
+        for child in element.children() {
+            if let Node::Text(content) = child.value() {
+                text.push_str(content);
+            }
+            let Some(child) = ElementRef::wrap(child) else {
+                continue;
+            };
+            match child.value().name() {
+                "table" | "script" | "style" | "template" => {}
+                "br" => text.push(' '),
+                name => {
+                    *struck |= matches!(name, "s" | "del" | "strike");
+                    walk(child, text, struck);
+                }
+            }
+        }
+    }
+
+    let mut text = String::new();
+    let mut struck = false;
+    walk(element, &mut text, &mut struck);
+    (collapse_whitespace(&text), struck)
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tables() {
+        let html = r#"
+            <h2>Hommes &ndash; -80 kg</h2>
+            <!-- <table><tr><td>commented out</td></tr></table> -->
+            <table id="results" class="table resultats">
+              <caption>Men  -80&nbsp;kg</caption>
+              <thead><tr><td>Name</td><td colspan=3>Dips</td></tr></thead>
+              <tr><td>Jean <b>DUPONT</b></td><td>60</td><td class='nul'>65</td><td><del>70</del></td></tr>
+              <tr><td>Paul BERNARD<td>55<td>60<td>&#45;</tr>
+            </table>
+            <script>document.write("<table>")</script>
+        "#;
+
+        let tables = parse_tables(html);
+        assert_eq!(tables.len(), 1);
+
+        let table = &tables[0];
+        assert_eq!(table.id.as_deref(), Some("results"));
+        assert!(table.has_class("resultats"));
+        assert_eq!(table.caption.as_deref(), Some("Men -80 kg"));
+        assert_eq!(table.heading.as_deref(), Some("Hommes – -80 kg"));
+        assert_eq!(table.rows.len(), 3);
+
+        assert!(table.rows[0].is_header());
+        assert_eq!(table.rows[0].cells[1].colspan, 3);
+
+        let dupont = &table.rows[1].cells;
+        assert_eq!(dupont[0].text, "Jean DUPONT");
+        assert!(dupont[2].has_class("nul"));
+        assert!(!dupont[2].struck);
+        assert!(dupont[3].struck);
+
+        let bernard = &table.rows[2].cells;
+        assert_eq!(bernard.len(), 4);
+        assert_eq!(bernard[3].text, "-");
+    }
+
+    #[test]
+    fn test_parse_malformed_tables() {
+        let html = r#"
+            <h3>Femmes -63 kg
+            <table class=resultats>
+              <tr><th>Name<th>Dips
+              <tr><td>Marie <s>MARTIN</s><td>40
+              <tr><td>Lea PETIT<td>35<br>
+            </table>
+            <script>if (a < b && "</td>") { document.write("<table><tr><td>x") }</script>
+            <table><tr><td>Total<td><table><tr><td>nested</td></tr></table>12</td></tr></table>
+        "#;
+
+        let tables = parse_tables(html);
+        assert_eq!(tables.len(), 3);
+
+        let table = &tables[0];
+        assert_eq!(table.heading.as_deref(), Some("Femmes -63 kg"));
+        assert_eq!(table.rows.len(), 3);
+        assert!(table.rows[0].is_header());
+        assert!(!table.rows[1].is_header());
+        assert_eq!(table.rows[1].cells[0].text, "Marie MARTIN");
+        assert!(table.rows[1].cells[0].struck);
+        assert_eq!(table.rows[2].cells[1].text, "35");
+
+        let outer = &tables[1];
+        assert_eq!(outer.rows.len(), 1);
+        assert_eq!(outer.rows[0].cells.len(), 2);
+        assert_eq!(outer.rows[0].cells[1].text, "12");
+        assert_eq!(tables[2].rows[0].cells[0].text, "nested");
+    }
+}
@@ -0,0 +1,213 @@
+mod adapter;
+mod dom;
+mod parser;
+
+pub use adapter::{FourLiftAdapter, GenericAdapter, SiteAdapter, adapter, adapters};
+pub use dom::{HtmlCell, HtmlRow, HtmlTable, parse_tables};
+pub use parser::{HtmlResultsParser, ParsedPage};
+
+use crate::canonical::models as canonical;
+use crate::sources::metadata::{CompetitionMetadata, SourceConfig};
+use crate::{ImporterError, Result, traits::CompetitionImporter};
+use chrono::Utc;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// Competition published on a saved results page
+#[derive(Debug, Clone)]
+pub struct HtmlSpec {
+    file: PathBuf,
+    url: Option<String>,
+    slug: String,
+    metadata: CompetitionMetadata,
+}
+
+impl HtmlSpec {
+    pub fn new(file: impl Into<PathBuf>, url: Option<String>, config: SourceConfig) -> Self {
+        Self {
+            file: file.into(),
+            url,
+            slug: config.slug,
+            metadata: config.metadata,
+        }
+    }
+
+    pub fn file(&self) -> &Path {
+        &self.file
+    }
+
+    /// Address the page was saved from
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+
+    pub fn slug(&self) -> &str {
+        &self.slug
+    }
+
+    pub fn metadata(&self) -> &CompetitionMetadata {
+        &self.metadata
+    }
+}
+
+pub struct HtmlImporter {
+    adapter: Box<dyn SiteAdapter>,
+}
+
+impl HtmlImporter {
+    pub fn new(adapter: Box<dyn SiteAdapter>) -> Self {
+        Self { adapter }
+    }
+
+    /// Reads the saved page and converts its results tables to the canonical format
+    pub async fn export(&self, spec: &HtmlSpec) -> Result<canonical::CanonicalFormat> {
+        info!(
+            "Reading {} with the {} adapter",
+            spec.file().display(),
+            self.adapter.name()
+        );
+        let html = tokio::fs::read_to_string(spec.file()).await.map_err(|e| {
+            ImporterError::ImportError(format!("Cannot read {}: {}", spec.file().display(), e))
+        })?;
+
+        let original_filename = spec
+            .file()
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string());
+        self.to_canonical_document(spec, &html, original_filename)
+    }
+
+    pub fn to_canonical_document(
+        &self,
+        spec: &HtmlSpec,
+        html: &str,
+        original_filename: Option<String>,
+    ) -> Result<canonical::CanonicalFormat> {
+        let metadata = spec.metadata();
+        let page = HtmlResultsParser::new(
+            self.adapter.as_ref(),
+            &metadata.default_athlete_country,
+            &metadata.default_athlete_nationality,
+        )
+        .parse(html);
+
+        if page.categories.iter().all(|c| c.athletes.is_empty()) {
+            return Err(ImporterError::TransformationError(format!(
+                "No results table found by the {} adapter",
+                self.adapter.name()
+            )));
+        }
+
+        for warning in &page.warnings {
+            warn!("{}", warning);
+        }
+
+        Ok(canonical::CanonicalFormat {
+            format_version: canonical::CANONICAL_FORMAT_VERSION.to_string(),
+            source: canonical::SourceMetadata {
+                r#type: canonical::SourceType::Html,
+                url: spec.url().map(str::to_string),
+                extracted_at: Utc::now(),
+                extractor: format!("html-{}-v1", self.adapter.name()),
+                original_filename,
+            },
+            competition: metadata.to_competition_data(spec.slug()),
+            movements: page
+                .movements
+                .iter()
+                .enumerate()
+                .map(|(i, movement)| canonical::MovementData {
+                    name: movement.as_str().to_string(),
+                    order: i as i16 + 1,
+                    is_required: Some(true),
+                })
+                .collect(),
+            categories: page
+                .categories
+                .into_iter()
+                .filter(|c| !c.athletes.is_empty())
+                .collect(),
+            liftcontrol_metadata: None,
+            pdf_metadata: None,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl CompetitionImporter for HtmlImporter {
+    type Spec = HtmlSpec;
+
+    async fn to_canonical(&self, spec: &Self::Spec) -> Result<Vec<canonical::CanonicalFormat>> {
+        Ok(vec![self.export(spec).await?])
+    }
+
+    fn name(&self) -> &'static str {
+        "HTML"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canonical::validator::CanonicalValidator;
+
+    const CONFIG_TOML: &str = r#"
+slug = "championnat-4lift-2024"
+
+[metadata]
+name = "Championnat 4Lift 2024"
+start_date = "2024-06-08"
+end_date = "2024-06-08"
+country = "France"
+default_athlete_country = "FR"
+default_athlete_nationality = "French"
+
+[metadata.federation]
+name = "4Lift"
+abbreviation = "4L"
+country = "FR"
+"#;
+
+    #[tokio::test]
+    async fn test_export_saved_page() {
+        let spec = HtmlSpec::new(
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures/html/4lift-results.html"),
+            Some("https://example.org/resultats".to_string()),
+            toml::from_str(CONFIG_TOML).unwrap(),
+        );
+
+        let canonical = HtmlImporter::new(adapter("4lift").unwrap())
+            .export(&spec)
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            canonical.source.r#type,
+            canonical::SourceType::Html
+        ));
+        assert_eq!(canonical.source.extractor, "html-4lift-v1");
+        assert_eq!(
+            canonical.source.url.as_deref(),
+            Some("https://example.org/resultats")
+        );
+        assert_eq!(canonical.movements.len(), 4);
+        assert_eq!(canonical.categories.len(), 2);
+
+        CanonicalValidator::validate(&canonical).unwrap();
+    }
+
+    #[test]
+    fn test_page_without_results_fails() {
+        let spec = HtmlSpec::new("empty.html", None, toml::from_str(CONFIG_TOML).unwrap());
+        let err = HtmlImporter::new(Box::new(GenericAdapter))
+            .to_canonical_document(&spec, "<p>Coming soon</p>", None)
+            .unwrap_err();
+        assert!(err.to_string().contains("No results table"));
+    }
+
+    #[test]
+    fn test_unknown_adapter() {
+        assert!(adapter("4LIFT").is_some());
+        assert!(adapter("unknown").is_none());
+    }
+}
@@ -0,0 +1,328 @@
+use super::adapter::SiteAdapter;
+use super::dom::{HtmlCell, HtmlRow, HtmlTable, parse_tables};
+use crate::canonical::models as canonical;
+use crate::movement_mapper::{CanonicalMovement, MovementMapper};
+use crate::sources::table::{
+    Column, RowCell, RowReader, TableMovementMapper, classify_header, parse_category_line,
+};
+
+/// Results read from a saved HTML page
+#[derive(Debug, Default)]
+pub struct ParsedPage {
+    /// Movements in the order of the table columns
+    pub movements: Vec<CanonicalMovement>,
+    pub categories: Vec<canonical::CategoryData>,
+    /// Tables, rows and cells a human has to review
+    pub warnings: Vec<String>,
+}
+
+struct Location {
+    table: usize,
+    row: usize,
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "table {}, row {}", self.table, self.row)
+    }
+}
+
+/// Reads the results tables a [`SiteAdapter`] picks out of a page. A table has a
+/// header row naming the movements, either once over three attempt columns
+/// (`colspan="3"`) or once per attempt (`Dips 1`, `Dips 2`, `Dips 3`).
+pub struct HtmlResultsParser<'a> {
+    adapter: &'a dyn SiteAdapter,
+    rows: RowReader<'a>,
+}
+
+impl<'a> HtmlResultsParser<'a> {
+    pub fn new(
+        adapter: &'a dyn SiteAdapter,
+        default_country: &'a str,
+        default_nationality: &'a str,
+    ) -> Self {
+        Self {
+            adapter,
+            rows: RowReader {
+                default_country,
+                default_nationality,
+            },
+        }
+    }
+
+    pub fn parse(&self, html: &str) -> ParsedPage {
+        let mut page = ParsedPage::default();
+
+        for (table_idx, table) in parse_tables(html).iter().enumerate() {
+            if self.adapter.is_results_table(table) {
+                self.parse_table(table_idx + 1, table, &mut page);
+            }
+        }
+
+        page
+    }
+
+    fn parse_table(&self, table_number: usize, table: &HtmlTable, page: &mut ParsedPage) {
+        let Some((header_idx, columns)) = table
+            .rows
+            .iter()
+            .enumerate()
+            .find_map(|(idx, row)| parse_header(row).map(|columns| (idx, columns)))
+        else {
+            return;
+        };
+
+        let Some(title) = self.adapter.category_title(table) else {
+            page.warnings.push(format!(
+                "table {}: no category title, its athletes were left out",
+                table_number
+            ));
+            return;
+        };
+        let category = match parse_category_line(title) {
+            Some(Ok(category)) => category,
+            Some(Err(name)) => {
+                page.warnings.push(format!(
+                    "table {}: category '{}' has no gender, its athletes were left out",
+                    table_number, name
+                ));
+                return;
+            }
+            None => {
+                page.warnings.push(format!(
+                    "table {}: cannot read a category from '{}', its athletes were left out",
+                    table_number, title
+                ));
+                return;
+            }
+        };
+
+        for column in &columns {
+            if let Column::Movement(movement) = column
+                && !page.movements.contains(movement)
+            {
+                page.movements.push(*movement);
+            }
+        }
+
+        let category_idx = match page
+            .categories
+            .iter()
+            .position(|c| c.name == category.name && c.gender == category.gender)
+        {
+            Some(idx) => idx,
+            None => {
+                page.categories.push(category);
+                page.categories.len() - 1
+            }
+        };
+
+        let expected = columns.iter().map(Column::width).sum::<usize>();
+        for (row_idx, row) in table.rows.iter().enumerate().skip(header_idx + 1) {
+            if row.is_header() || row.cells.iter().all(|c| c.text.is_empty()) {
+                continue;
+            }
+
+            let location = Location {
+                table: table_number,
+                row: row_idx + 1,
+            };
+            let cells = expand_colspans(&row.cells)
+                .map(|cell| RowCell {
+                    text: &cell.text,
+                    failed: self.adapter.is_failed_attempt(cell),
+                })
+                .collect::<Vec<_>>();
+
+            if cells.len() != expected {
+                page.warnings.push(format!(
+                    "{}: expected {} cells, found {}, row left out",
+                    location,
+                    expected,
+                    cells.len()
+                ));
+                continue;
+            }
+
+            if let Some(athlete) = self
+                .rows
+                .read(&columns, &cells, &location, &mut page.warnings)
+            {
+                page.categories[category_idx].athletes.push(athlete);
+            }
+        }
+    }
+}
+
+/// Reads a header row into columns, or `None` when it does not name the athlete
+/// and at least one movement with its three attempts
+fn parse_header(row: &HtmlRow) -> Option<Vec<Column>> {
+    let mut columns = Vec::new();
+    let mut per_attempt: Option<(CanonicalMovement, i16)> = None;
+
+    for cell in &row.cells {
+        if let Some((movement, attempt)) = attempt_header(&cell.text) {
+            let follows = per_attempt.is_some_and(|(m, a)| m == movement && a + 1 == attempt);
+            if attempt == 1 {
+                columns.push(Column::Movement(movement));
+                per_attempt = Some((movement, 1));
+            } else if follows {
+                per_attempt = Some((movement, attempt));
+            } else {
+                return None;
+            }
+            continue;
+        }
+        if per_attempt.is_some_and(|(_, attempt)| attempt != 3) {
+            return None;
+        }
+        per_attempt = None;
+
+        match (classify_header(&cell.text), cell.colspan) {
+            (Column::Movement(movement), 3) => columns.push(Column::Movement(movement)),
+            // A single column per movement only holds the best lift
+            (Column::Movement(_), colspan) => {
+                columns.extend(std::iter::repeat_n(Column::Other, colspan))
+            }
+            (column, 1) => columns.push(column),
+            (_, colspan) => columns.extend(std::iter::repeat_n(Column::Other, colspan)),
+        }
+    }
+
+    let has_name = columns.contains(&Column::Name);
+    let has_movement = columns.iter().any(|c| matches!(c, Column::Movement(_)));
+    (has_name && has_movement).then_some(columns)
+}
+
+/// `Dips 1`, `Squat 3`, `MU2` into the movement and the attempt number
+fn attempt_header(text: &str) -> Option<(CanonicalMovement, i16)> {
+    let text = text.trim();
+    let attempt = match text.chars().last()? {
+        '1' => 1,
+        '2' => 2,
+        '3' => 3,
+        _ => return None,
+    };
+    let name = text[..text.len() - 1].trim_end_matches([' ', '#', '-']);
+    TableMovementMapper
+        .map_movement(name)
+        .map(|movement| (movement, attempt))
+}
+
+/// Repeats a cell spanning several columns so that row cells line up with the header
+fn expand_colspans(cells: &[HtmlCell]) -> impl Iterator<Item = &HtmlCell> {
+    cells
+        .iter()
+        .flat_map(|cell| std::iter::repeat_n(cell, cell.colspan))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::adapter::{FourLiftAdapter, GenericAdapter};
+    use super::*;
+    use rust_decimal::Decimal;
+
+    fn attempts(athlete: &canonical::AthleteData, lift: usize) -> Vec<(i16, Decimal, bool)> {
+        athlete.lifts[lift]
+            .attempts
+            .iter()
+            .map(|a| (a.attempt_number, a.weight, a.is_successful))
+            .collect()
+    }
+
+    #[test]
+    fn test_generic_fixture() {
+        let html = include_str!("../../../fixtures/html/generic-results.html");
+        let page = HtmlResultsParser::new(&GenericAdapter, "FR", "French").parse(html);
+
+        assert_eq!(
+            page.movements,
+            vec![CanonicalMovement::PullUp, CanonicalMovement::Dips]
+        );
+
+        let categories = page
+            .categories
+            .iter()
+            .map(|c| (c.name.as_str(), c.gender.as_str(), c.athletes.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(categories, vec![("-73", "M", 2), ("-57", "F", 2)]);
+
+        let smith = &page.categories[0].athletes[0];
+        assert_eq!(
+            (smith.first_name.as_str(), smith.last_name.as_str()),
+            ("John", "Smith")
+        );
+        assert_eq!(smith.country, "GB");
+        assert_eq!(
+            attempts(smith, 0),
+            vec![
+                (1, Decimal::from(40), true),
+                (2, Decimal::from(45), false),
+                (3, Decimal::from(45), true)
+            ]
+        );
+        assert_eq!(
+            attempts(smith, 1),
+            vec![
+                (1, Decimal::from(60), true),
+                (2, Decimal::new(675, 1), true)
+            ]
+        );
+
+        assert_eq!(
+            page.warnings,
+            vec!["table 2, row 4: unreadable Dips attempt 2 '5O', left out".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_4lift_fixture() {
+        let html = include_str!("../../../fixtures/html/4lift-results.html");
+        let page = HtmlResultsParser::new(&FourLiftAdapter, "FR", "French").parse(html);
+
+        assert_eq!(
+            page.movements,
+            vec![
+                CanonicalMovement::MuscleUp,
+                CanonicalMovement::PullUp,
+                CanonicalMovement::Dips,
+                CanonicalMovement::Squat
+            ]
+        );
+
+        let categories = page
+            .categories
+            .iter()
+            .map(|c| (c.name.as_str(), c.gender.as_str(), c.athletes.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(categories, vec![("-80", "M", 2), ("+63", "F", 1)]);
+
+        let dupont = &page.categories[0].athletes[0];
+        assert_eq!(dupont.last_name, "DUPONT");
+        assert_eq!(dupont.bodyweight, Some(Decimal::new(785, 1)));
+        assert_eq!(
+            attempts(dupont, 0),
+            vec![
+                (1, Decimal::from(20), true),
+                (2, Decimal::from(25), true),
+                (3, Decimal::new(275, 1), false)
+            ]
+        );
+
+        let disqualified = &page.categories[0].athletes[1];
+        assert_eq!(disqualified.is_disqualified, Some(true));
+        assert!(page.warnings.is_empty(), "{:?}", page.warnings);
+    }
+
+    #[test]
+    fn test_adapter_picks_tables() {
+        let html = "<h3>Hommes -80kg</h3><table><tr><th>Nom</th><th colspan=3>Dips</th></tr>\
+                    <tr><td>DUPONT Jean</td><td>60</td><td>65</td><td>70</td></tr></table>";
+
+        let page = HtmlResultsParser::new(&FourLiftAdapter, "FR", "French").parse(html);
+        assert!(page.categories.is_empty());
+
+        let page = HtmlResultsParser::new(&GenericAdapter, "FR", "French").parse(html);
+        assert_eq!(page.categories[0].athletes.len(), 1);
+    }
+}
@@ -1,37 +1,352 @@
+use super::discovery::{DiscoveredSession, parse_session_slugs};
 use super::models::ApiResponse;
-use crate::error::Result;
+use crate::error::{ImporterError, Result};
+use reqwest::StatusCode;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+pub const DEFAULT_BASE_URL: &str = "https://liftcontrol.fr";
+pub const DEFAULT_USER_AGENT: &str = concat!(
+    "OpenStreetLifting-importer/",
+    env!("CARGO_PKG_VERSION"),
+    " (+https://openstreetlifting.org)"
+);
+
+/// Settings of the LiftControl HTTP client
+#[derive(Debug, Clone)]
+pub struct LiftControlClientConfig {
+    pub base_url: String,
+    pub user_agent: String,
+    /// Timeout of a single request, including reading the body
+    pub timeout: Duration,
+    /// Extra attempts after a 429, a 5xx or a network error
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled on each following one
+    pub retry_backoff: Duration,
+    /// Minimum delay between two requests sent to LiftControl
+    pub min_request_interval: Duration,
+}
+
+impl Default for LiftControlClientConfig {
+    fn default() -> Self {
+        Self {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            timeout: Duration::from_secs(30),
+            max_retries: 3,
+            retry_backoff: Duration::from_millis(500),
+            min_request_interval: Duration::from_millis(500),
+        }
+    }
+}
+
+enum Backend {
+    Http {
+        config: LiftControlClientConfig,
+        client: reqwest::Client,
+        last_request: Mutex<Option<Instant>>,
+    },
+    /// Recorded responses: `<sub_slug>.json` for sessions and `<base_slug>.html` for event pages
+    Fixtures(PathBuf),
+}
 
 pub struct LiftControlClient {
-    base_url: String,
-    client: reqwest::Client,
+    backend: Backend,
 }
 
 impl LiftControlClient {
-    pub fn new() -> Self {
+    pub fn new(config: LiftControlClientConfig) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .user_agent(&config.user_agent)
+            .timeout(config.timeout)
+            .build()?;
+
+        Ok(Self {
+            backend: Backend::Http {
+                config: LiftControlClientConfig {
+                    base_url: config.base_url.trim_end_matches('/').to_string(),
+                    ..config
+                },
+                client,
+                last_request: Mutex::new(None),
+            },
+        })
+    }
+
+    /// Reads saved responses from a directory instead of calling LiftControl
+    pub fn from_fixtures(dir: impl Into<PathBuf>) -> Self {
         Self {
-            base_url: "https://liftcontrol.fr".to_string(),
-            client: reqwest::Client::builder()
-                .user_agent("Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7)")
-                .build()
-                .unwrap(),
+            backend: Backend::Fixtures(dir.into()),
         }
     }
 
     pub async fn fetch_live_general_table(&self, event_slug: &str) -> Result<ApiResponse> {
-        let url = format!(
-            "{}/evenements-liftcontrol/get-live-data/tableau-general/{}",
-            self.base_url, event_slug
-        );
+        let body = match &self.backend {
+            Backend::Http { config, .. } => {
+                let url = format!(
+                    "{}/evenements-liftcontrol/get-live-data/tableau-general/{}",
+                    config.base_url, event_slug
+                );
+                self.get(&url).await?
+            }
+            Backend::Fixtures(dir) => read_fixture(dir, &format!("{}.json", event_slug)).await?,
+        };
+
+        Ok(serde_json::from_str(&body)?)
+    }
+
+    pub async fn fetch_event_page(&self, base_slug: &str) -> Result<String> {
+        match &self.backend {
+            Backend::Http { config, .. } => {
+                let url = format!("{}/evenements-liftcontrol/{}", config.base_url, base_slug);
+                self.get(&url).await
+            }
+            Backend::Fixtures(dir) => read_fixture(dir, &format!("{}.html", base_slug)).await,
+        }
+    }
+
+    /// Lists the sessions linked from the event page and fetches each of them
+    /// to tell open, closed and empty sessions apart
+    pub async fn discover_sessions(&self, base_slug: &str) -> Result<Vec<DiscoveredSession>> {
+        let html = self.fetch_event_page(base_slug).await?;
+
+        let mut sessions = Vec::new();
+        for sub_slug in parse_session_slugs(&html, base_slug) {
+            let response = self.fetch_live_general_table(&sub_slug).await?;
+            sessions.push(DiscoveredSession::from_response(sub_slug, &response));
+        }
+
+        Ok(sessions)
+    }
+
+    /// GET with rate limiting, retrying 429, 5xx and network errors with exponential backoff
+    async fn get(&self, url: &str) -> Result<String> {
+        let Backend::Http {
+            config,
+            client,
+            last_request,
+        } = &self.backend
+        else {
+            unreachable!("get is only called by the HTTP backend");
+        };
+
+        let mut attempt = 0;
+        loop {
+            self.wait_for_rate_limit(config, last_request).await;
 
-        let response = self.client.get(&url).send().await?;
-        let data = response.json::<ApiResponse>().await?;
+            let delay = config.retry_backoff * 2u32.saturating_pow(attempt);
+            let retries_left = attempt < config.max_retries;
+            attempt += 1;
 
-        Ok(data)
+            match client.get(url).send().await {
+                Ok(response) if is_retryable(response.status()) && retries_left => {
+                    let delay = retry_after(&response).unwrap_or(delay);
+                    tracing::warn!(
+                        "LiftControl answered {} for {}, retrying in {:?}",
+                        response.status(),
+                        url,
+                        delay
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                Ok(response) => return Ok(response.error_for_status()?.text().await?),
+                Err(e) if (e.is_timeout() || e.is_connect()) && retries_left => {
+                    tracing::warn!("Request to {} failed ({}), retrying in {:?}", url, e, delay);
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    async fn wait_for_rate_limit(
+        &self,
+        config: &LiftControlClientConfig,
+        last_request: &Mutex<Option<Instant>>,
+    ) {
+        let mut last_request = last_request.lock().await;
+        if let Some(last) = *last_request {
+            tokio::time::sleep_until(last + config.min_request_interval).await;
+        }
+        *last_request = Some(Instant::now());
     }
 }
 
-impl Default for LiftControlClient {
-    fn default() -> Self {
-        Self::new()
+fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Delay requested by the server, only the delay-seconds form is supported
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+async fn read_fixture(dir: &std::path::Path, name: &str) -> Result<String> {
+    let path = dir.join(name);
+    tokio::fs::read_to_string(&path).await.map_err(|e| {
+        ImporterError::ImportError(format!("Cannot read fixture {}: {}", path.display(), e))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const SESSION: &str =
+        include_str!("../../../fixtures/liftcontrol/annecy-4-lift-2025-dimanche-matin-39.json");
+    const SESSION_PATH: &str = "/evenements-liftcontrol/get-live-data/tableau-general/annecy-4-lift-2025-dimanche-matin-39";
+
+    fn fixtures_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures/liftcontrol")
+    }
+
+    fn stub_client(server: &MockServer) -> LiftControlClient {
+        LiftControlClient::new(LiftControlClientConfig {
+            base_url: server.uri(),
+            retry_backoff: Duration::from_millis(1),
+            min_request_interval: Duration::ZERO,
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_fetch_from_stub_server() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(SESSION_PATH))
+            .respond_with(ResponseTemplate::new(200).set_body_string(SESSION))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let response = stub_client(&server)
+            .fetch_live_general_table("annecy-4-lift-2025-dimanche-matin-39")
+            .await
+            .unwrap();
+
+        assert_eq!(response.contest.id, 39);
+    }
+
+    #[tokio::test]
+    async fn test_retries_server_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(SESSION_PATH))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(SESSION_PATH))
+            .respond_with(ResponseTemplate::new(200).set_body_string(SESSION))
+            .mount(&server)
+            .await;
+
+        let response = stub_client(&server)
+            .fetch_live_general_table("annecy-4-lift-2025-dimanche-matin-39")
+            .await
+            .unwrap();
+
+        assert_eq!(response.contest.id, 39);
+        assert_eq!(server.received_requests().await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_retries() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(429))
+            .mount(&server)
+            .await;
+
+        let error = stub_client(&server)
+            .fetch_live_general_table("annecy-4-lift-2025-dimanche-matin-39")
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(error, ImporterError::RequestError(e) if e.status() == Some(StatusCode::TOO_MANY_REQUESTS))
+        );
+        assert_eq!(server.received_requests().await.unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_are_not_retried() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let error = stub_client(&server)
+            .fetch_live_general_table("unknown-session-1")
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error, ImporterError::RequestError(_)));
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_spaces_requests() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(SESSION))
+            .mount(&server)
+            .await;
+
+        let client = LiftControlClient::new(LiftControlClientConfig {
+            base_url: server.uri(),
+            min_request_interval: Duration::from_millis(100),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let start = Instant::now();
+        for _ in 0..3 {
+            client
+                .fetch_live_general_table("annecy-4-lift-2025-dimanche-matin-39")
+                .await
+                .unwrap();
+        }
+
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+
+    #[tokio::test]
+    async fn test_discover_sessions_from_fixtures() {
+        let sessions = LiftControlClient::from_fixtures(fixtures_dir())
+            .discover_sessions("annecy-4-lift-2025")
+            .await
+            .unwrap();
+
+        let statuses = sessions
+            .iter()
+            .map(|s| s.status.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(statuses, vec!["empty", "closed", "open"]);
+    }
+
+    #[tokio::test]
+    async fn test_missing_fixture() {
+        let error = LiftControlClient::from_fixtures(fixtures_dir())
+            .fetch_live_general_table("unknown-session-1")
+            .await
+            .unwrap_err();
+
+        assert!(error.to_string().contains("unknown-session-1.json"));
     }
 }
@@ -0,0 +1,198 @@
+use super::models::ApiResponse;
+use std::collections::HashSet;
+use std::fmt;
+
+/// Contest statuses LiftControl uses once a session is over, as found in the
+/// recorded responses of `fixtures/liftcontrol/recorded`
+const CLOSED_STATUSES: &[&str] = &["closed"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionStatus {
+    /// Running or not started yet, results may still change
+    Open,
+    /// Finished, results are final
+    Closed,
+    /// No athlete registered, nothing to export
+    Empty,
+}
+
+impl SessionStatus {
+    pub fn from_response(response: &ApiResponse) -> Self {
+        let has_athletes = response
+            .results
+            .results
+            .values()
+            .any(|athletes| !athletes.is_empty());
+
+        if !has_athletes {
+            Self::Empty
+        } else if CLOSED_STATUSES.contains(&response.contest.status.to_lowercase().as_str()) {
+            Self::Closed
+        } else {
+            Self::Open
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Closed => "closed",
+            Self::Empty => "empty",
+        }
+    }
+}
+
+impl fmt::Display for SessionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A session found on the page of a LiftControl event
+#[derive(Debug, Clone)]
+pub struct DiscoveredSession {
+    pub sub_slug: String,
+    pub name: String,
+    pub status: SessionStatus,
+    pub athlete_count: usize,
+}
+
+impl DiscoveredSession {
+    pub fn from_response(sub_slug: impl Into<String>, response: &ApiResponse) -> Self {
+        Self {
+            sub_slug: sub_slug.into(),
+            name: response.contest.name.clone(),
+            status: SessionStatus::from_response(response),
+            athlete_count: response.results.results.values().map(|a| a.len()).sum(),
+        }
+    }
+}
+
+/// Extracts the session sub-slugs linked from an event page, in page order.
+/// A session sub-slug is the base slug followed by the session name and its id
+/// (`annecy-4-lift-2025-dimanche-matin-39`). Only links under the event's own path
+/// (`/annecy-4-lift-2025/{sub_slug}`) are kept, live pages and other events whose
+/// slug starts with the same base are ignored.
+pub fn parse_session_slugs(html: &str, base_slug: &str) -> Vec<String> {
+    let prefix = format!("{}-", base_slug);
+    let mut seen = HashSet::new();
+    let mut slugs = Vec::new();
+
+    for link in html.split("href=\"").skip(1) {
+        let Some(href) = link.split('"').next() else {
+            continue;
+        };
+        let path = href.split(['?', '#']).next().unwrap_or_default();
+
+        let mut segments = path.trim_end_matches('/').rsplit('/');
+        let (Some(segment), Some(parent)) = (segments.next(), segments.next()) else {
+            continue;
+        };
+
+        if parent == base_slug
+            && is_session_slug(segment, &prefix)
+            && seen.insert(segment.to_string())
+        {
+            slugs.push(segment.to_string());
+        }
+    }
+
+    slugs
+}
+
+/// Session sub-slugs end with the numeric LiftControl session id
+fn is_session_slug(segment: &str, prefix: &str) -> bool {
+    segment.strip_prefix(prefix).is_some_and(|rest| {
+        rest.rsplit_once('-').is_some_and(|(name, id)| {
+            !name.is_empty() && !id.is_empty() && id.chars().all(|c| c.is_ascii_digit())
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EVENT_PAGE: &str = include_str!("../../../fixtures/liftcontrol/annecy-4-lift-2025.html");
+
+    fn fixture(name: &str) -> ApiResponse {
+        let json = match name {
+            "open" => include_str!(
+                "../../../fixtures/liftcontrol/annecy-4-lift-2025-dimanche-apres-midi-40.json"
+            ),
+            "closed" => include_str!(
+                "../../../fixtures/liftcontrol/annecy-4-lift-2025-dimanche-matin-39.json"
+            ),
+            "empty" => include_str!(
+                "../../../fixtures/liftcontrol/annecy-4-lift-2025-samedi-matin-37.json"
+            ),
+            _ => unreachable!(),
+        };
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_parse_session_slugs() {
+        let slugs = parse_session_slugs(EVENT_PAGE, "annecy-4-lift-2025");
+
+        assert_eq!(
+            slugs,
+            vec![
+                "annecy-4-lift-2025-samedi-matin-37",
+                "annecy-4-lift-2025-dimanche-matin-39",
+                "annecy-4-lift-2025-dimanche-apres-midi-40",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_session_slugs_unknown_event() {
+        assert!(parse_session_slugs(EVENT_PAGE, "paris-open-2026").is_empty());
+    }
+
+    #[test]
+    fn test_session_status() {
+        assert_eq!(
+            SessionStatus::from_response(&fixture("open")),
+            SessionStatus::Open
+        );
+        assert_eq!(
+            SessionStatus::from_response(&fixture("closed")),
+            SessionStatus::Closed
+        );
+        assert_eq!(
+            SessionStatus::from_response(&fixture("empty")),
+            SessionStatus::Empty
+        );
+    }
+
+    #[test]
+    fn test_recorded_sessions_are_closed() {
+        for json in [
+            include_str!(
+                "../../../fixtures/liftcontrol/recorded/annecy-4-lift-2025-dimanche-matin-39.json"
+            ),
+            include_str!(
+                "../../../fixtures/liftcontrol/recorded/annecy-4-lift-2025-dimanche-apres-midi-40.json"
+            ),
+        ] {
+            let response: ApiResponse = serde_json::from_str(json).unwrap();
+            assert_eq!(
+                SessionStatus::from_response(&response),
+                SessionStatus::Closed
+            );
+        }
+    }
+
+    #[test]
+    fn test_discovered_session_from_response() {
+        let session = DiscoveredSession::from_response(
+            "annecy-4-lift-2025-dimanche-matin-39",
+            &fixture("closed"),
+        );
+
+        assert_eq!(session.name, "Annecy 4 Lift 2025 - Dimanche matin");
+        assert_eq!(session.athlete_count, 1);
+        assert_eq!(session.status.to_string(), "closed");
+    }
+}
@@ -1,8 +1,8 @@
 use super::models as liftcontrol_models;
 use super::movement_mapper::LiftControlMovementMapper;
-use super::spec::CompetitionMetadata;
 use crate::canonical::models as canonical;
 use crate::movement_mapper::MovementMapper;
+use crate::sources::metadata::CompetitionMetadata;
 use crate::{ImporterError, Result};
 use chrono::Utc;
 use rust_decimal::Decimal;
@@ -27,13 +27,14 @@ impl LiftControlExporter {
         api_response: liftcontrol_models::ApiResponse,
     ) -> Result<canonical::CanonicalFormat> {
         Ok(canonical::CanonicalFormat {
-            format_version: "1.0.0".to_string(),
+            format_version: canonical::CANONICAL_FORMAT_VERSION.to_string(),
             source: self.build_source_metadata(&api_response),
-            competition: self.build_competition_data(),
+            competition: self.metadata.to_competition_data(&self.base_slug),
             movements: self.build_movements(&api_response.results.movements)?,
             categories: self.build_categories(&api_response.results)?,
             liftcontrol_metadata: Some(canonical::LiftControlMetadata {
                 contest_id: api_response.contest.id,
+                merged_contest_ids: Vec::new(),
             }),
             pdf_metadata: None,
         })
@@ -55,30 +56,6 @@ impl LiftControlExporter {
         }
     }
 
-    fn build_competition_data(&self) -> canonical::CompetitionData {
-        canonical::CompetitionData {
-            name: self.metadata.name.clone(),
-            slug: self.base_slug.clone(),
-            federation: canonical::FederationData {
-                name: self.metadata.federation.name.clone(),
-                slug: None,
-                abbreviation: Some(self.metadata.federation.abbreviation.clone()),
-                country: Some(self.metadata.federation.country.clone()),
-            },
-            start_date: self.metadata.start_date,
-            end_date: self.metadata.end_date,
-            venue: self.metadata.venue.clone(),
-            city: self.metadata.city.clone(),
-            country: self
-                .metadata
-                .country
-                .clone()
-                .unwrap_or_else(|| "Unknown".to_string()),
-            number_of_judges: self.metadata.number_of_judges,
-            status: Some("completed".to_string()),
-        }
-    }
-
     fn build_movements(
         &self,
         movements: &HashMap<String, liftcontrol_models::Movement>,
@@ -161,7 +138,10 @@ impl LiftControlExporter {
             gender: None,
             country: self.metadata.default_athlete_country.clone(),
             nationality: Some(self.metadata.default_athlete_nationality.clone()),
+            birth_date: None,
+            birth_year: None,
             bodyweight,
+            lot_number: None,
             is_disqualified: Some(athlete_data.athlete_info.is_out),
             disqualified_reason: athlete_data.athlete_info.reason_out.clone(),
             lifts,
@@ -169,7 +149,9 @@ impl LiftControlExporter {
                 athlete_id: athlete_data.athlete_info.id,
                 reglage_dips: athlete_data.athlete_info.reglage_dips.clone(),
                 reglage_squat: athlete_data.athlete_info.reglage_squat.clone(),
+                merged_athlete_ids: Vec::new(),
             }),
+            source_session: None,
         })
     }
 
@@ -185,7 +167,7 @@ impl LiftControlExporter {
 
         let mut attempts = Vec::new();
 
-        for attempt_num in 1..=3 {
+        for attempt_num in 1..=canonical::AttemptKind::RECORD_ATTEMPT_NUMBER {
             if let Some(Some(attempt)) = movement_results.results.get(&attempt_num.to_string()) {
                 attempts.push(self.build_attempt_data(attempt)?);
             }
@@ -194,6 +176,7 @@ impl LiftControlExporter {
         Ok(canonical::LiftData {
             movement: canonical_name.as_str().to_string(),
             attempts,
+            equipment_setting: None,
         })
     }
 
@@ -214,6 +197,7 @@ impl LiftControlExporter {
             weight,
             is_successful,
             no_rep_reason: attempt.justification_no_rep.clone(),
+            kind: canonical::AttemptKind::for_attempt_number(attempt.no_essai as i16),
         })
     }
 }
@@ -271,3 +255,57 @@ fn map_gender(genre: &str) -> String {
         _ => "M".to_string(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canonical::validator::CanonicalValidator;
+    use crate::sources::liftcontrol::{FederationInfo, LiftControlClient};
+    use chrono::NaiveDate;
+    use std::path::PathBuf;
+
+    fn metadata() -> CompetitionMetadata {
+        CompetitionMetadata {
+            name: "Annecy 4 Lift 2025".to_string(),
+            start_date: NaiveDate::from_ymd_opt(2025, 11, 1).unwrap(),
+            end_date: NaiveDate::from_ymd_opt(2025, 11, 2).unwrap(),
+            venue: None,
+            city: Some("Annecy".to_string()),
+            country: Some("France".to_string()),
+            number_of_judges: Some(3),
+            federation: FederationInfo {
+                name: "4Lift".to_string(),
+                abbreviation: "4L".to_string(),
+                country: "FR".to_string(),
+            },
+            default_athlete_country: "FR".to_string(),
+            default_athlete_nationality: "French".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_export_recorded_session() {
+        let client = LiftControlClient::from_fixtures(
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures/liftcontrol"),
+        );
+        let response = client
+            .fetch_live_general_table("annecy-4-lift-2025-dimanche-matin-39")
+            .await
+            .unwrap();
+
+        let canonical = LiftControlExporter::new("annecy-4-lift-2025".to_string(), metadata())
+            .to_canonical(response)
+            .unwrap();
+
+        assert_eq!(canonical.competition.slug, "annecy-4-lift-2025");
+        assert_eq!(canonical.movements[0].name, "Squat");
+        assert_eq!(canonical.categories.len(), 1);
+
+        let athlete = &canonical.categories[0].athletes[0];
+        assert_eq!(athlete.last_name, "Sebastien");
+        assert_eq!(athlete.lifts[0].attempts[0].weight, Decimal::from(140));
+        assert!(athlete.lifts[0].attempts[0].is_successful);
+
+        CanonicalValidator::validate(&canonical).unwrap();
+    }
+}
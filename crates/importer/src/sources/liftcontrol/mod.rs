@@ -1,21 +1,22 @@
 mod client;
+mod discovery;
 mod exporter;
 mod models;
 mod movement_mapper;
 mod spec;
-mod transformer;
 
-pub use client::LiftControlClient;
+pub use crate::sources::metadata::{CompetitionMetadata, FederationInfo};
+pub use client::{
+    DEFAULT_BASE_URL, DEFAULT_USER_AGENT, LiftControlClient, LiftControlClientConfig,
+};
+pub use discovery::{DiscoveredSession, SessionStatus, parse_session_slugs};
 pub use exporter::LiftControlExporter;
 pub use models::*;
 pub use movement_mapper::LiftControlMovementMapper;
-pub use spec::{
-    CompetitionConfig, CompetitionId, CompetitionMetadata, FederationInfo, LiftControlRegistry,
-    LiftControlSpec,
-};
-pub use transformer::LiftControlTransformer;
+pub use spec::{CompetitionConfig, LiftControlRegistry, LiftControlSpec};
 
-use crate::{ImportContext, Result, traits::CompetitionImporter};
+use crate::canonical::models::CanonicalFormat;
+use crate::{Result, traits::CompetitionImporter};
 use tracing::info;
 
 pub struct LiftControlImporter {
@@ -23,16 +24,22 @@ pub struct LiftControlImporter {
 }
 
 impl LiftControlImporter {
-    pub fn new() -> Self {
-        Self {
-            client: LiftControlClient::new(),
-        }
+    pub fn new(client: LiftControlClient) -> Self {
+        Self { client }
     }
-}
 
-impl Default for LiftControlImporter {
-    fn default() -> Self {
-        Self::new()
+    /// Fetches one session and converts it to the canonical format
+    pub async fn export_session(
+        &self,
+        spec: &LiftControlSpec,
+        sub_slug: &str,
+    ) -> Result<CanonicalFormat> {
+        info!("Fetching data for sub-slug: {}", sub_slug);
+        let api_response = self.client.fetch_live_general_table(sub_slug).await?;
+        info!("Competition status: {}", api_response.contest.status);
+
+        LiftControlExporter::new(spec.base_slug().to_string(), spec.metadata().clone())
+            .to_canonical(api_response)
     }
 }
 
@@ -40,34 +47,92 @@ impl Default for LiftControlImporter {
 impl CompetitionImporter for LiftControlImporter {
     type Spec = LiftControlSpec;
 
-    async fn import(&self, spec: &Self::Spec, context: &ImportContext) -> Result<()> {
+    async fn to_canonical(&self, spec: &Self::Spec) -> Result<Vec<CanonicalFormat>> {
         info!(
-            "Importing competition '{}' from {} sub-slugs",
+            "Exporting competition '{}' from {} sub-slugs",
             spec.base_slug(),
             spec.sub_slugs().len()
         );
 
+        let mut documents = Vec::new();
         for sub_slug in spec.sub_slugs() {
             let sub_slug = sub_slug.trim();
             if sub_slug.is_empty() {
                 continue;
             }
-
-            info!("Fetching data for sub-slug: {}", sub_slug);
-            let api_response = self.client.fetch_live_general_table(sub_slug).await?;
-            let transformer = LiftControlTransformer::new(
-                &context.pool,
-                spec.base_slug().to_string(),
-                spec.metadata().clone(),
-            );
-            info!("Competition status: {}", api_response.contest.status);
-            transformer.import_competition(api_response).await?;
+            documents.push(self.export_session(spec, sub_slug).await?);
         }
 
-        Ok(())
+        Ok(documents)
     }
 
     fn name(&self) -> &'static str {
         "LiftControl"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+    use std::path::PathBuf;
+
+    fn repo_path(path: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../..")
+            .join(path)
+    }
+
+    /// Drops the extraction time and sorts categories and athletes, whose order
+    /// comes from the LiftControl JSON maps
+    fn normalize(mut document: Value) -> Value {
+        document["source"]
+            .as_object_mut()
+            .unwrap()
+            .remove("extracted_at");
+
+        let categories = document["categories"].as_array_mut().unwrap();
+        categories.sort_by_key(|c| c["name"].as_str().unwrap().to_string());
+        for category in categories {
+            category["athletes"]
+                .as_array_mut()
+                .unwrap()
+                .sort_by_key(|a| a["liftcontrol_athlete_metadata"]["athlete_id"].as_i64());
+        }
+
+        document
+    }
+
+    /// The recorded Annecy sessions go through the importer and must give back
+    /// the canonical files committed in `imports/`, which are what gets loaded
+    #[tokio::test]
+    async fn test_importer_matches_committed_canonical_files() {
+        let registry = LiftControlRegistry::from_dir(&repo_path("registry/liftcontrol")).unwrap();
+        let spec = registry.get_spec("annecy-4-lift-2025").unwrap();
+        let importer = LiftControlImporter::new(LiftControlClient::from_fixtures(repo_path(
+            "crates/importer/fixtures/liftcontrol/recorded",
+        )));
+
+        let documents = importer.to_canonical(&spec).await.unwrap();
+        assert_eq!(documents.len(), 2);
+
+        for (document, file) in documents.into_iter().zip([
+            "2025-12-07T15-18-10_annecy-4-lift-2025-dimanche-matin-39_liftcontrol.json",
+            "2025-12-07T15-18-11_annecy-4-lift-2025-dimanche-apres-midi-40_liftcontrol.json",
+        ]) {
+            let committed: Value = serde_json::from_str(
+                &std::fs::read_to_string(repo_path("imports/annecy-4-lift-2025").join(file))
+                    .unwrap(),
+            )
+            .unwrap();
+            let committed = crate::canonical::upgrade::upgrade(committed).unwrap();
+
+            assert_eq!(
+                normalize(serde_json::to_value(&document).unwrap()),
+                normalize(committed),
+                "{}",
+                file
+            );
+        }
+    }
+}
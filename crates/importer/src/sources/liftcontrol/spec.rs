@@ -1,50 +1,9 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 
-use crate::ImporterError;
-use chrono::NaiveDate;
-
-/// Metadata for a competition that cannot be inferred from the API
-#[derive(Debug, Clone)]
-pub struct CompetitionMetadata {
-    pub name: String,
-    pub start_date: NaiveDate,
-    pub end_date: NaiveDate,
-    pub venue: Option<String>,
-    pub city: Option<String>,
-    pub country: Option<String>,
-    pub number_of_judges: Option<i16>,
-    pub federation: FederationInfo,
-    pub default_athlete_country: String,
-    pub default_athlete_nationality: String,
-}
-
-#[derive(Debug, Clone)]
-pub struct FederationInfo {
-    pub name: String,
-    pub abbreviation: String,
-    pub country: String,
-}
-
-impl CompetitionMetadata {
-    pub fn annecy_4lift_2025() -> Self {
-        Self {
-            name: "Annecy 4 Lift 2025".to_string(),
-            start_date: NaiveDate::from_ymd_opt(2025, 11, 1).unwrap(),
-            end_date: NaiveDate::from_ymd_opt(2025, 11, 2).unwrap(),
-            venue: Some("Oski Crossfit".to_string()),
-            city: Some("Annecy".to_string()),
-            country: Some("France".to_string()),
-            number_of_judges: Some(3),
-            federation: FederationInfo {
-                name: "4Lift".to_string(),
-                abbreviation: "4L".to_string(),
-                country: "FR".to_string(),
-            },
-            default_athlete_country: "FR".to_string(),
-            default_athlete_nationality: "French".to_string(),
-        }
-    }
-}
+use crate::sources::metadata::{CompetitionMetadata, is_slug};
+use crate::{ImporterError, Result};
+use serde::Deserialize;
 
 #[derive(Debug, Clone)]
 pub struct LiftControlSpec {
@@ -78,6 +37,12 @@ impl LiftControlSpec {
         &self.metadata
     }
 
+    /// Replaces the configured sessions, e.g. with the ones found by discovery
+    pub fn with_sub_slugs(mut self, sub_slugs: Vec<String>) -> Self {
+        self.sub_slugs = sub_slugs;
+        self
+    }
+
     pub fn from_config(config: &CompetitionConfig) -> Self {
         Self {
             base_slug: config.base_slug.clone(),
@@ -87,178 +52,279 @@ impl LiftControlSpec {
     }
 }
 
-#[derive(Debug, Clone)]
+/// One competition of the registry, as described by a TOML or YAML file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct CompetitionConfig {
-    pub id: CompetitionId,
     pub base_slug: String,
     pub sub_slugs: Vec<String>,
+    /// Alternative names accepted on the command line
+    #[serde(default)]
+    pub aliases: Vec<String>,
     pub metadata: CompetitionMetadata,
 }
 
 impl CompetitionConfig {
-    pub fn new(
-        id: CompetitionId,
-        base_slug: impl Into<String>,
-        sub_slugs: Vec<String>,
-        metadata: CompetitionMetadata,
-    ) -> Self {
-        Self {
-            id,
-            base_slug: base_slug.into(),
-            sub_slugs,
-            metadata,
-        }
-    }
-}
+    /// Reads and validates a competition file, the format is picked from its extension
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            ImporterError::ValidationError(format!("{}: cannot read file: {}", path.display(), e))
+        })?;
+
+        let config = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml_str(&content),
+            Some("yaml") | Some("yml") => Self::from_yaml_str(&content),
+            _ => Err(ImporterError::ValidationError(
+                "unsupported file extension, expected .toml, .yaml or .yml".to_string(),
+            )),
+        };
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum CompetitionId {
-    Annecy4Lift2025,
-}
+        config.map_err(|e| with_path_context(path, e))
+    }
 
-impl CompetitionId {
-    pub fn as_str(&self) -> &'static str {
-        match self {
-            Self::Annecy4Lift2025 => "annecy-4-lift-2025",
-        }
+    pub fn from_toml_str(content: &str) -> Result<Self> {
+        let config: Self =
+            toml::from_str(content).map_err(|e| ImporterError::ValidationError(e.to_string()))?;
+        config.validate()?;
+        Ok(config)
     }
 
-    pub fn all() -> &'static [CompetitionId] {
-        &[Self::Annecy4Lift2025]
+    pub fn from_yaml_str(content: &str) -> Result<Self> {
+        let config: Self = serde_yaml::from_str(content)
+            .map_err(|e| ImporterError::ValidationError(e.to_string()))?;
+        config.validate()?;
+        Ok(config)
     }
 
-    fn parse_str(s: &str) -> Result<Self, ImporterError> {
-        let normalized = s.to_lowercase().replace('_', "-");
-        match normalized.as_str() {
-            "annecy-4-lift-2025" | "annecy4lift2025" | "annecy" => Ok(Self::Annecy4Lift2025),
-            _ => Err(ImporterError::ImportError(format!(
-                "Unknown competition: '{}'. Available: {}",
-                s,
-                Self::all()
-                    .iter()
-                    .map(|c| c.as_str())
-                    .collect::<Vec<_>>()
-                    .join(", ")
-            ))),
+    /// Checks the values serde cannot, reporting every problem at once
+    pub fn validate(&self) -> Result<()> {
+        let mut errors = Vec::new();
+
+        if !is_slug(&self.base_slug) {
+            errors.push(format!(
+                "base_slug '{}' must be lowercase letters, digits and dashes",
+                self.base_slug
+            ));
         }
-    }
-}
 
-impl TryFrom<&str> for CompetitionId {
-    type Error = ImporterError;
+        if self.sub_slugs.is_empty() {
+            errors.push("sub_slugs must list at least one session".to_string());
+        }
+        let mut seen = HashSet::new();
+        for sub_slug in &self.sub_slugs {
+            if sub_slug.trim().is_empty() {
+                errors.push("sub_slugs must not contain empty entries".to_string());
+            } else if !seen.insert(sub_slug.trim()) {
+                errors.push(format!("sub_slug '{}' is listed twice", sub_slug));
+            }
+        }
 
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        Self::parse_str(value)
-    }
-}
+        for alias in &self.aliases {
+            if normalize_name(alias).is_empty() {
+                errors.push("aliases must not contain empty entries".to_string());
+            }
+        }
 
-impl std::str::FromStr for CompetitionId {
-    type Err = ImporterError;
+        self.metadata.check(&mut errors);
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Self::parse_str(s)
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ImporterError::ValidationError(errors.join("; ")))
+        }
     }
 }
 
-// Implement Display for pretty printing
-impl std::fmt::Display for CompetitionId {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.as_str())
-    }
+fn with_path_context(path: &Path, error: ImporterError) -> ImporterError {
+    let message = match error {
+        ImporterError::ValidationError(message) => message,
+        other => other.to_string(),
+    };
+    ImporterError::ValidationError(format!("{}: {}", path.display(), message))
 }
 
-/// Registry of predefined LiftControl competitions.
-/// This provides a central place to define all importable competitions
-/// with their configuration (base slug + sub-slugs).
+/// Lookup key for names typed on the command line ("Annecy_4_Lift_2025" -> "annecy-4-lift-2025")
+fn normalize_name(name: &str) -> String {
+    name.trim().to_lowercase().replace('_', "-")
+}
+
+/// Registry of importable LiftControl competitions.
+/// Each competition is described by its own TOML or YAML file, so new meets can
+/// be added without touching the importer code.
+#[derive(Debug, Default)]
 pub struct LiftControlRegistry {
-    competitions: HashMap<CompetitionId, CompetitionConfig>,
+    competitions: HashMap<String, CompetitionConfig>,
+    /// Normalized base slug or alias -> base slug
+    names: HashMap<String, String>,
 }
 
 impl LiftControlRegistry {
     pub fn new() -> Self {
-        let mut registry = Self {
-            competitions: HashMap::new(),
-        };
+        Self::default()
+    }
 
-        // Register Annecy 4 Lift 2025
-        // has two open session (rest of for the moment closed)
-        registry.register(CompetitionConfig::new(
-            CompetitionId::Annecy4Lift2025,
-            "annecy-4-lift-2025",
-            vec![
-                "annecy-4-lift-2025-dimanche-matin-39".to_string(),
-                "annecy-4-lift-2025-dimanche-apres-midi-40".to_string(),
-            ],
-            CompetitionMetadata::annecy_4lift_2025(),
-        ));
+    /// Loads every `.toml`, `.yaml` and `.yml` file of a directory.
+    /// Fails with the errors of all invalid files rather than the first one.
+    pub fn from_dir(dir: &Path) -> Result<Self> {
+        let entries = std::fs::read_dir(dir).map_err(|e| {
+            ImporterError::ValidationError(format!(
+                "Cannot read LiftControl registry {}: {}",
+                dir.display(),
+                e
+            ))
+        })?;
+
+        let mut paths = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| matches!(ext, "toml" | "yaml" | "yml"))
+            })
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        let mut registry = Self::new();
+        let mut errors = Vec::new();
+
+        for path in paths {
+            let result = CompetitionConfig::from_file(&path).and_then(|config| {
+                registry
+                    .register(config)
+                    .map_err(|e| with_path_context(&path, e))
+            });
+
+            match result {
+                Ok(()) => {}
+                Err(ImporterError::ValidationError(e)) => errors.push(e),
+                Err(e) => errors.push(e.to_string()),
+            }
+        }
 
-        registry
+        if errors.is_empty() {
+            Ok(registry)
+        } else {
+            Err(ImporterError::ValidationError(errors.join("\n")))
+        }
     }
 
-    fn register(&mut self, config: CompetitionConfig) {
-        self.competitions.insert(config.id, config);
-    }
+    /// Adds a competition, rejecting base slugs or aliases already in use
+    pub fn register(&mut self, config: CompetitionConfig) -> Result<()> {
+        let mut names = vec![normalize_name(&config.base_slug)];
+        names.extend(config.aliases.iter().map(|a| normalize_name(a)));
+
+        for name in &names {
+            if let Some(existing) = self.names.get(name) {
+                return Err(ImporterError::ValidationError(format!(
+                    "'{}' is already used by competition '{}'",
+                    name, existing
+                )));
+            }
+        }
 
-    pub fn get_config(&self, id: CompetitionId) -> Option<&CompetitionConfig> {
-        self.competitions.get(&id)
+        for name in names {
+            self.names.insert(name, config.base_slug.clone());
+        }
+        self.competitions.insert(config.base_slug.clone(), config);
+
+        Ok(())
     }
 
-    pub fn list_competitions(&self) -> Vec<CompetitionId> {
-        self.competitions.keys().copied().collect()
+    /// Finds a competition by base slug or alias, ignoring case and `_`/`-` differences
+    pub fn get_config(&self, name: &str) -> Option<&CompetitionConfig> {
+        self.names
+            .get(&normalize_name(name))
+            .and_then(|slug| self.competitions.get(slug))
     }
 
-    pub fn get_spec(&self, id: CompetitionId) -> Option<LiftControlSpec> {
-        self.get_config(id).map(LiftControlSpec::from_config)
+    /// Base slugs of all registered competitions, sorted
+    pub fn list_competitions(&self) -> Vec<&str> {
+        let mut slugs = self
+            .competitions
+            .keys()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        slugs.sort_unstable();
+        slugs
     }
-}
 
-impl Default for LiftControlRegistry {
-    fn default() -> Self {
-        Self::new()
+    pub fn get_spec(&self, name: &str) -> Option<LiftControlSpec> {
+        self.get_config(name).map(LiftControlSpec::from_config)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::path::PathBuf;
+
+    const ANNECY_TOML: &str = r#"
+base_slug = "annecy-4-lift-2025"
+sub_slugs = [
+    "annecy-4-lift-2025-dimanche-matin-39",
+    "annecy-4-lift-2025-dimanche-apres-midi-40",
+]
+aliases = ["annecy", "annecy4lift2025"]
+
+[metadata]
+name = "Annecy 4 Lift 2025"
+start_date = "2025-11-01"
+end_date = "2025-11-02"
+venue = "Oski Crossfit"
+city = "Annecy"
+country = "France"
+number_of_judges = 3
+default_athlete_country = "FR"
+default_athlete_nationality = "French"
+
+[metadata.federation]
+name = "4Lift"
+abbreviation = "4L"
+country = "FR"
+"#;
+
+    fn annecy_registry() -> LiftControlRegistry {
+        let mut registry = LiftControlRegistry::new();
+        registry
+            .register(CompetitionConfig::from_toml_str(ANNECY_TOML).unwrap())
+            .unwrap();
+        registry
+    }
 
     #[test]
-    fn test_competition_id_parsing() {
-        use std::str::FromStr;
-
-        let result = CompetitionId::try_from("annecy-4-lift-2025");
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), CompetitionId::Annecy4Lift2025);
-
-        let result = CompetitionId::from_str("ANNECY");
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), CompetitionId::Annecy4Lift2025);
+    fn test_competition_lookup_by_slug_or_alias() {
+        let registry = annecy_registry();
 
-        let result = "annecy".parse::<CompetitionId>();
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), CompetitionId::Annecy4Lift2025);
-
-        assert!(CompetitionId::from_str("annecy4lift2025").is_ok());
-        assert!(CompetitionId::try_from("Annecy-4-Lift-2025").is_ok());
+        for name in [
+            "annecy-4-lift-2025",
+            "Annecy-4-Lift-2025",
+            "ANNECY",
+            "annecy4lift2025",
+            "annecy_4_lift_2025",
+        ] {
+            let config = registry.get_config(name).unwrap();
+            assert_eq!(config.base_slug, "annecy-4-lift-2025");
+        }
 
-        assert!(CompetitionId::from_str("unknown").is_err());
-        assert!(CompetitionId::try_from("invalid").is_err());
-        assert!("paris".parse::<CompetitionId>().is_err());
+        assert!(registry.get_config("unknown").is_none());
+        assert!(registry.get_config("paris").is_none());
     }
 
     #[test]
     fn test_registry_get_config() {
-        let registry = LiftControlRegistry::new();
-        let config = registry.get_config(CompetitionId::Annecy4Lift2025).unwrap();
+        let registry = annecy_registry();
+        let config = registry.get_config("annecy-4-lift-2025").unwrap();
 
         assert_eq!(config.base_slug, "annecy-4-lift-2025");
         assert_eq!(config.sub_slugs.len(), 2);
+        assert_eq!(config.metadata.federation.abbreviation, "4L");
+        assert_eq!(config.metadata.default_athlete_country, "FR");
     }
 
     #[test]
     fn test_create_spec_from_registry() {
-        let registry = LiftControlRegistry::new();
-        let spec = registry.get_spec(CompetitionId::Annecy4Lift2025).unwrap();
+        let registry = annecy_registry();
+        let spec = registry.get_spec("annecy-4-lift-2025").unwrap();
 
         assert_eq!(spec.base_slug(), "annecy-4-lift-2025");
         assert_eq!(spec.sub_slugs().len(), 2);
@@ -266,10 +332,87 @@ mod tests {
 
     #[test]
     fn test_list_competitions() {
-        let registry = LiftControlRegistry::new();
+        let registry = annecy_registry();
         let competitions = registry.list_competitions();
 
-        assert!(!competitions.is_empty());
-        assert!(competitions.contains(&CompetitionId::Annecy4Lift2025));
+        assert_eq!(competitions, vec!["annecy-4-lift-2025"]);
+    }
+
+    #[test]
+    fn test_yaml_config() {
+        let yaml = r#"
+base_slug: paris-open-2026
+sub_slugs:
+  - paris-open-2026-samedi-12
+metadata:
+  name: Paris Open 2026
+  start_date: "2026-03-14"
+  end_date: "2026-03-14"
+  federation:
+    name: 4Lift
+    abbreviation: 4L
+    country: FR
+  default_athlete_country: FR
+  default_athlete_nationality: French
+"#;
+        let config = CompetitionConfig::from_yaml_str(yaml).unwrap();
+
+        assert_eq!(config.base_slug, "paris-open-2026");
+        assert!(config.aliases.is_empty());
+        assert!(config.metadata.venue.is_none());
+    }
+
+    #[test]
+    fn test_invalid_config_reports_every_problem() {
+        let toml = ANNECY_TOML
+            .replace("\"2025-11-02\"", "\"2025-10-31\"")
+            .replace("number_of_judges = 3", "number_of_judges = 2")
+            .replace(
+                "base_slug = \"annecy-4-lift-2025\"",
+                "base_slug = \"Annecy 2025\"",
+            );
+
+        let error = CompetitionConfig::from_toml_str(&toml)
+            .unwrap_err()
+            .to_string();
+
+        assert!(error.contains("base_slug"));
+        assert!(error.contains("end_date"));
+        assert!(error.contains("number_of_judges"));
+    }
+
+    #[test]
+    fn test_unknown_and_missing_fields_are_rejected() {
+        let unknown = ANNECY_TOML.replace("venue =", "location =");
+        assert!(CompetitionConfig::from_toml_str(&unknown).is_err());
+
+        let missing = ANNECY_TOML.replace("default_athlete_country = \"FR\"", "");
+        assert!(CompetitionConfig::from_toml_str(&missing).is_err());
+
+        let bad_date = ANNECY_TOML.replace("\"2025-11-01\"", "\"01/11/2025\"");
+        assert!(CompetitionConfig::from_toml_str(&bad_date).is_err());
+    }
+
+    #[test]
+    fn test_duplicate_names_are_rejected() {
+        let mut registry = annecy_registry();
+        let other = ANNECY_TOML.replace(
+            "base_slug = \"annecy-4-lift-2025\"",
+            "base_slug = \"annecy-4-lift-2026\"",
+        );
+
+        let error = registry
+            .register(CompetitionConfig::from_toml_str(&other).unwrap())
+            .unwrap_err();
+
+        assert!(error.to_string().contains("annecy"));
+    }
+
+    #[test]
+    fn test_shipped_registry_is_valid() {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../registry/liftcontrol");
+        let registry = LiftControlRegistry::from_dir(&dir).unwrap();
+
+        assert!(registry.get_config("annecy-4-lift-2025").is_some());
     }
 }
@@ -0,0 +1,103 @@
+use crate::Result;
+use crate::canonical::models as canonical;
+use sqlx::PgPool;
+use storage::repository::{athlete::AthleteRepository, federation::FederationRepository};
+
+/// Number of athletes or federations offered for one answer
+const MAX_SUGGESTIONS: usize = 5;
+
+/// An athlete already in the database
+#[derive(Debug, Clone)]
+pub struct KnownAthlete {
+    pub first_name: String,
+    pub last_name: String,
+    pub gender: String,
+    pub country: String,
+    pub nationality: Option<String>,
+}
+
+/// Athletes and federations offered while typing, so that a manual entry reuses the
+/// spelling already stored instead of creating a near-duplicate
+#[derive(Debug, Clone, Default)]
+pub struct KnownEntities {
+    pub athletes: Vec<KnownAthlete>,
+    pub federations: Vec<canonical::FederationData>,
+}
+
+impl KnownEntities {
+    pub async fn load(pool: &PgPool) -> Result<Self> {
+        let athletes = AthleteRepository::new(pool)
+            .list()
+            .await?
+            .into_iter()
+            .map(|athlete| KnownAthlete {
+                first_name: athlete.first_name,
+                last_name: athlete.last_name,
+                gender: athlete.gender,
+                country: athlete.country,
+                nationality: athlete.nationality,
+            })
+            .collect();
+
+        let federations = FederationRepository::new(pool)
+            .list()
+            .await?
+            .into_iter()
+            .map(|federation| canonical::FederationData {
+                name: federation.name,
+                slug: None,
+                abbreviation: federation.abbreviation,
+                country: federation.country,
+            })
+            .collect();
+
+        Ok(Self {
+            athletes,
+            federations,
+        })
+    }
+
+    /// Athletes whose names start with every word of the query, in any order:
+    /// `dup`, `jean d` and `DUPONT Jean` all find Jean Dupont
+    pub fn find_athletes(&self, query: &str) -> Vec<&KnownAthlete> {
+        let words = query
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>();
+        if words.is_empty() {
+            return Vec::new();
+        }
+
+        self.athletes
+            .iter()
+            .filter(|athlete| {
+                let names = format!("{} {}", athlete.first_name, athlete.last_name).to_lowercase();
+                let names = names.split_whitespace().collect::<Vec<_>>();
+                words
+                    .iter()
+                    .all(|word| names.iter().any(|name| name.starts_with(word.as_str())))
+            })
+            .take(MAX_SUGGESTIONS)
+            .collect()
+    }
+
+    /// Federations whose abbreviation is the query or whose name contains it
+    pub fn find_federations(&self, query: &str) -> Vec<&canonical::FederationData> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        self.federations
+            .iter()
+            .filter(|federation| {
+                federation
+                    .abbreviation
+                    .as_deref()
+                    .is_some_and(|abbreviation| abbreviation.to_lowercase() == query)
+                    || federation.name.to_lowercase().contains(&query)
+            })
+            .take(MAX_SUGGESTIONS)
+            .collect()
+    }
+}
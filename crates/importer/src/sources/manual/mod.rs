@@ -0,0 +1,568 @@
+//! Results typed in by hand, for competitions whose results only exist on paper or in
+//! a format no source reads. The entry is a question-and-answer session that builds
+//! the canonical document section by section.
+
+mod known;
+mod prompt;
+
+pub use known::{KnownAthlete, KnownEntities};
+pub use prompt::Prompter;
+
+use crate::canonical::models as canonical;
+use crate::canonical::validator::{CanonicalValidator, Severity};
+use crate::movement_mapper::MovementMapper;
+use crate::sources::metadata::is_slug;
+use crate::sources::table::{TableMovementMapper, parse_attempt, parse_weight_class, split_name};
+use crate::{CanonicalMovement, Result};
+use chrono::{NaiveDate, Utc};
+use rust_decimal::Decimal;
+use std::io::{BufRead, Write};
+use storage::normalize;
+
+pub const MANUAL_EXTRACTOR: &str = "manual-cli-v1";
+
+/// Asks for the competition, its movements, then categories with their athletes and
+/// attempts. Each section is checked with [`CanonicalValidator`] once entered: errors
+/// make it asked again, warnings are shown and can be accepted.
+pub struct ManualEntry<'a, R, W> {
+    prompt: Prompter<R, W>,
+    known: &'a KnownEntities,
+}
+
+impl<'a, R: BufRead, W: Write> ManualEntry<'a, R, W> {
+    pub fn new(prompt: Prompter<R, W>, known: &'a KnownEntities) -> Self {
+        Self { prompt, known }
+    }
+
+    pub fn run(&mut self) -> Result<canonical::CanonicalFormat> {
+        self.prompt.say("== Competition")?;
+        let mut document = canonical::CanonicalFormat {
+            format_version: canonical::CANONICAL_FORMAT_VERSION.to_string(),
+            source: canonical::SourceMetadata {
+                r#type: canonical::SourceType::Manual,
+                url: None,
+                extracted_at: Utc::now(),
+                extractor: MANUAL_EXTRACTOR.to_string(),
+                original_filename: None,
+            },
+            competition: self.competition()?,
+            movements: Vec::new(),
+            categories: Vec::new(),
+            liftcontrol_metadata: None,
+            pdf_metadata: None,
+        };
+        while !self.review(&document, "/competition", &[])? {
+            document.competition = self.competition()?;
+        }
+
+        self.prompt.say("== Movements")?;
+        document.movements = self.movements()?;
+        while !self.review(&document, "/movements", &[])? {
+            document.movements = self.movements()?;
+        }
+
+        while self
+            .prompt
+            .confirm("Add a category", document.categories.is_empty())?
+        {
+            self.prompt.say("== Category")?;
+            let c = document.categories.len();
+            let category_path = format!("/categories/{}", c);
+            document.categories.push(self.category()?);
+            // Its athletes are entered next
+            while !self.review(&document, &category_path, &["empty_category"])? {
+                document.categories[c] = self.category()?;
+            }
+
+            while self.prompt.confirm(
+                &format!(
+                    "Add an athlete to {}",
+                    category_label(&document.categories[c])
+                ),
+                true,
+            )? {
+                let a = document.categories[c].athletes.len();
+                let athlete_path = format!("{}/athletes/{}", category_path, a);
+                let athlete = self.athlete(&document, c)?;
+                document.categories[c].athletes.push(athlete);
+                while !self.review(&document, &athlete_path, &[])? {
+                    document.categories[c].athletes[a] = self.athlete(&document, c)?;
+                }
+            }
+        }
+
+        Ok(document)
+    }
+
+    fn competition(&mut self) -> Result<canonical::CompetitionData> {
+        let name = self.prompt.text("Name", None)?;
+        let slug = loop {
+            let slug = self.prompt.text("Slug", Some(&normalize::slugify(&name)))?;
+            if is_slug(&slug) {
+                break slug;
+            }
+            self.prompt
+                .say("  A slug is made of lowercase letters, digits and dashes")?;
+        };
+
+        let federation = self.federation()?;
+        let start_date: NaiveDate = self.prompt.parse("Start date (YYYY-MM-DD)", None)?;
+        let end_date = self
+            .prompt
+            .parse("End date (YYYY-MM-DD)", Some(&start_date.to_string()))?;
+        let venue = self.prompt.optional_text("Venue", None)?;
+        let city = self.prompt.optional_text("City", None)?;
+        let country = self.prompt.text("Country", federation.country.as_deref())?;
+        let number_of_judges = self
+            .prompt
+            .optional_parse("Number of judges (1 or 3)", None)?;
+
+        Ok(canonical::CompetitionData {
+            name,
+            slug,
+            federation,
+            start_date,
+            end_date,
+            venue,
+            city,
+            country,
+            number_of_judges,
+            status: Some("completed".to_string()),
+        })
+    }
+
+    fn federation(&mut self) -> Result<canonical::FederationData> {
+        let query = self
+            .prompt
+            .text("Federation (name or abbreviation)", None)?;
+
+        let matches = self.known.find_federations(&query);
+        if !matches.is_empty() {
+            for (i, federation) in matches.iter().enumerate() {
+                let abbreviation = federation.abbreviation.as_deref().unwrap_or("-");
+                self.prompt.say(format!(
+                    "  {}. {} ({})",
+                    i + 1,
+                    federation.name,
+                    abbreviation
+                ))?;
+            }
+            self.prompt.say("  0. New federation")?;
+            if let Some(known) = self.choose(&matches)? {
+                return Ok(known.clone());
+            }
+        }
+
+        Ok(canonical::FederationData {
+            name: self.prompt.text("Federation name", Some(&query))?,
+            slug: None,
+            abbreviation: self.prompt.optional_text("Federation abbreviation", None)?,
+            country: self.prompt.optional_text("Federation country", None)?,
+        })
+    }
+
+    fn movements(&mut self) -> Result<Vec<canonical::MovementData>> {
+        let default = [
+            CanonicalMovement::MuscleUp,
+            CanonicalMovement::PullUp,
+            CanonicalMovement::Dips,
+            CanonicalMovement::Squat,
+        ]
+        .map(|movement| movement.as_str())
+        .join(", ");
+        let answer = self
+            .prompt
+            .text("Movements, in order and comma separated", Some(&default))?;
+
+        Ok(answer
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .enumerate()
+            .map(|(i, name)| canonical::MovementData {
+                name: TableMovementMapper
+                    .map_movement(name)
+                    .map_or_else(|| name.to_string(), |m| m.as_str().to_string()),
+                order: i as i16 + 1,
+                is_required: Some(true),
+            })
+            .collect())
+    }
+
+    fn category(&mut self) -> Result<canonical::CategoryData> {
+        let (name, weight_class_min, weight_class_max) = loop {
+            let answer = self.prompt.text("Weight class (e.g. -80 or +80)", None)?;
+            if let Some(weight_class) = parse_weight_class(&answer.to_lowercase().replace(' ', ""))
+            {
+                break weight_class;
+            }
+            self.prompt
+                .say("  Expected -<max> or +<min> in kg, e.g. -80")?;
+        };
+
+        let gender = loop {
+            let answer = self.prompt.text("Gender (M/F)", None)?.to_uppercase();
+            if answer == "M" || answer == "F" {
+                break answer;
+            }
+            self.prompt.say("  Answer M or F")?;
+        };
+
+        Ok(canonical::CategoryData {
+            name,
+            gender,
+            weight_class_min,
+            weight_class_max,
+            athletes: Vec::new(),
+        })
+    }
+
+    fn athlete(
+        &mut self,
+        document: &canonical::CanonicalFormat,
+        category: usize,
+    ) -> Result<canonical::AthleteData> {
+        let gender = document.categories[category].gender.clone();
+        let query = self.prompt.text("Athlete (first and last name)", None)?;
+
+        let known = self.known;
+        let matches = known
+            .find_athletes(&query)
+            .into_iter()
+            .filter(|athlete| athlete.gender == gender)
+            .collect::<Vec<_>>();
+        let mut chosen = None;
+        if !matches.is_empty() {
+            for (i, athlete) in matches.iter().enumerate() {
+                self.prompt.say(format!(
+                    "  {}. {} {} ({})",
+                    i + 1,
+                    athlete.first_name,
+                    athlete.last_name,
+                    athlete.country
+                ))?;
+            }
+            self.prompt.say("  0. New athlete")?;
+            chosen = self.choose(&matches)?;
+        }
+
+        let (first_name, last_name, country, nationality) = match chosen {
+            Some(athlete) => (
+                athlete.first_name.clone(),
+                athlete.last_name.clone(),
+                athlete.country.clone(),
+                athlete.nationality.clone(),
+            ),
+            None => {
+                let (first, last, _) =
+                    split_name(&query).unwrap_or((query.clone(), String::new(), false));
+                let first_name = self.prompt.text("First name", Some(&first))?;
+                let last_name = self.prompt.text("Last name", Some(&last))?;
+                let country = self
+                    .prompt
+                    .text("Country", Some(&document.competition.country))?;
+                let nationality = self.prompt.optional_text("Nationality", None)?;
+                (first_name, last_name, country, nationality)
+            }
+        };
+
+        let bodyweight: Option<Decimal> = self.prompt.optional_parse("Bodyweight (kg)", None)?;
+        let lot_number: Option<i32> = self.prompt.optional_parse("Lot number", None)?;
+        let is_disqualified = self.prompt.confirm("Disqualified", false)?;
+        let disqualified_reason = if is_disqualified {
+            self.prompt.optional_text("Disqualification reason", None)?
+        } else {
+            None
+        };
+
+        let mut lifts = Vec::new();
+        for movement in &document.movements {
+            if let Some(attempts) = self.attempts(&movement.name)? {
+                lifts.push(canonical::LiftData {
+                    movement: movement.name.clone(),
+                    attempts,
+                    equipment_setting: None,
+                });
+            }
+        }
+
+        Ok(canonical::AthleteData {
+            first_name,
+            last_name,
+            gender: Some(gender),
+            country,
+            nationality,
+            birth_date: None,
+            birth_year: None,
+            bodyweight,
+            lot_number,
+            is_disqualified: Some(is_disqualified),
+            disqualified_reason,
+            lifts,
+            liftcontrol_athlete_metadata: None,
+            source_session: None,
+        })
+    }
+
+    /// Reads the attempts of one movement written as on a score sheet, `20 25 x27.5`:
+    /// a miss is marked with `x` and a skipped attempt with `-`
+    fn attempts(&mut self, movement: &str) -> Result<Option<Vec<canonical::AttemptData>>> {
+        let label = format!(
+            "{} attempts (e.g. 20 25 x27.5, a fourth for a record attempt, empty if none)",
+            movement
+        );
+        'ask: loop {
+            let Some(answer) = self.prompt.optional_text(&label, None)? else {
+                return Ok(None);
+            };
+
+            let mut attempts = Vec::new();
+            for (i, cell) in answer.split_whitespace().enumerate() {
+                match parse_attempt(cell) {
+                    Ok(Some((weight, is_successful))) => {
+                        let attempt_number = i as i16 + 1;
+                        attempts.push(canonical::AttemptData {
+                            attempt_number,
+                            weight,
+                            is_successful,
+                            no_rep_reason: None,
+                            kind: canonical::AttemptKind::for_attempt_number(attempt_number),
+                        })
+                    }
+                    Ok(None) => {}
+                    Err(()) => {
+                        self.prompt
+                            .say(format!("  Cannot read attempt '{}'", cell))?;
+                        continue 'ask;
+                    }
+                }
+            }
+            return Ok(Some(attempts));
+        }
+    }
+
+    /// Reads the number of a listed suggestion, `None` for the `0` entry
+    fn choose<'b, T>(&mut self, options: &[&'b T]) -> Result<Option<&'b T>> {
+        loop {
+            let choice: usize = self.prompt.parse("Choice", Some("1"))?;
+            if choice == 0 {
+                return Ok(None);
+            }
+            if let Some(option) = options.get(choice - 1) {
+                return Ok(Some(*option));
+            }
+            self.prompt
+                .say(format!("  Choose between 0 and {}", options.len()))?;
+        }
+    }
+
+    /// Shows the issues the validator finds under `path`, ignoring the `skip` codes.
+    /// Returns whether the section is kept: never with errors, on confirmation with
+    /// warnings only.
+    fn review(
+        &mut self,
+        document: &canonical::CanonicalFormat,
+        path: &str,
+        skip: &[&str],
+    ) -> Result<bool> {
+        let report = CanonicalValidator::check(document);
+        let issues = report
+            .issues
+            .iter()
+            .filter(|issue| issue.path == path || issue.path.starts_with(&format!("{}/", path)))
+            .filter(|issue| !skip.contains(&issue.code))
+            .collect::<Vec<_>>();
+
+        for issue in &issues {
+            let severity = match issue.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            };
+            self.prompt.say(format!("  {}: {}", severity, issue))?;
+        }
+
+        if issues.iter().any(|issue| issue.severity == Severity::Error) {
+            self.prompt.say("  Please enter it again")?;
+            return Ok(false);
+        }
+        if issues.is_empty() {
+            return Ok(true);
+        }
+        self.prompt.confirm("Keep it anyway", true)
+    }
+}
+
+fn category_label(category: &canonical::CategoryData) -> String {
+    format!("{} {}", category.gender, category.name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn known() -> KnownEntities {
+        KnownEntities {
+            athletes: vec![KnownAthlete {
+                first_name: "Jean".to_string(),
+                last_name: "Dupont".to_string(),
+                gender: "M".to_string(),
+                country: "BE".to_string(),
+                nationality: Some("Belgian".to_string()),
+            }],
+            federations: vec![canonical::FederationData {
+                name: "Fédération Française de Streetlifting".to_string(),
+                slug: None,
+                abbreviation: Some("FFSL".to_string()),
+                country: Some("FR".to_string()),
+            }],
+        }
+    }
+
+    fn run(answers: &[&str]) -> (Result<canonical::CanonicalFormat>, String) {
+        let input = answers
+            .iter()
+            .map(|a| format!("{}\n", a))
+            .collect::<String>();
+        let known = known();
+        let mut entry = ManualEntry::new(
+            Prompter::new(Cursor::new(input.into_bytes()), Vec::new()),
+            &known,
+        );
+        let result = entry.run();
+        let output = String::from_utf8(entry.prompt.into_output()).unwrap();
+        (result, output)
+    }
+
+    #[test]
+    fn test_manual_entry() {
+        let (document, output) = run(&[
+            // Competition
+            "Coupe de Lyon 2024",
+            "",
+            "ffsl",
+            "1",
+            "2024-03-09",
+            "",
+            "Gymnase Jean Macé",
+            "Lyon",
+            "",
+            "3",
+            // Movements
+            "mu, dips",
+            // Category
+            "",
+            "-80",
+            "m",
+            // Known athlete, with a miss and a decreasing weight to accept
+            "",
+            "dup",
+            "1",
+            "78.5",
+            "3",
+            "",
+            "20 x25 22.5",
+            "40 45 47.5 50",
+            "y",
+            // New athlete, whose first attempts are re-entered
+            "",
+            "MARTIN Paul",
+            "",
+            "",
+            "FR",
+            "",
+            "79",
+            "",
+            "",
+            "15 abc",
+            "15 17.5 -",
+            "",
+            "n",
+            "n",
+        ]);
+        let document = document.unwrap();
+
+        assert!(matches!(
+            document.source.r#type,
+            canonical::SourceType::Manual
+        ));
+        assert_eq!(document.competition.slug, "coupe-de-lyon-2024");
+        assert_eq!(
+            document.competition.federation.name,
+            "Fédération Française de Streetlifting"
+        );
+        assert_eq!(document.competition.country, "FR");
+        assert_eq!(
+            document.competition.end_date,
+            document.competition.start_date
+        );
+        assert_eq!(
+            document
+                .movements
+                .iter()
+                .map(|m| m.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Muscle-up", "Dips"]
+        );
+
+        let category = &document.categories[0];
+        assert_eq!(
+            (category.name.as_str(), category.gender.as_str()),
+            ("-80", "M")
+        );
+
+        let dupont = &category.athletes[0];
+        assert_eq!(
+            (dupont.first_name.as_str(), dupont.country.as_str()),
+            ("Jean", "BE")
+        );
+        assert!(!dupont.lifts[0].attempts[1].is_successful);
+        assert_eq!(dupont.lot_number, Some(3));
+        assert_eq!(
+            dupont.lifts[1].attempts[3].kind,
+            canonical::AttemptKind::Record
+        );
+
+        let martin = &category.athletes[1];
+        assert_eq!(
+            (martin.first_name.as_str(), martin.last_name.as_str()),
+            ("Paul", "MARTIN")
+        );
+        assert_eq!(martin.lifts.len(), 1);
+        assert_eq!(martin.lifts[0].attempts.len(), 2);
+
+        assert!(output.contains("1. Fédération Française de Streetlifting (FFSL)"));
+        assert!(output.contains("warning: Athlete '1. Jean Dupont', movement 'Muscle-up'"));
+        assert!(output.contains("Cannot read attempt 'abc'"));
+        assert!(
+            CanonicalValidator::check(&document)
+                .errors()
+                .next()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_errors_make_the_section_asked_again() {
+        let (result, output) = run(&[
+            "Coupe de Lyon 2024",
+            "",
+            "FFSL",
+            "0",
+            "",
+            "",
+            "",
+            "2024-03-10",
+            "2024-03-09",
+            "",
+            "",
+            "FR",
+            "",
+        ]);
+
+        // The input ends while the competition is asked again
+        assert!(result.is_err());
+        assert!(output.contains("error: Competition end_date must be >= start_date"));
+        assert!(output.contains("Please enter it again"));
+    }
+}
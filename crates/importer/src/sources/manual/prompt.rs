@@ -0,0 +1,156 @@
+//! Line-based questions on any reader and writer, so that an entry session can be
+//! driven by a terminal as well as by a script
+
+use crate::{ImporterError, Result};
+use std::fmt::Display;
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+
+pub struct Prompter<R, W> {
+    input: R,
+    output: W,
+}
+
+impl<R: BufRead, W: Write> Prompter<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        Self { input, output }
+    }
+
+    pub fn into_output(self) -> W {
+        self.output
+    }
+
+    pub fn say(&mut self, message: impl Display) -> Result<()> {
+        writeln!(self.output, "{}", message).map_err(output_error)
+    }
+
+    /// Asks for a value until one is given, the default being used on an empty answer
+    pub fn text(&mut self, label: &str, default: Option<&str>) -> Result<String> {
+        loop {
+            let answer = self.ask(label, default)?;
+            if !answer.is_empty() {
+                return Ok(answer);
+            }
+            self.say("  A value is required")?;
+        }
+    }
+
+    /// Asks for a value that can be left empty
+    pub fn optional_text(&mut self, label: &str, default: Option<&str>) -> Result<Option<String>> {
+        let answer = self.ask(label, default)?;
+        Ok((!answer.is_empty()).then_some(answer))
+    }
+
+    /// Asks until the answer parses as `T`
+    pub fn parse<T>(&mut self, label: &str, default: Option<&str>) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        loop {
+            if let Some(value) = self.optional_parse(label, default)? {
+                return Ok(value);
+            }
+            self.say("  A value is required")?;
+        }
+    }
+
+    /// Asks until the answer is empty or parses as `T`
+    pub fn optional_parse<T>(&mut self, label: &str, default: Option<&str>) -> Result<Option<T>>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        loop {
+            let answer = self.ask(label, default)?;
+            if answer.is_empty() {
+                return Ok(None);
+            }
+            match answer.parse() {
+                Ok(value) => return Ok(Some(value)),
+                Err(e) => self.say(format!("  Invalid value '{}': {}", answer, e))?,
+            }
+        }
+    }
+
+    pub fn confirm(&mut self, label: &str, default: bool) -> Result<bool> {
+        let hint = if default { "Y/n" } else { "y/N" };
+        loop {
+            let answer = self.ask(&format!("{} ({})", label, hint), None)?;
+            match answer.to_lowercase().as_str() {
+                "" => return Ok(default),
+                "y" | "yes" | "o" | "oui" => return Ok(true),
+                "n" | "no" | "non" => return Ok(false),
+                _ => self.say("  Answer y or n")?,
+            }
+        }
+    }
+
+    /// Prints the question and reads one trimmed line, or the default when it is empty
+    fn ask(&mut self, label: &str, default: Option<&str>) -> Result<String> {
+        match default.filter(|d| !d.is_empty()) {
+            Some(default) => write!(self.output, "{} [{}]: ", label, default),
+            None => write!(self.output, "{}: ", label),
+        }
+        .and_then(|_| self.output.flush())
+        .map_err(output_error)?;
+
+        let mut line = String::new();
+        let read = self
+            .input
+            .read_line(&mut line)
+            .map_err(|e| ImporterError::ImportError(format!("Cannot read input: {}", e)))?;
+        if read == 0 {
+            return Err(ImporterError::ImportError(
+                "Input closed before the entry was complete".to_string(),
+            ));
+        }
+
+        let answer = line.trim();
+        Ok(if answer.is_empty() {
+            default.unwrap_or_default().to_string()
+        } else {
+            answer.to_string()
+        })
+    }
+}
+
+fn output_error(e: std::io::Error) -> ImporterError {
+    ImporterError::ImportError(format!("Cannot write prompt: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    use std::io::Cursor;
+
+    fn prompter(input: &str) -> Prompter<Cursor<Vec<u8>>, Vec<u8>> {
+        Prompter::new(Cursor::new(input.as_bytes().to_vec()), Vec::new())
+    }
+
+    #[test]
+    fn test_answers_defaults_and_retries() {
+        let mut prompt = prompter("\nParis\n\nabc\n82.5\nmaybe\nn\n");
+
+        assert_eq!(prompt.text("City", None).unwrap(), "Paris");
+        assert_eq!(prompt.text("Country", Some("FR")).unwrap(), "FR");
+        assert_eq!(
+            prompt.parse::<Decimal>("Bodyweight", None).unwrap(),
+            Decimal::new(825, 1)
+        );
+        assert!(!prompt.confirm("Disqualified", true).unwrap());
+
+        let output = String::from_utf8(prompt.into_output()).unwrap();
+        assert!(output.contains("A value is required"));
+        assert!(output.contains("Country [FR]: "));
+        assert!(output.contains("Invalid value 'abc'"));
+        assert!(output.contains("Answer y or n"));
+    }
+
+    #[test]
+    fn test_closed_input_fails() {
+        let mut prompt = prompter("");
+        assert!(prompt.optional_text("Venue", None).is_err());
+    }
+}
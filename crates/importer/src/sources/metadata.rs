@@ -0,0 +1,143 @@
+use crate::canonical::models as canonical;
+use crate::{ImporterError, Result};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::path::Path;
+
+/// Metadata for a competition that cannot be inferred from the source
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CompetitionMetadata {
+    pub name: String,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    #[serde(default)]
+    pub venue: Option<String>,
+    #[serde(default)]
+    pub city: Option<String>,
+    #[serde(default)]
+    pub country: Option<String>,
+    #[serde(default)]
+    pub number_of_judges: Option<i16>,
+    pub federation: FederationInfo,
+    pub default_athlete_country: String,
+    pub default_athlete_nationality: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FederationInfo {
+    pub name: String,
+    pub abbreviation: String,
+    pub country: String,
+}
+
+impl CompetitionMetadata {
+    /// Adds the problems serde cannot catch to `errors`, prefixed with `metadata.`
+    pub(crate) fn check(&self, errors: &mut Vec<String>) {
+        if self.name.trim().is_empty() {
+            errors.push("metadata.name is required".to_string());
+        }
+        if self.end_date < self.start_date {
+            errors.push("metadata.end_date must be >= metadata.start_date".to_string());
+        }
+        if let Some(judges) = self.number_of_judges
+            && judges != 1
+            && judges != 3
+        {
+            errors.push(format!(
+                "metadata.number_of_judges must be 1 or 3, got {}",
+                judges
+            ));
+        }
+        if self.federation.name.trim().is_empty() {
+            errors.push("metadata.federation.name is required".to_string());
+        }
+        if self.federation.abbreviation.trim().is_empty() {
+            errors.push("metadata.federation.abbreviation is required".to_string());
+        }
+        if self.default_athlete_country.trim().is_empty() {
+            errors.push("metadata.default_athlete_country is required".to_string());
+        }
+    }
+
+    pub fn to_competition_data(&self, slug: &str) -> canonical::CompetitionData {
+        canonical::CompetitionData {
+            name: self.name.clone(),
+            slug: slug.to_string(),
+            federation: canonical::FederationData {
+                name: self.federation.name.clone(),
+                slug: None,
+                abbreviation: Some(self.federation.abbreviation.clone()),
+                country: Some(self.federation.country.clone()),
+            },
+            start_date: self.start_date,
+            end_date: self.end_date,
+            venue: self.venue.clone(),
+            city: self.city.clone(),
+            country: self
+                .country
+                .clone()
+                .unwrap_or_else(|| "Unknown".to_string()),
+            number_of_judges: self.number_of_judges,
+            status: Some("completed".to_string()),
+        }
+    }
+}
+
+/// Competition file given next to a saved results document (PDF sheet, HTML page),
+/// in TOML or YAML
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SourceConfig {
+    pub slug: String,
+    pub metadata: CompetitionMetadata,
+}
+
+impl SourceConfig {
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            ImporterError::ValidationError(format!("{}: cannot read file: {}", path.display(), e))
+        })?;
+
+        let config: Self = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => toml::from_str(&content).map_err(|e| e.to_string()),
+            Some("yaml") | Some("yml") => serde_yaml::from_str(&content).map_err(|e| e.to_string()),
+            _ => Err("unsupported file extension, expected .toml, .yaml or .yml".to_string()),
+        }
+        .map_err(|e| ImporterError::ValidationError(format!("{}: {}", path.display(), e)))?;
+
+        config
+            .validate()
+            .map_err(|e| ImporterError::ValidationError(format!("{}: {}", path.display(), e)))?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        let mut errors = Vec::new();
+
+        if !is_slug(&self.slug) {
+            errors.push(format!(
+                "slug '{}' must be lowercase letters, digits and dashes",
+                self.slug
+            ));
+        }
+        self.metadata.check(&mut errors);
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.join("; "))
+        }
+    }
+}
+
+/// Whether a value can be used as a competition slug
+pub(crate) fn is_slug(value: &str) -> bool {
+    !value.is_empty()
+        && !value.starts_with('-')
+        && !value.ends_with('-')
+        && value
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
@@ -1 +1,6 @@
+pub mod html;
 pub mod liftcontrol;
+pub mod manual;
+pub mod metadata;
+pub mod pdf;
+pub mod table;
@@ -0,0 +1,77 @@
+use super::parser::SheetParser;
+use crate::canonical::models as canonical;
+use crate::sources::metadata::CompetitionMetadata;
+use crate::{ImporterError, Result};
+use chrono::Utc;
+use tracing::warn;
+
+pub const PDF_EXTRACTOR: &str = "pdftotext-layout-v1";
+
+pub struct PdfExporter {
+    metadata: CompetitionMetadata,
+    slug: String,
+}
+
+impl PdfExporter {
+    pub fn new(slug: String, metadata: CompetitionMetadata) -> Self {
+        Self { metadata, slug }
+    }
+
+    /// Builds the canonical document from the text of each page. Cells that could
+    /// not be read with certainty end up in `pdf_metadata.warnings`.
+    pub fn to_canonical(
+        &self,
+        pages: &[String],
+        original_filename: Option<String>,
+    ) -> Result<canonical::CanonicalFormat> {
+        let sheet = SheetParser::new(
+            &self.metadata.default_athlete_country,
+            &self.metadata.default_athlete_nationality,
+        )
+        .parse(pages);
+
+        if sheet.categories.iter().all(|c| c.athletes.is_empty()) {
+            return Err(ImporterError::TransformationError(format!(
+                "No result table found in {} page(s), is the PDF a scan without a text layer?",
+                pages.len()
+            )));
+        }
+
+        for warning in &sheet.warnings {
+            warn!("{}", warning);
+        }
+
+        Ok(canonical::CanonicalFormat {
+            format_version: canonical::CANONICAL_FORMAT_VERSION.to_string(),
+            source: canonical::SourceMetadata {
+                r#type: canonical::SourceType::Pdf,
+                url: None,
+                extracted_at: Utc::now(),
+                extractor: PDF_EXTRACTOR.to_string(),
+                original_filename,
+            },
+            competition: self.metadata.to_competition_data(&self.slug),
+            movements: sheet
+                .movements
+                .iter()
+                .enumerate()
+                .map(|(i, movement)| canonical::MovementData {
+                    name: movement.as_str().to_string(),
+                    order: i as i16 + 1,
+                    is_required: Some(true),
+                })
+                .collect(),
+            categories: sheet
+                .categories
+                .into_iter()
+                .filter(|c| !c.athletes.is_empty())
+                .collect(),
+            liftcontrol_metadata: None,
+            pdf_metadata: Some(canonical::PdfMetadata {
+                extraction_confidence: Some(sheet.confidence),
+                pages_processed: Some(sheet.pages_processed),
+                warnings: (!sheet.warnings.is_empty()).then_some(sheet.warnings),
+            }),
+        })
+    }
+}
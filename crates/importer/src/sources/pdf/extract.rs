@@ -0,0 +1,72 @@
+use crate::error::{ImporterError, Result};
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+pub const DEFAULT_PDFTOTEXT: &str = "pdftotext";
+
+/// Reads the text layer of a PDF with poppler's `pdftotext -layout`, which keeps
+/// the columns of the tables aligned. Scanned sheets without a text layer give
+/// empty pages and have to be typed in by hand.
+#[derive(Debug, Clone)]
+pub struct PdfTextExtractor {
+    program: PathBuf,
+}
+
+impl Default for PdfTextExtractor {
+    fn default() -> Self {
+        Self::new(DEFAULT_PDFTOTEXT)
+    }
+}
+
+impl PdfTextExtractor {
+    pub fn new(program: impl Into<PathBuf>) -> Self {
+        Self {
+            program: program.into(),
+        }
+    }
+
+    /// Returns the text of each page. A `.txt` file is taken as an already
+    /// extracted text layer, pages separated by form feeds.
+    pub async fn extract(&self, file: &Path) -> Result<Vec<String>> {
+        if file.extension().is_some_and(|ext| ext == "txt") {
+            let text = tokio::fs::read_to_string(file).await.map_err(|e| {
+                ImporterError::ImportError(format!("Cannot read {}: {}", file.display(), e))
+            })?;
+            return Ok(split_pages(&text));
+        }
+
+        let output = Command::new(&self.program)
+            .args(["-layout", "-enc", "UTF-8"])
+            .arg(file)
+            .arg("-")
+            .output()
+            .await
+            .map_err(|e| {
+                ImporterError::ImportError(format!(
+                    "Cannot run {} (install poppler-utils or pass its path): {}",
+                    self.program.display(),
+                    e
+                ))
+            })?;
+
+        if !output.status.success() {
+            return Err(ImporterError::ImportError(format!(
+                "{} failed on {}: {}",
+                self.program.display(),
+                file.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Ok(split_pages(&String::from_utf8_lossy(&output.stdout)))
+    }
+}
+
+/// Splits `pdftotext` output on the form feed it writes after every page
+pub fn split_pages(text: &str) -> Vec<String> {
+    let mut pages = text.split('\x0c').map(str::to_string).collect::<Vec<_>>();
+    if pages.last().is_some_and(|page| page.trim().is_empty()) {
+        pages.pop();
+    }
+    pages
+}
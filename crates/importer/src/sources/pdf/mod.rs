@@ -0,0 +1,115 @@
+mod exporter;
+mod extract;
+mod parser;
+mod spec;
+
+pub use exporter::{PDF_EXTRACTOR, PdfExporter};
+pub use extract::{DEFAULT_PDFTOTEXT, PdfTextExtractor, split_pages};
+pub use parser::{ParsedSheet, SheetParser};
+pub use spec::PdfSpec;
+
+use crate::canonical::models::CanonicalFormat;
+use crate::{Result, traits::CompetitionImporter};
+use tracing::info;
+
+pub struct PdfImporter {
+    extractor: PdfTextExtractor,
+}
+
+impl PdfImporter {
+    pub fn new(extractor: PdfTextExtractor) -> Self {
+        Self { extractor }
+    }
+
+    /// Extracts the text layer of the sheet and converts its tables to the canonical format
+    pub async fn export(&self, spec: &PdfSpec) -> Result<CanonicalFormat> {
+        info!("Extracting text from: {}", spec.file().display());
+        let pages = self.extractor.extract(spec.file()).await?;
+        info!("Read {} page(s)", pages.len());
+
+        let original_filename = spec
+            .file()
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string());
+
+        PdfExporter::new(spec.slug().to_string(), spec.metadata().clone())
+            .to_canonical(&pages, original_filename)
+    }
+}
+
+#[async_trait::async_trait]
+impl CompetitionImporter for PdfImporter {
+    type Spec = PdfSpec;
+
+    async fn to_canonical(&self, spec: &Self::Spec) -> Result<Vec<CanonicalFormat>> {
+        Ok(vec![self.export(spec).await?])
+    }
+
+    fn name(&self) -> &'static str {
+        "PDF"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canonical::validator::CanonicalValidator;
+    use crate::sources::metadata::SourceConfig;
+    use std::path::PathBuf;
+
+    const PARIS_TOML: &str = r#"
+slug = "paris-streetlifting-2023"
+
+[metadata]
+name = "Paris Streetlifting 2023"
+start_date = "2023-03-12"
+end_date = "2023-03-12"
+city = "Paris"
+country = "France"
+default_athlete_country = "FR"
+default_athlete_nationality = "French"
+
+[metadata.federation]
+name = "4Lift"
+abbreviation = "4L"
+country = "FR"
+"#;
+
+    #[tokio::test]
+    async fn test_export_text_layer() {
+        let config: SourceConfig = toml::from_str(PARIS_TOML).unwrap();
+        config.validate().unwrap();
+        let spec = PdfSpec::new(
+            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("fixtures/pdf/paris-streetlifting-2023.txt"),
+            config,
+        );
+
+        let canonical = PdfImporter::new(PdfTextExtractor::default())
+            .export(&spec)
+            .await
+            .unwrap();
+
+        assert_eq!(canonical.competition.slug, "paris-streetlifting-2023");
+        assert_eq!(canonical.movements.len(), 4);
+        assert_eq!(
+            canonical.source.original_filename.as_deref(),
+            Some("paris-streetlifting-2023.txt")
+        );
+
+        let pdf_metadata = canonical.pdf_metadata.as_ref().unwrap();
+        assert_eq!(pdf_metadata.pages_processed, Some(vec![1, 2]));
+        assert_eq!(pdf_metadata.warnings.as_ref().map(Vec::len), Some(3));
+
+        CanonicalValidator::validate(&canonical).unwrap();
+    }
+
+    #[test]
+    fn test_blank_pages_fail() {
+        let config: SourceConfig = toml::from_str(PARIS_TOML).unwrap();
+        let err = PdfExporter::new(config.slug, config.metadata)
+            .to_canonical(&split_pages("\x0c\x0c"), None)
+            .unwrap_err();
+        assert!(err.to_string().contains("text layer"));
+    }
+}
@@ -0,0 +1,306 @@
+use crate::canonical::models as canonical;
+use crate::movement_mapper::CanonicalMovement;
+use crate::sources::table::{
+    Column, RowCell, RowReader, classify_header, parse_attempt, parse_category_line,
+};
+
+/// Results read from the text layer of a score sheet
+#[derive(Debug, Default)]
+pub struct ParsedSheet {
+    /// Movements in the order of the table columns
+    pub movements: Vec<CanonicalMovement>,
+    pub categories: Vec<canonical::CategoryData>,
+    /// Cells and rows a human has to review, with their page and line
+    pub warnings: Vec<String>,
+    /// 1-based numbers of the pages results were read from
+    pub pages_processed: Vec<i32>,
+    /// Share of result rows read without any uncertain cell
+    pub confidence: f64,
+}
+
+/// Parses the tables of `pdftotext -layout` output: a header row naming the
+/// movements, category titles such as `Men -80 kg`, and one row per athlete.
+pub struct SheetParser<'a> {
+    rows: RowReader<'a>,
+}
+
+struct Location {
+    page: usize,
+    line: usize,
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "page {}, line {}", self.page, self.line)
+    }
+}
+
+impl<'a> SheetParser<'a> {
+    pub fn new(default_country: &'a str, default_nationality: &'a str) -> Self {
+        Self {
+            rows: RowReader {
+                default_country,
+                default_nationality,
+            },
+        }
+    }
+
+    pub fn parse(&self, pages: &[String]) -> ParsedSheet {
+        let mut sheet = ParsedSheet::default();
+        let mut columns: Option<Vec<Column>> = None;
+        let mut category: Option<usize> = None;
+        let mut rows = 0usize;
+        let mut clean_rows = 0usize;
+
+        for (page_idx, page) in pages.iter().enumerate() {
+            for (line_idx, line) in page.lines().enumerate() {
+                let location = Location {
+                    page: page_idx + 1,
+                    line: line_idx + 1,
+                };
+                let cells = split_cells(line);
+                if cells.is_empty() {
+                    continue;
+                }
+
+                if let Some(header) = parse_header(&cells) {
+                    for column in &header {
+                        if let Column::Movement(movement) = column
+                            && !sheet.movements.contains(movement)
+                        {
+                            sheet.movements.push(*movement);
+                        }
+                    }
+                    columns = Some(header);
+                    continue;
+                }
+
+                if cells.len() <= 2
+                    && let Some(parsed) = parse_category_line(line)
+                {
+                    category = match parsed {
+                        Ok(new_category) => Some(find_or_add_category(&mut sheet, new_category)),
+                        Err(name) => {
+                            sheet.warnings.push(format!(
+                                "{}: category '{}' has no gender, its athletes were left out",
+                                location, name
+                            ));
+                            None
+                        }
+                    };
+                    continue;
+                }
+
+                let Some(columns) = &columns else {
+                    continue;
+                };
+                if !looks_like_row(&cells) {
+                    continue;
+                }
+
+                rows += 1;
+                let expected = columns.iter().map(Column::width).sum::<usize>();
+                if cells.len() != expected {
+                    sheet.warnings.push(format!(
+                        "{}: expected {} cells, found {}, row left out: '{}'",
+                        location,
+                        expected,
+                        cells.len(),
+                        line.trim()
+                    ));
+                    continue;
+                }
+
+                let Some(category_idx) = category else {
+                    sheet.warnings.push(format!(
+                        "{}: row outside of any category, left out: '{}'",
+                        location,
+                        line.trim()
+                    ));
+                    continue;
+                };
+
+                let warnings_before = sheet.warnings.len();
+                let cells = cells
+                    .iter()
+                    .map(|text| RowCell::new(text))
+                    .collect::<Vec<_>>();
+                if let Some(athlete) =
+                    self.rows
+                        .read(columns, &cells, &location, &mut sheet.warnings)
+                {
+                    sheet.categories[category_idx].athletes.push(athlete);
+                    let page = location.page as i32;
+                    if !sheet.pages_processed.contains(&page) {
+                        sheet.pages_processed.push(page);
+                    }
+                    if sheet.warnings.len() == warnings_before {
+                        clean_rows += 1;
+                    }
+                }
+            }
+        }
+
+        sheet.confidence = if rows == 0 {
+            0.0
+        } else {
+            clean_rows as f64 / rows as f64
+        };
+        sheet
+    }
+}
+
+/// Splits a line on runs of two or more spaces, the column gaps `-layout` keeps
+fn split_cells(line: &str) -> Vec<&str> {
+    let line = line.trim();
+    let mut cells = Vec::new();
+    let mut start = 0;
+    let mut gap_start = None;
+
+    for (i, c) in line.char_indices() {
+        if c == ' ' || c == '\t' {
+            if gap_start.is_none() {
+                gap_start = Some(i);
+            }
+            continue;
+        }
+        if let Some(gap) = gap_start.take() {
+            let gap_text = &line[gap..i];
+            if gap_text.len() >= 2 || gap_text.contains('\t') {
+                cells.push(&line[start..gap]);
+                start = i;
+            }
+        }
+    }
+    if !line.is_empty() {
+        cells.push(&line[start..]);
+    }
+
+    cells
+}
+
+fn parse_header(cells: &[&str]) -> Option<Vec<Column>> {
+    let columns = cells
+        .iter()
+        .map(|cell| classify_header(cell))
+        .collect::<Vec<_>>();
+
+    let has_name = columns.contains(&Column::Name);
+    let has_movement = columns.iter().any(|c| matches!(c, Column::Movement(_)));
+    (has_name && has_movement).then_some(columns)
+}
+
+fn find_or_add_category(sheet: &mut ParsedSheet, category: canonical::CategoryData) -> usize {
+    if let Some(idx) = sheet
+        .categories
+        .iter()
+        .position(|c| c.name == category.name && c.gender == category.gender)
+    {
+        return idx;
+    }
+    sheet.categories.push(category);
+    sheet.categories.len() - 1
+}
+
+/// A result row starts with a rank, or carries several numbers
+fn looks_like_row(cells: &[&str]) -> bool {
+    // Sub-header numbering the attempts: "1  2  3  1  2  3"
+    if cells.iter().all(|c| matches!(*c, "1" | "2" | "3")) {
+        return false;
+    }
+
+    let first = cells[0].trim_end_matches('.');
+    let ranked = first.chars().all(|c| c.is_ascii_digit())
+        || matches!(first.to_uppercase().as_str(), "DQ" | "DSQ" | "DISQ" | "-");
+    let numbers = cells
+        .iter()
+        .filter(|c| parse_attempt(c).is_ok_and(|a| a.is_some()))
+        .count();
+
+    ranked || numbers >= 3
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+
+    fn pages(text: &str) -> Vec<String> {
+        super::super::extract::split_pages(text)
+    }
+
+    fn sheet(text: &str) -> ParsedSheet {
+        SheetParser::new("FR", "French").parse(&pages(text))
+    }
+
+    #[test]
+    fn test_parse_sheet() {
+        let sheet = sheet(include_str!(
+            "../../../fixtures/pdf/paris-streetlifting-2023.txt"
+        ));
+
+        assert_eq!(
+            sheet.movements,
+            vec![
+                CanonicalMovement::MuscleUp,
+                CanonicalMovement::PullUp,
+                CanonicalMovement::Dips,
+                CanonicalMovement::Squat
+            ]
+        );
+        assert_eq!(sheet.pages_processed, vec![1, 2]);
+
+        let names = sheet
+            .categories
+            .iter()
+            .map(|c| (c.name.as_str(), c.gender.as_str(), c.athletes.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![("-80", "M", 3), ("+80", "M", 1), ("-63", "F", 2)]
+        );
+
+        let dupont = &sheet.categories[0].athletes[0];
+        assert_eq!(dupont.first_name, "Jean");
+        assert_eq!(dupont.last_name, "DUPONT");
+        assert_eq!(dupont.bodyweight, Some(Decimal::new(785, 1)));
+        let muscle_up = &dupont.lifts[0];
+        assert_eq!(muscle_up.movement, "Muscle-up");
+        assert_eq!(
+            muscle_up
+                .attempts
+                .iter()
+                .map(|a| (a.attempt_number, a.weight, a.is_successful))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, Decimal::from(20), true),
+                (2, Decimal::from(25), true),
+                (3, Decimal::new(275, 1), false)
+            ]
+        );
+
+        let disqualified = &sheet.categories[0].athletes[2];
+        assert_eq!(disqualified.is_disqualified, Some(true));
+        assert_eq!(disqualified.country, "BE");
+    }
+
+    #[test]
+    fn test_uncertain_cells_are_flagged() {
+        let sheet = sheet(include_str!(
+            "../../../fixtures/pdf/paris-streetlifting-2023.txt"
+        ));
+
+        let warnings = sheet.warnings.join("\n");
+        assert!(
+            warnings.contains("page 1, line 11: unreadable Pull-up attempt 3 '-52.5', left out"),
+            "{}",
+            warnings
+        );
+        assert!(warnings.contains("page 2, line 7: total '173.5' of Léa MARTIN does not match"));
+        assert!(warnings.contains("page 2, line 8: expected 17 cells, found 16"));
+        assert_eq!(sheet.warnings.len(), 3);
+
+        // 7 rows, 2 with a flagged cell and 1 left out
+        assert!((sheet.confidence - 4.0 / 7.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,33 @@
+use std::path::{Path, PathBuf};
+
+use crate::sources::metadata::{CompetitionMetadata, SourceConfig};
+
+/// Competition described by a score sheet, with the metadata the sheet does not carry
+#[derive(Debug, Clone)]
+pub struct PdfSpec {
+    file: PathBuf,
+    slug: String,
+    metadata: CompetitionMetadata,
+}
+
+impl PdfSpec {
+    pub fn new(file: impl Into<PathBuf>, config: SourceConfig) -> Self {
+        Self {
+            file: file.into(),
+            slug: config.slug,
+            metadata: config.metadata,
+        }
+    }
+
+    pub fn file(&self) -> &Path {
+        &self.file
+    }
+
+    pub fn slug(&self) -> &str {
+        &self.slug
+    }
+
+    pub fn metadata(&self) -> &CompetitionMetadata {
+        &self.metadata
+    }
+}
@@ -0,0 +1,428 @@
+//! Readers for the cells of printed result tables, shared by the PDF and HTML sources
+
+use crate::canonical::models as canonical;
+use crate::movement_mapper::{CanonicalMovement, MovementMapper};
+use rust_decimal::Decimal;
+use std::fmt;
+use std::str::FromStr;
+
+/// Maps the column headers found on printed results, in English and French
+pub struct TableMovementMapper;
+
+impl MovementMapper for TableMovementMapper {
+    fn map_movement(&self, name: &str) -> Option<CanonicalMovement> {
+        let name = name.trim().trim_end_matches("(kg)").trim().to_lowercase();
+        match name.as_str() {
+            "muscle-up" | "muscle up" | "muscleup" | "muscle-ups" | "mu" => {
+                Some(CanonicalMovement::MuscleUp)
+            }
+            "pull-up" | "pull up" | "pullup" | "pull-ups" | "traction" | "tractions" => {
+                Some(CanonicalMovement::PullUp)
+            }
+            "dips" | "dip" => Some(CanonicalMovement::Dips),
+            "squat" | "squats" => Some(CanonicalMovement::Squat),
+            _ => None,
+        }
+    }
+}
+
+/// What a column of a result table holds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Column {
+    Rank,
+    Name,
+    Country,
+    Bodyweight,
+    Movement(CanonicalMovement),
+    Total,
+    Other,
+}
+
+impl Column {
+    /// Number of cells the column takes in a result row
+    pub fn width(&self) -> usize {
+        match self {
+            Column::Movement(_) => 3,
+            _ => 1,
+        }
+    }
+}
+
+/// Reads a header cell, matching English and French column names
+pub(crate) fn classify_header(cell: &str) -> Column {
+    let key = cell.trim().trim_end_matches('.').to_lowercase();
+    match key.as_str() {
+        "rank" | "#" | "pl" | "place" | "pos" | "position" | "rang" | "classement" | "clt" => {
+            Column::Rank
+        }
+        "name" | "athlete" | "athlète" | "nom" | "nom prénom" | "nom prenom" => Column::Name,
+        "country" | "nat" | "nation" | "nationality" | "pays" | "ctry" => Column::Country,
+        "bw" | "bodyweight" | "body weight" | "pc" | "pdc" | "poids" | "poids de corps"
+        | "weight" => Column::Bodyweight,
+        "total" | "total (kg)" => Column::Total,
+        _ => TableMovementMapper
+            .map_movement(&key)
+            .map(Column::Movement)
+            .unwrap_or(Column::Other),
+    }
+}
+
+/// Reads a category title: `Men -80 kg`, `Catégorie : Femmes +63kg`, `H -73`.
+/// Returns the weight class without a gender as an error.
+pub(crate) fn parse_category_line(line: &str) -> Option<Result<canonical::CategoryData, String>> {
+    let mut gender = None;
+    let mut weight_class = None;
+
+    for word in line.split_whitespace() {
+        let word = word.trim_end_matches(':');
+        let lower = word.to_lowercase();
+        match lower.as_str() {
+            "men" | "man" | "male" | "hommes" | "homme" | "masculin" | "h" | "m" => {
+                gender = Some("M")
+            }
+            "women" | "woman" | "female" | "femmes" | "femme" | "féminin" | "feminin" | "f"
+            | "w" | "d" => gender = Some("F"),
+            "category" | "catégorie" | "categorie" | "cat" | "kg" | ":" | "-" | "–" | "—" | "" =>
+                {}
+            _ => match parse_weight_class(&lower) {
+                Some(parsed) if weight_class.is_none() => weight_class = Some(parsed),
+                _ => return None,
+            },
+        }
+    }
+
+    let (name, min, max) = weight_class?;
+    Some(match gender {
+        Some(gender) => Ok(canonical::CategoryData {
+            name,
+            gender: gender.to_string(),
+            weight_class_min: min,
+            weight_class_max: max,
+            athletes: Vec::new(),
+        }),
+        None => Err(name),
+    })
+}
+
+/// `-80`, `-80kg`, `+90`, `90+` into a name like LiftControl's and its bounds
+pub(crate) fn parse_weight_class(word: &str) -> Option<(String, Option<Decimal>, Option<Decimal>)> {
+    let word = word.trim_end_matches("kg");
+    if let Some(max) = word.strip_prefix('-') {
+        let max = parse_weight(max)?;
+        return Some((format!("-{}", max), None, Some(max)));
+    }
+    let min = word.strip_prefix('+').or_else(|| word.strip_suffix('+'))?;
+    let min = parse_weight(min)?;
+    Some((format!("+{}", min), Some(min), None))
+}
+
+/// Splits "SMITH John", "John SMITH" or "Smith, John" into first and last name.
+/// The flag is false when the split is only a guess.
+pub(crate) fn split_name(cell: &str) -> Option<(String, String, bool)> {
+    if let Some((last, first)) = cell.split_once(',') {
+        let (first, last) = (first.trim(), last.trim());
+        return (!first.is_empty() && !last.is_empty())
+            .then(|| (first.to_string(), last.to_string(), true));
+    }
+
+    let words = cell.split_whitespace().collect::<Vec<_>>();
+    if words.len() < 2 {
+        return None;
+    }
+
+    let is_upper =
+        |w: &str| w.chars().any(char::is_alphabetic) && !w.chars().any(char::is_lowercase);
+    let (last, first): (Vec<&str>, Vec<&str>) = words.iter().partition(|w| is_upper(w));
+    if !last.is_empty() && !first.is_empty() {
+        return Some((first.join(" "), last.join(" "), true));
+    }
+
+    let certain = last.is_empty() && words.len() == 2;
+    Some((words[0].to_string(), words[1..].join(" "), certain))
+}
+
+pub(crate) fn is_empty_cell(cell: &str) -> bool {
+    matches!(cell.trim(), "" | "-" | "—" | "–")
+}
+
+pub(crate) fn parse_weight(cell: &str) -> Option<Decimal> {
+    let cell = cell.trim().trim_end_matches("kg").trim().replace(',', ".");
+    Decimal::from_str(&cell)
+        .ok()
+        .filter(|weight| !weight.is_sign_negative())
+        .map(|weight| weight.normalize())
+}
+
+/// `27.5` is a good lift, `x27.5`, `27.5x` and `(27.5)` a miss, `-` no attempt.
+/// Fails on anything else, including `-27.5` which sheets use both for a miss
+/// and for a negative weight.
+pub(crate) fn parse_attempt(cell: &str) -> Result<Option<(Decimal, bool)>, ()> {
+    let cell = cell.trim();
+    if is_empty_cell(cell) {
+        return Ok(None);
+    }
+
+    let failed_markers: &[char] = &['x', 'X', '✗', '✘'];
+    let (value, is_successful) =
+        if let Some(inner) = cell.strip_prefix('(').and_then(|c| c.strip_suffix(')')) {
+            (inner, false)
+        } else if let Some(value) = cell
+            .strip_prefix(failed_markers)
+            .or_else(|| cell.strip_suffix(failed_markers))
+        {
+            (value.trim(), false)
+        } else {
+            (cell, true)
+        };
+
+    if value.starts_with('-') {
+        return Err(());
+    }
+    parse_weight(value)
+        .map(|weight| Some((weight, is_successful)))
+        .ok_or(())
+}
+
+/// A cell of a result row, with what the page says about it besides its text
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RowCell<'a> {
+    pub text: &'a str,
+    /// Marked as a missed attempt by its styling rather than its text
+    pub failed: bool,
+}
+
+impl<'a> RowCell<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            failed: false,
+        }
+    }
+}
+
+/// Turns result rows into athletes. A row has one cell per column, three for a
+/// movement, and failed attempts are written `x27.5`, `27.5x` or `(27.5)`,
+/// attempts not taken `-`.
+pub(crate) struct RowReader<'a> {
+    pub default_country: &'a str,
+    pub default_nationality: &'a str,
+}
+
+impl RowReader<'_> {
+    /// Reads one row laid out as `columns`, adding what is uncertain to `warnings`.
+    /// Returns `None` when the row cannot be used at all.
+    pub(crate) fn read(
+        &self,
+        columns: &[Column],
+        cells: &[RowCell<'_>],
+        location: &dyn fmt::Display,
+        warnings: &mut Vec<String>,
+    ) -> Option<canonical::AthleteData> {
+        let mut athlete = canonical::AthleteData {
+            first_name: String::new(),
+            last_name: String::new(),
+            gender: None,
+            country: self.default_country.to_string(),
+            nationality: Some(self.default_nationality.to_string()),
+            birth_date: None,
+            birth_year: None,
+            bodyweight: None,
+            lot_number: None,
+            is_disqualified: Some(false),
+            disqualified_reason: None,
+            lifts: Vec::new(),
+            liftcontrol_athlete_metadata: None,
+            source_session: None,
+        };
+        let mut total = None;
+        let mut cells = cells.iter();
+
+        for column in columns {
+            match column {
+                Column::Rank => {
+                    let rank = cells.next()?.text.to_uppercase();
+                    if matches!(rank.as_str(), "DQ" | "DSQ" | "DISQ") {
+                        athlete.is_disqualified = Some(true);
+                    }
+                }
+                Column::Name => {
+                    let cell = cells.next()?.text;
+                    match split_name(cell) {
+                        Some((first_name, last_name, certain)) => {
+                            if !certain {
+                                warnings.push(format!(
+                                    "{}: cannot tell first from last name in '{}', read as first name '{}' and last name '{}'",
+                                    location, cell, first_name, last_name
+                                ));
+                            }
+                            athlete.first_name = first_name;
+                            athlete.last_name = last_name;
+                        }
+                        None => {
+                            warnings.push(format!(
+                                "{}: '{}' is not a full name, row left out",
+                                location, cell
+                            ));
+                            return None;
+                        }
+                    }
+                }
+                Column::Country => {
+                    let cell = cells.next()?.text;
+                    if (2..=3).contains(&cell.len()) && cell.chars().all(|c| c.is_ascii_uppercase())
+                    {
+                        athlete.country = cell.to_string();
+                    } else if !is_empty_cell(cell) {
+                        warnings.push(format!(
+                            "{}: unreadable country '{}', using {}",
+                            location, cell, self.default_country
+                        ));
+                    }
+                }
+                Column::Bodyweight => {
+                    let cell = cells.next()?.text;
+                    if is_empty_cell(cell) {
+                        continue;
+                    }
+                    match parse_weight(cell) {
+                        Some(bodyweight) => athlete.bodyweight = Some(bodyweight),
+                        None => {
+                            warnings.push(format!("{}: unreadable bodyweight '{}'", location, cell))
+                        }
+                    }
+                }
+                Column::Movement(movement) => {
+                    let mut attempts = Vec::new();
+                    for attempt_number in 1..=3 {
+                        let RowCell { text: cell, failed } = *cells.next()?;
+                        match parse_attempt(cell) {
+                            Ok(Some((weight, is_successful))) => {
+                                attempts.push(canonical::AttemptData {
+                                    attempt_number,
+                                    weight,
+                                    is_successful: is_successful && !failed,
+                                    no_rep_reason: None,
+                                    kind: canonical::AttemptKind::Regular,
+                                })
+                            }
+                            Ok(None) => {}
+                            Err(()) => warnings.push(format!(
+                                "{}: unreadable {} attempt {} '{}', left out",
+                                location,
+                                movement.as_str(),
+                                attempt_number,
+                                cell
+                            )),
+                        }
+                    }
+                    if !attempts.is_empty() {
+                        athlete.lifts.push(canonical::LiftData {
+                            movement: movement.as_str().to_string(),
+                            attempts,
+                            equipment_setting: None,
+                        });
+                    }
+                }
+                Column::Total => {
+                    let cell = cells.next()?.text;
+                    if !is_empty_cell(cell) {
+                        total = Some((cell, parse_weight(cell)));
+                    }
+                }
+                Column::Other => {
+                    cells.next()?;
+                }
+            }
+        }
+
+        if let Some((cell, total)) = total
+            && athlete.is_disqualified != Some(true)
+        {
+            let computed = athlete
+                .lifts
+                .iter()
+                .filter_map(|lift| {
+                    lift.attempts
+                        .iter()
+                        .filter(|a| a.is_successful)
+                        .map(|a| a.weight)
+                        .max()
+                })
+                .sum::<Decimal>();
+            if total != Some(computed) {
+                warnings.push(format!(
+                    "{}: total '{}' of {} {} does not match the best attempts ({} kg)",
+                    location, cell, athlete.first_name, athlete.last_name, computed
+                ));
+            }
+        }
+
+        Some(athlete)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_attempt() {
+        assert_eq!(
+            parse_attempt("27.5"),
+            Ok(Some((Decimal::new(275, 1), true)))
+        );
+        assert_eq!(
+            parse_attempt("27,5"),
+            Ok(Some((Decimal::new(275, 1), true)))
+        );
+        assert_eq!(parse_attempt("x30"), Ok(Some((Decimal::from(30), false))));
+        assert_eq!(parse_attempt("30X"), Ok(Some((Decimal::from(30), false))));
+        assert_eq!(parse_attempt("(30)"), Ok(Some((Decimal::from(30), false))));
+        assert_eq!(parse_attempt("-"), Ok(None));
+        assert_eq!(parse_attempt("-30"), Err(()));
+        assert_eq!(parse_attempt("3O"), Err(()));
+    }
+
+    #[test]
+    fn test_split_name() {
+        assert_eq!(
+            split_name("DUPONT Jean"),
+            Some(("Jean".to_string(), "DUPONT".to_string(), true))
+        );
+        assert_eq!(
+            split_name("Marie Claire LE GALL"),
+            Some(("Marie Claire".to_string(), "LE GALL".to_string(), true))
+        );
+        assert_eq!(
+            split_name("Smith, John"),
+            Some(("John".to_string(), "Smith".to_string(), true))
+        );
+        assert_eq!(
+            split_name("John Smith"),
+            Some(("John".to_string(), "Smith".to_string(), true))
+        );
+        assert_eq!(
+            split_name("Anna Maria Rossi"),
+            Some(("Anna".to_string(), "Maria Rossi".to_string(), false))
+        );
+        assert_eq!(split_name("Cher"), None);
+    }
+
+    #[test]
+    fn test_parse_category_line() {
+        let category = parse_category_line("Men -80 kg").unwrap().unwrap();
+        assert_eq!(category.name, "-80");
+        assert_eq!(category.gender, "M");
+        assert_eq!(category.weight_class_max, Some(Decimal::from(80)));
+
+        let category = parse_category_line("Catégorie : Femmes 63+")
+            .unwrap()
+            .unwrap();
+        assert_eq!(category.name, "+63");
+        assert_eq!(category.gender, "F");
+        assert_eq!(category.weight_class_min, Some(Decimal::from(63)));
+
+        assert_eq!(parse_category_line("-73kg").unwrap().unwrap_err(), "-73");
+        assert!(parse_category_line("Results of the day").is_none());
+    }
+}
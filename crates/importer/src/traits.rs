@@ -1,4 +1,7 @@
 use crate::Result;
+use crate::canonical::loader::{CanonicalLoader, ImportOrigin};
+use crate::canonical::models::CanonicalFormat;
+use crate::canonical::validator::CanonicalValidator;
 use sqlx::PgPool;
 
 /// Context passed to all importers, containing shared resources
@@ -10,6 +13,9 @@ pub struct ImportContext {
 /// Each importer type (LiftControl, Spreadsheet, PDF, etc.) implements this trait
 /// with its own specification type that defines the contract for that source.
 ///
+/// Importers only produce canonical documents: every database write goes through
+/// `CanonicalLoader`, the same path as `import canonical <file>`.
+///
 /// # Type Parameters
 /// * `Spec` - The specification type that defines what this importer needs to operate
 ///
@@ -21,7 +27,7 @@ pub struct ImportContext {
 /// impl CompetitionImporter for LiftControlImporter {
 ///     type Spec = LiftControlSpec;
 ///
-///     async fn import(&self, spec: &Self::Spec, context: &ImportContext) -> Result<()> {
+///     async fn to_canonical(&self, spec: &Self::Spec) -> Result<Vec<CanonicalFormat>> {
 ///         // Implementation
 ///     }
 /// }
@@ -31,9 +37,25 @@ pub trait CompetitionImporter: Send + Sync {
     /// The specification type that defines the contract for this importer
     type Spec: Send + Sync;
 
-    /// Imports competition data according to the provided specification
-    async fn import(&self, spec: &Self::Spec, context: &ImportContext) -> Result<()>;
+    /// Fetches the source and converts it to canonical documents, one per source unit
+    /// (e.g. one per LiftControl session)
+    async fn to_canonical(&self, spec: &Self::Spec) -> Result<Vec<CanonicalFormat>>;
 
     /// Returns a human-readable name for this importer (e.g., "LiftControl", "Spreadsheet")
     fn name(&self) -> &'static str;
+
+    /// Validates and loads every canonical document produced for the specification
+    async fn import(&self, spec: &Self::Spec, context: &ImportContext) -> Result<()> {
+        let loader = CanonicalLoader::new(&context.pool);
+
+        for canonical in self.to_canonical(spec).await? {
+            let report = CanonicalValidator::validate(&canonical)?;
+            report.log_warnings();
+
+            let origin = ImportOrigin::new(None, &serde_json::to_vec(&canonical)?);
+            loader.import_to_database(canonical, &origin).await?;
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,34 @@
+-- Provenance of imported data: which canonical file and source produced
+-- each competition and participant
+
+CREATE TABLE IF NOT EXISTS "import_batches" (
+	"import_batch_id" UUID NOT NULL UNIQUE DEFAULT gen_random_uuid(),
+	"source_type" VARCHAR(50) NOT NULL,
+	"source_url" VARCHAR(500),
+	"file_path" VARCHAR(1000),
+	"content_hash" VARCHAR(64) NOT NULL,
+	"format_version" VARCHAR(20) NOT NULL,
+	"extractor" VARCHAR(255) NOT NULL,
+	"extracted_at" TIMESTAMPTZ NOT NULL,
+	"importer_version" VARCHAR(50) NOT NULL,
+	"category_count" INTEGER NOT NULL DEFAULT 0,
+	"participant_count" INTEGER NOT NULL DEFAULT 0,
+	"attempt_count" INTEGER NOT NULL DEFAULT 0,
+	"imported_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY("import_batch_id")
+);
+
+CREATE INDEX "import_batches_content_hash_idx" ON "import_batches" ("content_hash");
+
+ALTER TABLE competitions ADD COLUMN import_batch_id UUID;
+ALTER TABLE competition_participants ADD COLUMN import_batch_id UUID;
+
+ALTER TABLE competitions
+ADD CONSTRAINT competitions_import_batch_id_fkey
+FOREIGN KEY(import_batch_id) REFERENCES import_batches(import_batch_id) ON UPDATE CASCADE ON DELETE SET NULL;
+
+ALTER TABLE competition_participants
+ADD CONSTRAINT competition_participants_import_batch_id_fkey
+FOREIGN KEY(import_batch_id) REFERENCES import_batches(import_batch_id) ON UPDATE CASCADE ON DELETE SET NULL;
+
+CREATE INDEX competition_participants_import_batch_idx ON competition_participants (import_batch_id);
@@ -0,0 +1,25 @@
+-- Canonical files uploaded through the API, waiting for a moderator to approve
+-- (import) or reject them
+
+CREATE TABLE IF NOT EXISTS "submissions" (
+	"submission_id" UUID NOT NULL UNIQUE DEFAULT gen_random_uuid(),
+	"status" VARCHAR(20) NOT NULL DEFAULT 'pending',
+	"competition_slug" VARCHAR(255) NOT NULL,
+	"competition_name" VARCHAR(255) NOT NULL,
+	"content" TEXT NOT NULL,
+	"content_hash" VARCHAR(64) NOT NULL,
+	"validation_report" JSONB NOT NULL,
+	"dry_run_diff" JSONB NOT NULL,
+	"submitted_by" VARCHAR(255),
+	"submitted_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	"reviewed_at" TIMESTAMP,
+	"review_comment" TEXT,
+	PRIMARY KEY("submission_id"),
+	CONSTRAINT "submissions_status_check" CHECK ("status" IN ('pending', 'approved', 'rejected'))
+);
+
+CREATE INDEX "submissions_status_idx" ON "submissions" ("status", "submitted_at");
+
+-- The same file cannot wait twice in the queue
+CREATE UNIQUE INDEX "submissions_pending_content_hash_idx" ON "submissions" ("content_hash")
+WHERE "status" = 'pending';
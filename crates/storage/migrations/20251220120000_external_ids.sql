@@ -0,0 +1,30 @@
+-- Identifiers given to athletes and competitions by the sources they are imported
+-- from (e.g. LiftControl registrations and contests), matched before names so that
+-- a re-import with a corrected spelling updates the same rows
+
+CREATE TABLE IF NOT EXISTS "athlete_external_ids" (
+	"source" VARCHAR(50) NOT NULL,
+	"external_id" VARCHAR(100) NOT NULL,
+	"athlete_id" UUID NOT NULL,
+	"created_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY("source", "external_id")
+);
+
+CREATE TABLE IF NOT EXISTS "competition_external_ids" (
+	"source" VARCHAR(50) NOT NULL,
+	"external_id" VARCHAR(100) NOT NULL,
+	"competition_id" UUID NOT NULL,
+	"created_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY("source", "external_id")
+);
+
+ALTER TABLE athlete_external_ids
+ADD CONSTRAINT athlete_external_ids_athlete_id_fkey
+FOREIGN KEY(athlete_id) REFERENCES athletes(athlete_id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+ALTER TABLE competition_external_ids
+ADD CONSTRAINT competition_external_ids_competition_id_fkey
+FOREIGN KEY(competition_id) REFERENCES competitions(competition_id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE INDEX athlete_external_ids_athlete_idx ON athlete_external_ids (athlete_id);
+CREATE INDEX competition_external_ids_competition_idx ON competition_external_ids (competition_id);
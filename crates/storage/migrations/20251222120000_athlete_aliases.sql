@@ -0,0 +1,18 @@
+-- Other spellings of an athlete's name found in sources (with or without accents,
+-- hyphenated, first and last name swapped), matched like the name on imports
+
+CREATE TABLE IF NOT EXISTS "athlete_aliases" (
+	"alias_id" UUID NOT NULL UNIQUE DEFAULT gen_random_uuid(),
+	"athlete_id" UUID NOT NULL,
+	"first_name" VARCHAR(255) NOT NULL,
+	"last_name" VARCHAR(255) NOT NULL,
+	"created_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY("alias_id")
+);
+
+ALTER TABLE athlete_aliases
+ADD CONSTRAINT athlete_aliases_athlete_id_fkey
+FOREIGN KEY(athlete_id) REFERENCES athletes(athlete_id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE UNIQUE INDEX athlete_aliases_athlete_name_idx ON athlete_aliases (athlete_id, last_name, first_name);
+CREATE INDEX athlete_aliases_name_idx ON athlete_aliases (last_name, first_name);
@@ -0,0 +1,49 @@
+-- Re-slug athletes with the rules of storage::normalize: slugs are transliterated
+-- to ASCII ("Éloïse Müller" -> "eloise-muller", "Søren" -> "soren") and every run
+-- of other characters becomes one dash. The previous slug is kept in slug_history
+-- so that old links still redirect.
+
+CREATE EXTENSION IF NOT EXISTS unaccent;
+
+DO $$
+DECLARE
+    athlete_record RECORD;
+    base_slug TEXT;
+    final_slug TEXT;
+    counter INTEGER;
+BEGIN
+    FOR athlete_record IN
+        SELECT athlete_id, first_name, last_name, slug
+        FROM athletes
+        ORDER BY created_at, athlete_id
+    LOOP
+        base_slug := trim(
+            both '-' from regexp_replace(
+                lower(unaccent(athlete_record.first_name || ' ' || athlete_record.last_name)),
+                '[^a-z0-9]+',
+                '-',
+                'g'
+            )
+        );
+
+        IF base_slug = '' THEN
+            base_slug := 'athlete';
+        END IF;
+
+        -- Slugs already following the rules are kept, with their number if any
+        CONTINUE WHEN athlete_record.slug ~ ('^' || base_slug || '(-[0-9]+)?$');
+
+        final_slug := base_slug;
+        counter := 2;
+
+        WHILE EXISTS (SELECT 1 FROM athletes WHERE slug = final_slug) LOOP
+            final_slug := base_slug || '-' || counter;
+            counter := counter + 1;
+        END LOOP;
+
+        UPDATE athletes
+        SET slug = final_slug,
+            slug_history = COALESCE(slug_history, '[]'::jsonb) || to_jsonb(athlete_record.slug)
+        WHERE athlete_id = athlete_record.athlete_id;
+    END LOOP;
+END $$;
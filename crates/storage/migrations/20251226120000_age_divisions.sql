@@ -0,0 +1,41 @@
+-- Birth date of athletes, or only the year when that is all a source gives, and the
+-- age divisions (sub-junior, junior, masters...) of each federation
+
+ALTER TABLE athletes ADD COLUMN birth_date DATE;
+ALTER TABLE athletes ADD COLUMN birth_year SMALLINT;
+
+ALTER TABLE athletes
+ADD CONSTRAINT athletes_birth_year_check
+CHECK (birth_date IS NULL OR birth_year = EXTRACT(YEAR FROM birth_date));
+
+CREATE TABLE IF NOT EXISTS "age_divisions" (
+	"age_division_id" UUID NOT NULL UNIQUE DEFAULT gen_random_uuid(),
+	"federation_id" UUID NOT NULL,
+	"name" VARCHAR(100) NOT NULL,
+	-- Ages in years at the competition date, both inclusive; no bound is open
+	"min_age" SMALLINT,
+	"max_age" SMALLINT,
+	"created_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY("age_division_id"),
+	CHECK (min_age IS NOT NULL OR max_age IS NOT NULL),
+	CHECK (min_age IS NULL OR max_age IS NULL OR min_age <= max_age)
+);
+
+ALTER TABLE age_divisions
+ADD CONSTRAINT age_divisions_federation_id_fkey
+FOREIGN KEY(federation_id) REFERENCES federations(federation_id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE UNIQUE INDEX age_divisions_federation_name_idx ON age_divisions (federation_id, name);
+
+-- Age of an athlete on a date, as computed by storage::models::Birth::age_on: exact
+-- with a birth date, the age reached during the year with only a birth year
+CREATE OR REPLACE FUNCTION athlete_age(birth_date DATE, birth_year SMALLINT, on_date DATE)
+RETURNS INTEGER
+LANGUAGE SQL
+IMMUTABLE
+AS $$
+    SELECT CASE
+        WHEN birth_date IS NOT NULL THEN EXTRACT(YEAR FROM age(on_date, birth_date))::INTEGER
+        WHEN birth_year IS NOT NULL THEN EXTRACT(YEAR FROM on_date)::INTEGER - birth_year
+    END
+$$;
@@ -0,0 +1,15 @@
+-- Official placings: competition_participants.rank is now computed once a
+-- competition is completed, with the tie-break rules of its federation, and the
+-- lot number drawn at the weigh-in is kept as the last tie-break.
+
+ALTER TABLE federations
+ADD COLUMN tie_breaks JSONB NOT NULL
+DEFAULT '["bodyweight", "earlier_achievement", "lot_number"]'::jsonb;
+
+ALTER TABLE competition_participants
+ADD COLUMN lot_number INTEGER CHECK (lot_number > 0);
+
+-- A movement without any successful attempt is kept with a best weight of 0, so
+-- that bombed-out athletes keep their attempts
+ALTER TABLE lifts DROP CONSTRAINT IF EXISTS lifts_max_weight_check;
+ALTER TABLE lifts ADD CONSTRAINT lifts_max_weight_check CHECK (max_weight >= 0);
@@ -0,0 +1,13 @@
+-- Record attempts: a fourth attempt some federations grant for a record. It counts
+-- for records but not for lifts.max_weight, totals and placings.
+
+ALTER TABLE attempts ADD COLUMN kind VARCHAR(10) NOT NULL DEFAULT 'regular';
+
+ALTER TABLE attempts DROP CONSTRAINT IF EXISTS attempts_attempt_number_check;
+
+ALTER TABLE attempts
+ADD CONSTRAINT attempts_attempt_number_check
+CHECK (
+    (kind = 'regular' AND attempt_number BETWEEN 1 AND 3)
+    OR (kind = 'record' AND attempt_number = 4)
+);
@@ -0,0 +1,7 @@
+-- Data migrations written in Rust (storage::services::data_migrations), for
+-- changes reusing rules of the storage crate. Each one is recorded here once run.
+
+CREATE TABLE data_migrations (
+    name VARCHAR(100) PRIMARY KEY,
+    applied_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
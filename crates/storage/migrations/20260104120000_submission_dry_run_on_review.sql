@@ -0,0 +1,4 @@
+-- The dry run of a submission is computed when a moderator first opens it, not on
+-- the anonymous upload: a dry run takes the locks of an import
+
+ALTER TABLE submissions ALTER COLUMN dry_run_diff DROP NOT NULL;
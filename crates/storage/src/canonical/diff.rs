@@ -0,0 +1,548 @@
+use super::loader::linked_competition;
+use crate::error::Result;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::PgConnection;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use uuid::Uuid;
+
+/// Effect of an import on the database, computed by comparing the state before
+/// and after running the transformer inside a transaction that is rolled back.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportDiff {
+    pub competition_slug: String,
+    pub competition: CompetitionDiff,
+    pub created_federations: Vec<String>,
+    pub created_categories: Vec<CategoryRef>,
+    pub created_athletes: Vec<AthleteRef>,
+    /// Athletes already stored, matched by alias or source id, whose stored fields change
+    pub updated_athletes: Vec<AthleteUpdate>,
+    pub participants: Vec<ParticipantDiff>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum CompetitionDiff {
+    Created,
+    Updated { fields: Vec<FieldChange> },
+    Unchanged,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct CategoryRef {
+    pub name: String,
+    pub gender: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AthleteRef {
+    pub slug: String,
+    pub first_name: String,
+    pub last_name: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AthleteUpdate {
+    #[serde(flatten)]
+    pub athlete: AthleteRef,
+    pub fields: Vec<FieldChange>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ParticipantDiff {
+    pub athlete_slug: String,
+    pub athlete_name: String,
+    pub category: String,
+    pub is_new: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bodyweight: Option<ValueChange<Decimal>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_disqualified: Option<ValueChange<bool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ris_score: Option<ValueChange<Decimal>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rank: Option<ValueChange<i32>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub attempts: Vec<AttemptDiff>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ValueChange<T> {
+    pub before: Option<T>,
+    pub after: Option<T>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AttemptDiff {
+    pub movement: String,
+    pub attempt_number: i16,
+    pub before: Option<AttemptValue>,
+    pub after: Option<AttemptValue>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AttemptValue {
+    pub weight: Decimal,
+    pub is_successful: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_rep_reason: Option<String>,
+}
+
+impl ImportDiff {
+    pub fn is_empty(&self) -> bool {
+        matches!(self.competition, CompetitionDiff::Unchanged)
+            && self.created_federations.is_empty()
+            && self.created_categories.is_empty()
+            && self.created_athletes.is_empty()
+            && self.updated_athletes.is_empty()
+            && self.participants.is_empty()
+    }
+
+    pub(crate) fn between(slug: &str, before: DatabaseSnapshot, after: DatabaseSnapshot) -> Self {
+        let competition = match (&before.competition, &after.competition) {
+            (None, _) => CompetitionDiff::Created,
+            (Some(b), Some(a)) => {
+                let fields = b
+                    .iter()
+                    .zip(a.iter())
+                    .filter(|((_, before), (_, after))| before != after)
+                    .map(|((field, before), (_, after))| FieldChange {
+                        field,
+                        before: before.clone(),
+                        after: after.clone(),
+                    })
+                    .collect::<Vec<_>>();
+
+                if fields.is_empty() {
+                    CompetitionDiff::Unchanged
+                } else {
+                    CompetitionDiff::Updated { fields }
+                }
+            }
+            (Some(_), None) => CompetitionDiff::Unchanged,
+        };
+
+        let created_federations = after
+            .federations
+            .difference(&before.federations)
+            .cloned()
+            .collect();
+
+        let created_categories = after
+            .categories
+            .difference(&before.categories)
+            .cloned()
+            .collect();
+
+        let created_athletes = after
+            .athletes
+            .iter()
+            .filter(|(id, _)| !before.athletes.contains_key(id))
+            .map(|(_, athlete)| athlete.reference.clone())
+            .collect();
+
+        let updated_athletes = after
+            .athletes
+            .iter()
+            .filter_map(|(id, current)| {
+                let previous = before.athletes.get(id)?;
+                let fields = previous
+                    .fields
+                    .iter()
+                    .zip(current.fields.iter())
+                    .filter(|((_, before), (_, after))| before != after)
+                    .map(|((field, before), (_, after))| FieldChange {
+                        field,
+                        before: before.clone(),
+                        after: after.clone(),
+                    })
+                    .collect::<Vec<_>>();
+
+                (!fields.is_empty()).then(|| AthleteUpdate {
+                    athlete: current.reference.clone(),
+                    fields,
+                })
+            })
+            .collect();
+
+        let mut participants = Vec::new();
+        for (participant_id, current) in &after.participants {
+            let previous = before.participants.get(participant_id);
+
+            let attempts = current
+                .attempts
+                .iter()
+                .filter_map(|(key, value)| {
+                    let old = previous.and_then(|p| p.attempts.get(key));
+                    (old != Some(value)).then(|| AttemptDiff {
+                        movement: key.0.clone(),
+                        attempt_number: key.1,
+                        before: old.cloned(),
+                        after: Some(value.clone()),
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            let diff = ParticipantDiff {
+                athlete_slug: current.athlete_slug.clone(),
+                athlete_name: current.athlete_name.clone(),
+                category: current.category.clone(),
+                is_new: previous.is_none(),
+                bodyweight: value_change(previous.and_then(|p| p.bodyweight), current.bodyweight),
+                is_disqualified: value_change(
+                    previous.map(|p| p.is_disqualified),
+                    Some(current.is_disqualified),
+                ),
+                ris_score: value_change(previous.and_then(|p| p.ris_score), current.ris_score),
+                rank: value_change(previous.and_then(|p| p.rank), current.rank),
+                attempts,
+            };
+
+            if diff.is_new
+                || diff.bodyweight.is_some()
+                || diff.is_disqualified.is_some()
+                || diff.ris_score.is_some()
+                || diff.rank.is_some()
+                || !diff.attempts.is_empty()
+            {
+                participants.push(diff);
+            }
+        }
+
+        participants
+            .sort_by(|a, b| (&a.category, &a.athlete_slug).cmp(&(&b.category, &b.athlete_slug)));
+
+        Self {
+            competition_slug: slug.to_string(),
+            competition,
+            created_federations,
+            created_categories,
+            created_athletes,
+            updated_athletes,
+            participants,
+        }
+    }
+}
+
+fn value_change<T: PartialEq>(before: Option<T>, after: Option<T>) -> Option<ValueChange<T>> {
+    (before != after).then_some(ValueChange { before, after })
+}
+
+impl fmt::Display for ImportDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.competition {
+            CompetitionDiff::Created => writeln!(f, "+ competition {}", self.competition_slug)?,
+            CompetitionDiff::Updated { fields } => {
+                writeln!(f, "~ competition {}", self.competition_slug)?;
+                for change in fields {
+                    writeln!(
+                        f,
+                        "    {}: {} -> {}",
+                        change.field,
+                        display_option(&change.before),
+                        display_option(&change.after)
+                    )?;
+                }
+            }
+            CompetitionDiff::Unchanged => {
+                writeln!(f, "  competition {} (unchanged)", self.competition_slug)?
+            }
+        }
+
+        for federation in &self.created_federations {
+            writeln!(f, "+ federation {}", federation)?;
+        }
+        for category in &self.created_categories {
+            writeln!(f, "+ category {} ({})", category.name, category.gender)?;
+        }
+        for athlete in &self.created_athletes {
+            writeln!(
+                f,
+                "+ athlete {} {} ({})",
+                athlete.first_name, athlete.last_name, athlete.slug
+            )?;
+        }
+        for update in &self.updated_athletes {
+            writeln!(
+                f,
+                "~ athlete {} {} ({})",
+                update.athlete.first_name, update.athlete.last_name, update.athlete.slug
+            )?;
+            for change in &update.fields {
+                writeln!(
+                    f,
+                    "    {}: {} -> {}",
+                    change.field,
+                    display_option(&change.before),
+                    display_option(&change.after)
+                )?;
+            }
+        }
+
+        for participant in &self.participants {
+            if participant.is_new {
+                writeln!(
+                    f,
+                    "+ participant {} [{}] bodyweight {}, RIS {}, placing {}, {} attempt(s)",
+                    participant.athlete_name,
+                    participant.category,
+                    display_option(&participant.bodyweight.as_ref().and_then(|c| c.after)),
+                    display_option(&participant.ris_score.as_ref().and_then(|c| c.after)),
+                    display_option(&participant.rank.as_ref().and_then(|c| c.after)),
+                    participant.attempts.len()
+                )?;
+                continue;
+            }
+
+            writeln!(
+                f,
+                "~ participant {} [{}]",
+                participant.athlete_name, participant.category
+            )?;
+            if let Some(change) = &participant.bodyweight {
+                writeln!(
+                    f,
+                    "    bodyweight: {} -> {}",
+                    display_option(&change.before),
+                    display_option(&change.after)
+                )?;
+            }
+            if let Some(change) = &participant.is_disqualified {
+                writeln!(
+                    f,
+                    "    disqualified: {} -> {}",
+                    display_option(&change.before),
+                    display_option(&change.after)
+                )?;
+            }
+            if let Some(change) = &participant.ris_score {
+                writeln!(
+                    f,
+                    "    RIS: {} -> {}",
+                    display_option(&change.before),
+                    display_option(&change.after)
+                )?;
+            }
+            if let Some(change) = &participant.rank {
+                writeln!(
+                    f,
+                    "    placing: {} -> {}",
+                    display_option(&change.before),
+                    display_option(&change.after)
+                )?;
+            }
+            for attempt in &participant.attempts {
+                writeln!(
+                    f,
+                    "    {} #{}: {} -> {}",
+                    attempt.movement,
+                    attempt.attempt_number,
+                    display_attempt(&attempt.before),
+                    display_attempt(&attempt.after)
+                )?;
+            }
+        }
+
+        if self.is_empty() {
+            writeln!(f, "No changes")?;
+        }
+
+        Ok(())
+    }
+}
+
+fn display_option<T: fmt::Display>(value: &Option<T>) -> String {
+    value
+        .as_ref()
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn display_attempt(attempt: &Option<AttemptValue>) -> String {
+    match attempt {
+        Some(a) if a.is_successful => format!("{} good", a.weight),
+        Some(a) => match &a.no_rep_reason {
+            Some(reason) => format!("{} no-rep ({})", a.weight, reason),
+            None => format!("{} no-rep", a.weight),
+        },
+        None => "-".to_string(),
+    }
+}
+
+/// State of the rows an import of one competition can touch
+pub(crate) struct DatabaseSnapshot {
+    competition: Option<Vec<(&'static str, Option<String>)>>,
+    federations: BTreeSet<String>,
+    categories: BTreeSet<CategoryRef>,
+    athletes: BTreeMap<Uuid, AthleteState>,
+    participants: BTreeMap<Uuid, ParticipantState>,
+}
+
+struct AthleteState {
+    reference: AthleteRef,
+    /// Fields an import may change on a stored athlete
+    fields: Vec<(&'static str, Option<String>)>,
+}
+
+struct ParticipantState {
+    athlete_slug: String,
+    athlete_name: String,
+    category: String,
+    bodyweight: Option<Decimal>,
+    is_disqualified: bool,
+    ris_score: Option<Decimal>,
+    rank: Option<i32>,
+    attempts: BTreeMap<(String, i16), AttemptValue>,
+}
+
+impl DatabaseSnapshot {
+    /// The competition is resolved as the loader does: by its LiftControl
+    /// contests first, then by slug
+    pub(crate) async fn take(
+        conn: &mut PgConnection,
+        slug: &str,
+        contest_ids: &[String],
+    ) -> Result<Self> {
+        let linked = linked_competition(&mut *conn, contest_ids).await?;
+        let competition = sqlx::query!(
+            r#"
+            SELECT c.competition_id, c.name, c.status, f.name as federation, c.venue, c.city,
+                   c.country, c.start_date, c.end_date, c.number_of_judge
+            FROM competitions c
+            JOIN federations f ON f.federation_id = c.federation_id
+            WHERE c.competition_id = $1 OR ($1 IS NULL AND c.slug = $2)
+            "#,
+            linked,
+            slug
+        )
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        let federations = sqlx::query_scalar!("SELECT name FROM federations")
+            .fetch_all(&mut *conn)
+            .await?
+            .into_iter()
+            .collect();
+
+        let categories = sqlx::query_as!(CategoryRef, "SELECT name, gender FROM categories")
+            .fetch_all(&mut *conn)
+            .await?
+            .into_iter()
+            .collect();
+
+        let athletes = sqlx::query!(
+            "SELECT athlete_id, slug, first_name, last_name, country, birth_year FROM athletes"
+        )
+        .fetch_all(&mut *conn)
+        .await?
+        .into_iter()
+        .map(|a| {
+            (
+                a.athlete_id,
+                AthleteState {
+                    reference: AthleteRef {
+                        slug: a.slug,
+                        first_name: a.first_name,
+                        last_name: a.last_name,
+                    },
+                    fields: vec![
+                        ("country", Some(a.country)),
+                        ("birth_year", a.birth_year.map(|y| y.to_string())),
+                    ],
+                },
+            )
+        })
+        .collect();
+
+        let mut participants = BTreeMap::new();
+
+        if let Some(competition) = &competition {
+            let rows = sqlx::query!(
+                r#"
+                SELECT cp.participant_id, a.slug, a.first_name, a.last_name, cat.name as category,
+                       cp.bodyweight, cp.is_disqualified, cp.ris_score, cp.rank
+                FROM competition_participants cp
+                JOIN athletes a ON a.athlete_id = cp.athlete_id
+                JOIN categories cat ON cat.category_id = cp.category_id
+                WHERE cp.competition_id = $1
+                "#,
+                competition.competition_id
+            )
+            .fetch_all(&mut *conn)
+            .await?;
+
+            for row in rows {
+                participants.insert(
+                    row.participant_id,
+                    ParticipantState {
+                        athlete_slug: row.slug,
+                        athlete_name: format!("{} {}", row.first_name, row.last_name),
+                        category: row.category,
+                        bodyweight: row.bodyweight,
+                        is_disqualified: row.is_disqualified,
+                        ris_score: row.ris_score,
+                        rank: row.rank,
+                        attempts: BTreeMap::new(),
+                    },
+                );
+            }
+
+            let attempts = sqlx::query!(
+                r#"
+                SELECT l.participant_id, l.movement_name, at.attempt_number, at.weight,
+                       at.is_successful, at.no_rep_reason
+                FROM attempts at
+                JOIN lifts l ON l.lift_id = at.lift_id
+                JOIN competition_participants cp ON cp.participant_id = l.participant_id
+                WHERE cp.competition_id = $1
+                "#,
+                competition.competition_id
+            )
+            .fetch_all(&mut *conn)
+            .await?;
+
+            for attempt in attempts {
+                if let Some(participant) = participants.get_mut(&attempt.participant_id) {
+                    participant.attempts.insert(
+                        (attempt.movement_name, attempt.attempt_number),
+                        AttemptValue {
+                            weight: attempt.weight,
+                            is_successful: attempt.is_successful,
+                            no_rep_reason: attempt.no_rep_reason,
+                        },
+                    );
+                }
+            }
+        }
+
+        let competition = competition.map(|c| {
+            vec![
+                ("name", Some(c.name)),
+                ("status", Some(c.status)),
+                ("federation", Some(c.federation)),
+                ("venue", c.venue),
+                ("city", c.city),
+                ("country", c.country),
+                ("start_date", Some(c.start_date.to_string())),
+                ("end_date", Some(c.end_date.to_string())),
+                ("number_of_judges", c.number_of_judge.map(|n| n.to_string())),
+            ]
+        });
+
+        Ok(Self {
+            competition,
+            federations,
+            categories,
+            athletes,
+            participants,
+        })
+    }
+}
@@ -1,31 +1,144 @@
+use super::diff::{DatabaseSnapshot, ImportDiff};
 use super::models::*;
-use crate::{ImporterError, Result};
-use sqlx::PgPool;
-use storage::models::NormalizedAthleteName;
+use crate::error::{Result, StorageError};
+use crate::models::{Birth, NormalizedAthleteName};
+use crate::normalize;
+use crate::services::placings;
+use rust_decimal::Decimal;
+use sha2::{Digest, Sha256};
+use sqlx::{PgConnection, PgPool};
 use tracing::info;
 use uuid::Uuid;
 
-pub struct CanonicalTransformer<'a> {
+/// Where a canonical document was read from, recorded as its import batch
+#[derive(Debug, Clone)]
+pub struct ImportOrigin {
+    pub file_path: Option<String>,
+    /// SHA-256 of the document content, hex encoded
+    pub content_hash: String,
+}
+
+impl ImportOrigin {
+    pub fn new(file_path: Option<String>, content: &[u8]) -> Self {
+        Self {
+            file_path,
+            content_hash: Self::hash(content),
+        }
+    }
+
+    pub fn hash(content: &[u8]) -> String {
+        format!("{:x}", Sha256::digest(content))
+    }
+}
+
+/// LiftControl contests of a document, as stored in `competition_external_ids`
+fn contest_ids(canonical: &CanonicalFormat) -> Vec<String> {
+    canonical
+        .liftcontrol_metadata
+        .iter()
+        .flat_map(|m| m.contest_ids())
+        .map(|id| id.to_string())
+        .collect()
+}
+
+/// The competition linked to one of the LiftControl contests, the first one listed
+/// preferably
+pub(crate) async fn linked_competition(
+    conn: &mut PgConnection,
+    contest_ids: &[String],
+) -> Result<Option<Uuid>> {
+    if contest_ids.is_empty() {
+        return Ok(None);
+    }
+
+    let linked = sqlx::query_scalar!(
+        r#"
+        SELECT competition_id as "competition_id: Uuid"
+        FROM competition_external_ids
+        WHERE source = $1 AND external_id = ANY($2::text[])
+        ORDER BY array_position($2::text[], external_id::text)
+        LIMIT 1
+        "#,
+        SourceType::LiftControl.as_str(),
+        contest_ids
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(linked)
+}
+
+pub struct CanonicalLoader<'a> {
     pool: &'a PgPool,
 }
 
-impl<'a> CanonicalTransformer<'a> {
+impl<'a> CanonicalLoader<'a> {
     pub fn new(pool: &'a PgPool) -> Self {
         Self { pool }
     }
 
-    pub async fn import_to_database(&self, canonical: CanonicalFormat) -> Result<()> {
+    pub async fn import_to_database(
+        &self,
+        canonical: CanonicalFormat,
+        origin: &ImportOrigin,
+    ) -> Result<()> {
         let mut tx = self.pool.begin().await?;
+        self.apply(&canonical, origin, &mut tx).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Imports within a transaction of the caller, committed or rolled back along
+    /// with the caller's own changes
+    pub async fn import_in_transaction(
+        &self,
+        canonical: &CanonicalFormat,
+        origin: &ImportOrigin,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<()> {
+        self.apply(canonical, origin, tx).await
+    }
+
+    /// Runs the import in a transaction that is rolled back and reports what it would change
+    pub async fn dry_run(
+        &self,
+        canonical: CanonicalFormat,
+        origin: &ImportOrigin,
+    ) -> Result<ImportDiff> {
+        let slug = canonical.competition.slug.clone();
+        let contest_ids = contest_ids(&canonical);
+        let mut tx = self.pool.begin().await?;
+
+        let before = DatabaseSnapshot::take(&mut tx, &slug, &contest_ids).await?;
+        self.apply(&canonical, origin, &mut tx).await?;
+        let after = DatabaseSnapshot::take(&mut tx, &slug, &contest_ids).await?;
+
+        tx.rollback().await?;
+        Ok(ImportDiff::between(&slug, before, after))
+    }
+
+    async fn apply(
+        &self,
+        canonical: &CanonicalFormat,
+        origin: &ImportOrigin,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<()> {
+        let import_batch_id = self.create_import_batch(canonical, origin, tx).await?;
 
         let competition_id = self
-            .upsert_competition(&canonical.competition, &mut tx)
+            .upsert_competition(
+                &canonical.competition,
+                &contest_ids(canonical),
+                import_batch_id,
+                tx,
+            )
             .await?;
 
-        self.upsert_competition_movements(competition_id, &canonical.movements, &mut tx)
+        self.upsert_competition_movements(competition_id, &canonical.movements, tx)
             .await?;
 
         for category in &canonical.categories {
-            let category_id = self.upsert_category(category, &mut tx).await?;
+            let category_id = self.upsert_category(category, tx).await?;
 
             for athlete in &category.athletes {
                 self.import_athlete_performance(
@@ -33,34 +146,104 @@ impl<'a> CanonicalTransformer<'a> {
                     category,
                     competition_id,
                     category_id,
-                    &canonical.movements,
-                    &mut tx,
+                    import_batch_id,
+                    tx,
                 )
                 .await?;
             }
         }
 
         info!("Computing RIS scores for all participants...");
-        self.compute_ris_for_competition(competition_id, canonical.competition.start_date, &mut tx)
+        self.compute_ris_for_competition(competition_id, canonical.competition.start_date, tx)
             .await?;
 
-        tx.commit().await?;
+        let placed = placings::update_placings(tx, competition_id).await?;
+        info!("Placed {} participants", placed);
+
         Ok(())
     }
 
+    async fn create_import_batch(
+        &self,
+        canonical: &CanonicalFormat,
+        origin: &ImportOrigin,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<Uuid> {
+        let athletes = canonical.categories.iter().flat_map(|c| &c.athletes);
+        let participant_count = athletes.clone().count() as i32;
+        let attempt_count = athletes
+            .flat_map(|a| &a.lifts)
+            .map(|l| l.attempts.len())
+            .sum::<usize>() as i32;
+
+        let import_batch_id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO import_batches
+                (source_type, source_url, file_path, content_hash, format_version, extractor,
+                 extracted_at, importer_version, category_count, participant_count, attempt_count)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            RETURNING import_batch_id
+            "#,
+            canonical.source.r#type.as_str(),
+            canonical.source.url,
+            origin.file_path,
+            origin.content_hash,
+            canonical.format_version,
+            canonical.source.extractor,
+            canonical.source.extracted_at,
+            env!("CARGO_PKG_VERSION"),
+            canonical.categories.len() as i32,
+            participant_count,
+            attempt_count
+        )
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Ok(import_batch_id)
+    }
+
+    /// A competition linked to one of the LiftControl contests, the first one
+    /// listed preferably, is updated whatever its slug, otherwise the competition
+    /// is matched by slug. Every contest is then linked to it.
     async fn upsert_competition(
         &self,
         competition: &CompetitionData,
+        contest_ids: &[String],
+        import_batch_id: Uuid,
         tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
     ) -> Result<Uuid> {
+        if let Some(id) = linked_competition(tx, contest_ids).await? {
+            sqlx::query!(
+                r#"
+                UPDATE competitions
+                SET name = $2, status = $3, venue = $4, city = $5, country = $6,
+                    number_of_judge = $7, import_batch_id = $8
+                WHERE competition_id = $1
+                "#,
+                id,
+                competition.name,
+                competition.status.as_deref().unwrap_or("completed"),
+                competition.venue,
+                competition.city,
+                competition.country,
+                competition.number_of_judges,
+                import_batch_id
+            )
+            .execute(&mut **tx)
+            .await?;
+
+            self.link_contests(id, contest_ids, tx).await?;
+            return Ok(id);
+        }
+
         let federation_id = self
             .get_or_create_federation(&competition.federation, tx)
             .await?;
 
         let competition_id = sqlx::query_scalar!(
             r#"
-            INSERT INTO competitions (name, slug, status, federation_id, start_date, end_date, venue, city, country, number_of_judge)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            INSERT INTO competitions (name, slug, status, federation_id, start_date, end_date, venue, city, country, number_of_judge, import_batch_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
             ON CONFLICT (slug)
             DO UPDATE SET
                 name = EXCLUDED.name,
@@ -68,7 +251,8 @@ impl<'a> CanonicalTransformer<'a> {
                 venue = EXCLUDED.venue,
                 city = EXCLUDED.city,
                 country = EXCLUDED.country,
-                number_of_judge = EXCLUDED.number_of_judge
+                number_of_judge = EXCLUDED.number_of_judge,
+                import_batch_id = EXCLUDED.import_batch_id
             RETURNING competition_id as "competition_id: Uuid"
             "#,
             competition.name,
@@ -80,14 +264,42 @@ impl<'a> CanonicalTransformer<'a> {
             competition.venue,
             competition.city,
             competition.country,
-            competition.number_of_judges
+            competition.number_of_judges,
+            import_batch_id
         )
         .fetch_one(&mut **tx)
         .await?;
 
+        self.link_contests(competition_id, contest_ids, tx).await?;
+
         Ok(competition_id)
     }
 
+    /// Records the LiftControl contests of a competition, leaving the contests
+    /// already linked as they are
+    async fn link_contests(
+        &self,
+        competition_id: Uuid,
+        contest_ids: &[String],
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO competition_external_ids (source, external_id, competition_id)
+            SELECT $1, external_id, $3
+            FROM UNNEST($2::text[]) AS external_id
+            ON CONFLICT (source, external_id) DO NOTHING
+            "#,
+            SourceType::LiftControl.as_str(),
+            contest_ids,
+            competition_id
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
     async fn get_or_create_federation(
         &self,
         federation: &FederationData,
@@ -116,9 +328,7 @@ impl<'a> CanonicalTransformer<'a> {
         )
         .fetch_one(&mut **tx)
         .await
-        .map_err(|e| {
-            ImporterError::TransformationError(format!("Failed to create federation: {}", e))
-        })?;
+        .map_err(|e| StorageError::Import(format!("Failed to create federation: {}", e)))?;
 
         Ok(federation_id)
     }
@@ -191,7 +401,7 @@ impl<'a> CanonicalTransformer<'a> {
         category: &CategoryData,
         competition_id: Uuid,
         category_id: Uuid,
-        _movements: &[MovementData],
+        import_batch_id: Uuid,
         tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
     ) -> Result<()> {
         let athlete_id = self.upsert_athlete(athlete, category, tx).await?;
@@ -201,22 +411,24 @@ impl<'a> CanonicalTransformer<'a> {
         sqlx::query!(
             r#"
             INSERT INTO competition_participants
-                (competition_id, category_id, athlete_id, bodyweight, rank, is_disqualified, disqualified_reason)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+                (competition_id, category_id, athlete_id, bodyweight, lot_number, is_disqualified, disqualified_reason, import_batch_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
             ON CONFLICT (competition_id, category_id, athlete_id)
             DO UPDATE SET
                 bodyweight = EXCLUDED.bodyweight,
-                rank = EXCLUDED.rank,
+                lot_number = EXCLUDED.lot_number,
                 is_disqualified = EXCLUDED.is_disqualified,
-                disqualified_reason = EXCLUDED.disqualified_reason
+                disqualified_reason = EXCLUDED.disqualified_reason,
+                import_batch_id = EXCLUDED.import_batch_id
             "#,
             competition_id,
             category_id,
             athlete_id,
             athlete.bodyweight,
-            None as Option<i32>,
+            athlete.lot_number,
             is_disqualified,
-            athlete.disqualified_reason
+            athlete.disqualified_reason,
+            import_batch_id
         )
         .execute(&mut **tx)
         .await?;
@@ -242,49 +454,155 @@ impl<'a> CanonicalTransformer<'a> {
         category: &CategoryData,
         tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
     ) -> Result<Uuid> {
+        let birth =
+            Birth::new(athlete.birth_date, athlete.birth_year).map_err(StorageError::Import)?;
+
+        // Registrations of every merged session, the first listing's first
+        let registration_ids = athlete
+            .liftcontrol_athlete_metadata
+            .iter()
+            .flat_map(|m| m.athlete_ids())
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>();
+        if !registration_ids.is_empty() {
+            let linked = sqlx::query_scalar!(
+                r#"
+                SELECT athlete_id as "athlete_id: Uuid" FROM athlete_external_ids
+                WHERE source = $1 AND external_id = ANY($2::text[])
+                ORDER BY array_position($2::text[], external_id::text)
+                LIMIT 1
+                "#,
+                SourceType::LiftControl.as_str(),
+                &registration_ids
+            )
+            .fetch_optional(&mut **tx)
+            .await?;
+
+            if let Some(id) = linked {
+                self.link_registrations(id, &registration_ids, tx).await?;
+                self.update_birth(id, birth, tx).await?;
+                return Ok(id);
+            }
+        }
+
         let gender = athlete.gender.as_deref().unwrap_or(&category.gender);
 
         let normalized_name = NormalizedAthleteName::new(&athlete.first_name, &athlete.last_name);
         let (db_first_name, db_last_name) = normalized_name.as_database_tuple();
 
+        // An athlete with the name is preferred to one with it as an alias, and one
+        // born another year is a namesake
         let existing = sqlx::query_scalar!(
             r#"
-            SELECT athlete_id as "athlete_id: Uuid" FROM athletes
-            WHERE first_name = $1 AND last_name = $2 AND gender = $3 AND country = $4
+            SELECT a.athlete_id as "athlete_id: Uuid" FROM athletes a
+            WHERE a.gender = $3 AND a.country = $4
+              AND ($5::smallint IS NULL OR a.birth_year IS NULL OR a.birth_year = $5)
+              AND ((a.first_name = $1 AND a.last_name = $2)
+                   OR EXISTS (
+                       SELECT 1 FROM athlete_aliases al
+                       WHERE al.athlete_id = a.athlete_id AND al.first_name = $1 AND al.last_name = $2
+                   ))
+            ORDER BY (a.first_name = $1 AND a.last_name = $2) DESC, a.created_at
+            LIMIT 1
             "#,
             db_first_name,
             db_last_name,
             gender,
-            athlete.country
+            athlete.country,
+            birth.year()
         )
         .fetch_optional(&mut **tx)
         .await?;
 
-        if let Some(id) = existing {
-            return Ok(id);
-        }
+        let athlete_id = match existing {
+            Some(id) => id,
+            None => {
+                let slug = self
+                    .generate_unique_slug(db_first_name, db_last_name, &mut *tx)
+                    .await?;
+
+                sqlx::query_scalar!(
+                    r#"
+                    INSERT INTO athletes (first_name, last_name, gender, country, nationality, slug)
+                    VALUES ($1, $2, $3, $4, $5, $6)
+                    RETURNING athlete_id as "athlete_id: Uuid"
+                    "#,
+                    db_first_name,
+                    db_last_name,
+                    gender,
+                    athlete.country,
+                    athlete.nationality,
+                    slug
+                )
+                .fetch_one(&mut **tx)
+                .await?
+            }
+        };
 
-        let slug = self
-            .generate_unique_slug(db_first_name, db_last_name, &mut *tx)
+        self.link_registrations(athlete_id, &registration_ids, tx)
             .await?;
+        self.update_birth(athlete_id, birth, tx).await?;
+
+        Ok(athlete_id)
+    }
 
-        let athlete_id = sqlx::query_scalar!(
+    /// Records the LiftControl registrations of an athlete, leaving the ones
+    /// already linked as they are
+    async fn link_registrations(
+        &self,
+        athlete_id: Uuid,
+        registration_ids: &[String],
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<()> {
+        sqlx::query!(
             r#"
-            INSERT INTO athletes (first_name, last_name, gender, country, nationality, slug)
-            VALUES ($1, $2, $3, $4, $5, $6)
-            RETURNING athlete_id as "athlete_id: Uuid"
+            INSERT INTO athlete_external_ids (source, external_id, athlete_id)
+            SELECT $1, external_id, $3
+            FROM UNNEST($2::text[]) AS external_id
+            ON CONFLICT (source, external_id) DO NOTHING
             "#,
-            db_first_name,
-            db_last_name,
-            gender,
-            athlete.country,
-            athlete.nationality,
-            slug
+            SourceType::LiftControl.as_str(),
+            registration_ids,
+            athlete_id
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Records what the file tells of an athlete's birth, see [`Birth::updated`]
+    async fn update_birth(
+        &self,
+        athlete_id: Uuid,
+        birth: Birth,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<()> {
+        if birth == Birth::default() {
+            return Ok(());
+        }
+
+        let stored = sqlx::query!(
+            "SELECT birth_date, birth_year FROM athletes WHERE athlete_id = $1",
+            athlete_id
         )
         .fetch_one(&mut **tx)
         .await?;
+        let known = Birth::new(stored.birth_date, stored.birth_year).unwrap_or_default();
 
-        Ok(athlete_id)
+        let updated = known.updated(birth);
+        if updated != known {
+            sqlx::query!(
+                "UPDATE athletes SET birth_date = $2, birth_year = $3 WHERE athlete_id = $1",
+                athlete_id,
+                updated.date(),
+                updated.year()
+            )
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        Ok(())
     }
 
     async fn generate_unique_slug(
@@ -293,21 +611,7 @@ impl<'a> CanonicalTransformer<'a> {
         last_name: &str,
         tx: &mut sqlx::PgConnection,
     ) -> Result<String> {
-        let base_slug = format!("{}-{}", first_name, last_name)
-            .to_lowercase()
-            .chars()
-            .filter(|c| c.is_alphanumeric() || *c == '-')
-            .collect::<String>()
-            .split('-')
-            .filter(|s| !s.is_empty())
-            .collect::<Vec<&str>>()
-            .join("-");
-
-        let base_slug = if base_slug.is_empty() {
-            "athlete".to_string()
-        } else {
-            base_slug
-        };
+        let base_slug = normalize::athlete_slug(first_name, last_name);
 
         let mut final_slug = base_slug.clone();
         let mut counter = 2;
@@ -336,14 +640,19 @@ impl<'a> CanonicalTransformer<'a> {
         athlete: &AthleteData,
         tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
     ) -> Result<()> {
+        // A movement without any successful attempt is kept with a best weight of 0,
+        // and a record attempt does not count for it
         let max_weight = lift
             .attempts
             .iter()
-            .filter(|a| a.is_successful)
+            .filter(|a| a.is_successful && a.kind.is_regular())
             .map(|a| a.weight)
-            .max();
+            .max()
+            .unwrap_or(Decimal::ZERO);
 
-        let settings = if lift.movement == "Dips" {
+        let settings = if lift.equipment_setting.is_some() {
+            lift.equipment_setting.clone()
+        } else if lift.movement == "Dips" {
             athlete
                 .liftcontrol_athlete_metadata
                 .as_ref()
@@ -417,10 +726,11 @@ impl<'a> CanonicalTransformer<'a> {
 
         sqlx::query!(
             r#"
-            INSERT INTO attempts (lift_id, attempt_number, weight, is_successful, passing_judges, no_rep_reason, created_by)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            INSERT INTO attempts (lift_id, attempt_number, weight, is_successful, passing_judges, no_rep_reason, created_by, kind)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
             ON CONFLICT (lift_id, attempt_number)
             DO UPDATE SET
+                kind = EXCLUDED.kind,
                 weight = EXCLUDED.weight,
                 is_successful = EXCLUDED.is_successful,
                 passing_judges = EXCLUDED.passing_judges,
@@ -433,7 +743,8 @@ impl<'a> CanonicalTransformer<'a> {
             attempt.is_successful,
             None as Option<i16>,
             attempt.no_rep_reason,
-            "Canonical Importer"
+            "Canonical Importer",
+            attempt.kind.as_str()
         )
         .execute(&mut **tx)
         .await?;
@@ -448,10 +759,10 @@ impl<'a> CanonicalTransformer<'a> {
         tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
     ) -> Result<()> {
         let formula =
-            storage::services::ris_computation::get_formula_for_date(self.pool, competition_date)
+            crate::services::ris_computation::get_formula_for_date(self.pool, competition_date)
                 .await
                 .map_err(|e| {
-                    ImporterError::TransformationError(format!(
+                    StorageError::Import(format!(
                         "No RIS formula available for date {}: {}",
                         competition_date, e
                     ))
@@ -480,7 +791,7 @@ impl<'a> CanonicalTransformer<'a> {
 
         for participant in participants {
             if let Some(bodyweight) = participant.bodyweight {
-                let ris_score = storage::services::ris_computation::compute_ris(
+                let ris_score = crate::services::ris_computation::compute_ris(
                     bodyweight,
                     participant.total,
                     &participant.gender,
@@ -488,7 +799,7 @@ impl<'a> CanonicalTransformer<'a> {
                 )
                 .await
                 .map_err(|e| {
-                    ImporterError::TransformationError(format!(
+                    StorageError::Import(format!(
                         "Failed to compute RIS for participant {}: {}",
                         participant.participant_id, e
                     ))
@@ -0,0 +1,6 @@
+//! Canonical competition format shared by all importers, and the loader writing it to the database
+
+pub mod diff;
+pub mod loader;
+pub mod models;
+pub mod upgrade;
@@ -0,0 +1,254 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Version written by every producer of the canonical format.
+/// Older documents are brought to it by `canonical::upgrade` before being read.
+pub const CANONICAL_FORMAT_VERSION: &str = "1.5.0";
+
+/// A competition as produced by an importer, reviewed, committed under `imports/`
+/// and loaded into the database
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[schemars(title = "OpenStreetLifting canonical competition format")]
+pub struct CanonicalFormat {
+    pub format_version: String,
+    pub source: SourceMetadata,
+    pub competition: CompetitionData,
+    pub movements: Vec<MovementData>,
+    pub categories: Vec<CategoryData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub liftcontrol_metadata: Option<LiftControlMetadata>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pdf_metadata: Option<PdfMetadata>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SourceMetadata {
+    #[serde(rename = "type")]
+    pub r#type: SourceType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    pub extracted_at: DateTime<Utc>,
+    pub extractor: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_filename: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceType {
+    LiftControl,
+    Database,
+    Pdf,
+    Html,
+    Csv,
+    Manual,
+}
+
+impl SourceType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SourceType::LiftControl => "liftcontrol",
+            SourceType::Database => "database",
+            SourceType::Pdf => "pdf",
+            SourceType::Html => "html",
+            SourceType::Csv => "csv",
+            SourceType::Manual => "manual",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CompetitionData {
+    pub name: String,
+    pub slug: String,
+    pub federation: FederationData,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub venue: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub city: Option<String>,
+    pub country: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number_of_judges: Option<i16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FederationData {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slug: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub abbreviation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MovementData {
+    pub name: String,
+    pub order: i16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_required: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CategoryData {
+    pub name: String,
+    pub gender: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weight_class_min: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weight_class_max: Option<Decimal>,
+    pub athletes: Vec<AthleteData>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AthleteData {
+    pub first_name: String,
+    pub last_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gender: Option<String>,
+    pub country: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nationality: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub birth_date: Option<NaiveDate>,
+    /// When the birth date is not known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub birth_year: Option<i16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bodyweight: Option<Decimal>,
+    /// Lot number drawn at the weigh-in, the last tie-break of placings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lot_number: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_disqualified: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disqualified_reason: Option<String>,
+    pub lifts: Vec<LiftData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub liftcontrol_athlete_metadata: Option<LiftControlAthleteMetadata>,
+    /// Session the athlete was taken from when several session exports were merged
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_session: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LiftData {
+    pub movement: String,
+    pub attempts: Vec<AttemptData>,
+    /// Equipment setting (e.g. dips bar or squat rack height), takes precedence
+    /// over the one found in LiftControl athlete metadata
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub equipment_setting: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AttemptData {
+    pub attempt_number: i16,
+    pub weight: Decimal,
+    pub is_successful: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_rep_reason: Option<String>,
+    #[serde(default, skip_serializing_if = "AttemptKind::is_regular")]
+    pub kind: AttemptKind,
+}
+
+/// Regular attempts, numbered 1 to 3, count for the total. A record attempt is
+/// the fourth attempt some federations grant for a record: it counts for records
+/// but not for the total.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AttemptKind {
+    #[default]
+    Regular,
+    Record,
+}
+
+impl AttemptKind {
+    pub const RECORD_ATTEMPT_NUMBER: i16 = 4;
+
+    /// Kind of an attempt known only by its number, in sources without a kind
+    pub fn for_attempt_number(attempt_number: i16) -> Self {
+        if attempt_number == Self::RECORD_ATTEMPT_NUMBER {
+            AttemptKind::Record
+        } else {
+            AttemptKind::Regular
+        }
+    }
+
+    /// Kind stored in `attempts.kind`
+    pub fn from_db(kind: &str) -> Self {
+        if kind == AttemptKind::Record.as_str() {
+            AttemptKind::Record
+        } else {
+            AttemptKind::Regular
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttemptKind::Regular => "regular",
+            AttemptKind::Record => "record",
+        }
+    }
+
+    pub fn is_regular(&self) -> bool {
+        *self == AttemptKind::Regular
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LiftControlMetadata {
+    pub contest_id: i32,
+    /// Contests of the other sessions when several session exports were merged
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub merged_contest_ids: Vec<i32>,
+}
+
+impl LiftControlMetadata {
+    /// Every LiftControl contest the document was built from, its own first
+    pub fn contest_ids(&self) -> impl Iterator<Item = i32> + '_ {
+        std::iter::once(self.contest_id).chain(self.merged_contest_ids.iter().copied())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LiftControlAthleteMetadata {
+    pub athlete_id: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reglage_dips: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reglage_squat: Option<String>,
+    /// Registrations of the athlete in the other sessions when several session
+    /// exports were merged
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub merged_athlete_ids: Vec<i32>,
+}
+
+impl LiftControlAthleteMetadata {
+    /// Every LiftControl registration of the athlete, its own first
+    pub fn athlete_ids(&self) -> impl Iterator<Item = i32> + '_ {
+        std::iter::once(self.athlete_id).chain(self.merged_athlete_ids.iter().copied())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PdfMetadata {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extraction_confidence: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pages_processed: Option<Vec<i32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warnings: Option<Vec<String>>,
+}
+
+/// JSON Schema of the current canonical format, generated from the types above
+pub fn json_schema() -> serde_json::Value {
+    schemars::schema_for!(CanonicalFormat).to_value()
+}
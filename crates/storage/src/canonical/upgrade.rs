@@ -0,0 +1,155 @@
+use super::models::{CANONICAL_FORMAT_VERSION, CanonicalFormat};
+use crate::error::{Result, StorageError};
+use serde_json::Value;
+use tracing::info;
+
+type UpgradeStep = fn(&mut Value);
+
+/// Each step turns a document of `from` into a document of `to`, in order.
+/// Adding a field to the format means bumping `CANONICAL_FORMAT_VERSION` and
+/// appending a step, so that files committed under `imports/` keep loading.
+const UPGRADES: &[(&str, &str, UpgradeStep)] = &[
+    ("1.0.0", "1.1.0", upgrade_1_0_0),
+    ("1.1.0", "1.2.0", upgrade_1_1_0),
+    ("1.2.0", "1.3.0", upgrade_1_2_0),
+    ("1.3.0", "1.4.0", upgrade_1_3_0),
+    ("1.4.0", "1.5.0", upgrade_1_4_0),
+];
+
+/// 1.1.0 adds the optional `source_session` of athletes, written by the session merge
+fn upgrade_1_0_0(_document: &mut Value) {}
+
+/// 1.2.0 adds the optional `birth_date` and `birth_year` of athletes
+fn upgrade_1_1_0(_document: &mut Value) {}
+
+/// 1.3.0 adds the optional `lot_number` of athletes
+fn upgrade_1_2_0(_document: &mut Value) {}
+
+/// 1.4.0 adds the optional `kind` of attempts, allowing a fourth record attempt
+fn upgrade_1_3_0(_document: &mut Value) {}
+
+/// 1.5.0 adds the optional `merged_contest_ids` of the LiftControl metadata and
+/// `merged_athlete_ids` of the LiftControl athlete metadata, written by the session merge
+fn upgrade_1_4_0(_document: &mut Value) {}
+
+/// Reads a canonical document of any supported version as the current version
+pub fn parse(json: &str) -> Result<CanonicalFormat> {
+    let document = serde_json::from_str(json).map_err(invalid_document)?;
+    serde_json::from_value(upgrade(document)?).map_err(invalid_document)
+}
+
+/// Applies the upgrade steps needed to bring a document to `CANONICAL_FORMAT_VERSION`
+pub fn upgrade(mut document: Value) -> Result<Value> {
+    let original = document
+        .get("format_version")
+        .and_then(Value::as_str)
+        .ok_or_else(|| StorageError::Import("Missing format_version".to_string()))?
+        .to_string();
+
+    let mut version = original.clone();
+    while version != CANONICAL_FORMAT_VERSION {
+        let Some((_, to, step)) = UPGRADES.iter().find(|(from, _, _)| *from == version) else {
+            return Err(StorageError::Import(
+                if is_newer(&version, CANONICAL_FORMAT_VERSION) {
+                    format!(
+                        "Format version {} is newer than {}, update the importer",
+                        version, CANONICAL_FORMAT_VERSION
+                    )
+                } else {
+                    format!("Unsupported format version {}", version)
+                },
+            ));
+        };
+
+        step(&mut document);
+        document["format_version"] = Value::from(*to);
+        version = to.to_string();
+    }
+
+    if original != version {
+        info!(
+            "Upgraded canonical document from {} to {}",
+            original, version
+        );
+    }
+
+    Ok(document)
+}
+
+fn is_newer(version: &str, than: &str) -> bool {
+    let parse = |v: &str| {
+        v.split('.')
+            .map(|part| part.parse::<u32>().unwrap_or(0))
+            .collect::<Vec<_>>()
+    };
+    parse(version) > parse(than)
+}
+
+fn invalid_document(error: serde_json::Error) -> StorageError {
+    StorageError::Import(format!("Invalid canonical document: {}", error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_upgrade_chain_reaches_current_version() {
+        let last = UPGRADES.last().map(|(_, to, _)| *to);
+        assert_eq!(last, Some(CANONICAL_FORMAT_VERSION));
+
+        for window in UPGRADES.windows(2) {
+            assert_eq!(window[0].1, window[1].0);
+        }
+    }
+
+    #[test]
+    fn test_unsupported_versions() {
+        let newer = upgrade(serde_json::json!({ "format_version": "9.0.0" })).unwrap_err();
+        assert!(newer.to_string().contains("newer"));
+
+        let unknown = upgrade(serde_json::json!({ "format_version": "0.9.0" })).unwrap_err();
+        assert!(unknown.to_string().contains("Unsupported"));
+
+        assert!(upgrade(serde_json::json!({})).is_err());
+    }
+
+    #[test]
+    fn test_committed_imports_still_load() {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../imports");
+        let mut loaded = 0;
+
+        for competition in std::fs::read_dir(dir).unwrap() {
+            for file in std::fs::read_dir(competition.unwrap().path()).unwrap() {
+                let json = std::fs::read_to_string(file.unwrap().path()).unwrap();
+                let canonical = parse(&json).unwrap();
+
+                assert_eq!(canonical.format_version, CANONICAL_FORMAT_VERSION);
+                loaded += 1;
+            }
+        }
+
+        assert!(loaded > 0);
+    }
+
+    #[test]
+    fn test_json_schema_describes_the_format() {
+        let schema = super::super::models::json_schema();
+
+        let required = schema["required"].as_array().unwrap();
+        assert!(required.contains(&Value::from("format_version")));
+        assert!(required.contains(&Value::from("categories")));
+        assert!(schema["$defs"]["AthleteData"]["properties"]["source_session"].is_object());
+        assert!(schema["$defs"]["AthleteData"]["properties"]["birth_year"].is_object());
+        assert!(schema["$defs"]["AthleteData"]["properties"]["lot_number"].is_object());
+        assert!(schema["$defs"]["AttemptData"]["properties"]["kind"].is_object());
+        assert!(
+            schema["$defs"]["LiftControlMetadata"]["properties"]["merged_contest_ids"].is_object()
+        );
+        assert!(
+            schema["$defs"]["LiftControlAthleteMetadata"]["properties"]["merged_athlete_ids"]
+                .is_object()
+        );
+    }
+}
@@ -1,9 +1,11 @@
-use chrono::NaiveDateTime;
+use chrono::{NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 use validator::Validate;
 
+use crate::models::Birth;
+
 /// Response containing basic athlete information
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct AthleteResponse {
@@ -15,6 +17,7 @@ pub struct AthleteResponse {
     pub nationality: Option<String>,
     pub country: String,
     pub profile_picture_url: Option<String>,
+    pub birth_year: Option<i16>,
     pub created_at: NaiveDateTime,
 }
 
@@ -29,6 +32,7 @@ pub struct AthleteDetailResponse {
     pub nationality: Option<String>,
     pub country: String,
     pub profile_picture_url: Option<String>,
+    pub birth_year: Option<i16>,
     pub created_at: NaiveDateTime,
     pub competitions: Vec<AthleteCompetitionSummary>,
     pub personal_records: Vec<PersonalRecord>,
@@ -53,7 +57,10 @@ pub struct AthleteCompetitionSummary {
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct PersonalRecord {
     pub movement_name: String,
+    /// Heaviest successful attempt, record attempts included
     pub max_weight: rust_decimal::Decimal,
+    /// Whether it was set on a record attempt rather than counted in a total
+    pub is_record_attempt: bool,
     pub competition_name: String,
     pub competition_slug: String,
     pub date: Option<chrono::NaiveDate>,
@@ -88,6 +95,13 @@ pub struct CreateAthleteRequest {
     #[validate(url)]
     #[validate(length(max = 500))]
     pub profile_picture_url: Option<String>,
+
+    /// Not published, only the year is
+    pub birth_date: Option<NaiveDate>,
+
+    /// When the date is not known
+    #[validate(range(min = 1900, max = 2100))]
+    pub birth_year: Option<i16>,
 }
 
 /// Request payload for updating an existing athlete
@@ -111,6 +125,39 @@ pub struct UpdateAthleteRequest {
     #[validate(url)]
     #[validate(length(max = 500))]
     pub profile_picture_url: Option<String>,
+
+    /// Not published, only the year is
+    pub birth_date: Option<NaiveDate>,
+
+    /// When the date is not known
+    #[validate(range(min = 1900, max = 2100))]
+    pub birth_year: Option<i16>,
+}
+
+/// Query parameters of the athlete search
+#[derive(Debug, Clone, Deserialize, Validate, IntoParams)]
+pub struct AthleteSearchParams {
+    /// Part of a name or of an alias, first name first or last name first
+    #[validate(length(min = 2, max = 255))]
+    pub q: String,
+}
+
+/// Request payload for adding another spelling of an athlete's name
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+pub struct CreateAliasRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub first_name: String,
+
+    #[validate(length(min = 1, max = 255))]
+    pub last_name: String,
+}
+
+/// Request payload for merging a duplicate into an athlete
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+pub struct MergeAthleteRequest {
+    /// Slug of the duplicate, deleted once its results are moved
+    #[validate(length(min = 1, max = 255))]
+    pub duplicate: String,
 }
 
 // Validation helper
@@ -124,6 +171,20 @@ fn validate_gender(gender: &str) -> Result<(), validator::ValidationError> {
     }
 }
 
+impl CreateAthleteRequest {
+    /// Fails when the birth year is not the one of the birth date
+    pub fn birth(&self) -> Result<Birth, String> {
+        Birth::new(self.birth_date, self.birth_year)
+    }
+}
+
+impl UpdateAthleteRequest {
+    /// Fails when the birth year is not the one of the birth date
+    pub fn birth(&self) -> Result<Birth, String> {
+        Birth::new(self.birth_date, self.birth_year)
+    }
+}
+
 impl From<crate::models::Athlete> for AthleteResponse {
     fn from(athlete: crate::models::Athlete) -> Self {
         Self {
@@ -135,6 +196,7 @@ impl From<crate::models::Athlete> for AthleteResponse {
             nationality: athlete.nationality,
             country: athlete.country,
             profile_picture_url: athlete.profile_picture_url,
+            birth_year: athlete.birth_year,
             created_at: athlete.created_at,
         }
     }
@@ -4,6 +4,8 @@ use utoipa::ToSchema;
 use uuid::Uuid;
 use validator::Validate;
 
+use crate::models::ImportBatch;
+
 #[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct CreateCompetitionRequest {
     #[validate(length(
@@ -133,6 +135,8 @@ pub struct CompetitionDetailResponse {
     pub end_date: Option<chrono::NaiveDate>,
     pub federation: FederationInfo,
     pub categories: Vec<CategoryDetail>,
+    /// Canonical files the competition and its participants were imported from
+    pub import_batches: Vec<ImportBatch>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -153,7 +157,14 @@ pub struct CategoryInfo {
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ParticipantDetail {
     pub athlete: AthleteInfo,
+    /// Age division of the federation the athlete is in at the competition date,
+    /// the narrowest one when several apply
+    pub age_division: Option<String>,
     pub bodyweight: Option<rust_decimal::Decimal>,
+    /// Lot number drawn at the weigh-in
+    pub lot_number: Option<i32>,
+    /// Official placing in the category, stored once the competition is
+    /// completed; none for disqualified and bombed-out athletes
     pub rank: Option<i32>,
     pub ris_score: Option<rust_decimal::Decimal>,
     pub is_disqualified: bool,
@@ -176,8 +187,11 @@ pub struct AthleteInfo {
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct LiftDetail {
     pub movement_name: String,
+    /// Best regular attempt, counted in the total
     pub best_weight: rust_decimal::Decimal,
     pub attempts: Vec<AttemptInfo>,
+    /// Fourth attempts for a record, which count for records but not the total
+    pub record_attempts: Vec<AttemptInfo>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+pub struct LinkExternalIdRequest {
+    /// Source type, as in the canonical format (`liftcontrol`, `pdf`, ...)
+    #[validate(length(min = 1, max = 50))]
+    pub source: String,
+    #[validate(length(min = 1, max = 100))]
+    pub external_id: String,
+}
@@ -0,0 +1,134 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::models::TieBreak;
+
+/// An age division, by age in years at the competition date; both bounds are
+/// inclusive and a missing one is open ("Masters 40+" has no `max_age`)
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+pub struct AgeDivisionRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+
+    #[validate(range(min = 0, max = 120))]
+    pub min_age: Option<i16>,
+
+    #[validate(range(min = 0, max = 120))]
+    pub max_age: Option<i16>,
+}
+
+/// Request payload replacing all the age divisions of a federation
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReplaceAgeDivisionsRequest {
+    pub divisions: Vec<AgeDivisionRequest>,
+}
+
+impl ReplaceAgeDivisionsRequest {
+    pub fn validate_divisions(&self) -> Result<(), String> {
+        let mut names = HashSet::new();
+
+        for division in &self.divisions {
+            division
+                .validate()
+                .map_err(|e| format!("Age division '{}': {}", division.name, e))?;
+
+            match (division.min_age, division.max_age) {
+                (None, None) => {
+                    return Err(format!(
+                        "Age division '{}' needs a min_age or a max_age",
+                        division.name
+                    ));
+                }
+                (Some(min), Some(max)) if min > max => {
+                    return Err(format!(
+                        "Age division '{}' has min_age {} above max_age {}",
+                        division.name, min, max
+                    ));
+                }
+                _ => {}
+            }
+
+            if !names.insert(division.name.as_str()) {
+                return Err(format!("Age division '{}' is listed twice", division.name));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Request payload setting the tie-break rules of a federation, applied in order
+/// to athletes of a category with the same total
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateTieBreaksRequest {
+    pub tie_breaks: Vec<TieBreak>,
+}
+
+impl UpdateTieBreaksRequest {
+    pub fn validate_tie_breaks(&self) -> Result<(), String> {
+        let mut seen = HashSet::new();
+        match self.tie_breaks.iter().find(|rule| !seen.insert(**rule)) {
+            Some(rule) => Err(format!("Tie-break '{}' is listed twice", rule.as_str())),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn division(name: &str, min_age: Option<i16>, max_age: Option<i16>) -> AgeDivisionRequest {
+        AgeDivisionRequest {
+            name: name.to_string(),
+            min_age,
+            max_age,
+        }
+    }
+
+    #[test]
+    fn test_validate_divisions() {
+        let request = |divisions| ReplaceAgeDivisionsRequest { divisions };
+
+        assert!(
+            request(vec![
+                division("Junior", Some(19), Some(23)),
+                division("Masters 40+", Some(40), None),
+            ])
+            .validate_divisions()
+            .is_ok()
+        );
+
+        let errors = [
+            request(vec![division("Open", None, None)]),
+            request(vec![division("Junior", Some(23), Some(19))]),
+            request(vec![division("", Some(19), Some(23))]),
+            request(vec![division("Masters", Some(40), Some(150))]),
+            request(vec![
+                division("Junior", Some(19), Some(23)),
+                division("Junior", Some(18), Some(23)),
+            ]),
+        ];
+        for request in errors {
+            assert!(request.validate_divisions().is_err(), "{:?}", request);
+        }
+    }
+
+    #[test]
+    fn test_validate_tie_breaks() {
+        let request = |tie_breaks: &[TieBreak]| UpdateTieBreaksRequest {
+            tie_breaks: tie_breaks.to_vec(),
+        };
+
+        assert!(request(&TieBreak::DEFAULT).validate_tie_breaks().is_ok());
+        assert!(request(&[]).validate_tie_breaks().is_ok());
+        assert!(
+            request(&[TieBreak::LotNumber, TieBreak::LotNumber])
+                .validate_tie_breaks()
+                .is_err()
+        );
+    }
+}
@@ -1,5 +1,9 @@
 pub mod athlete;
 pub mod common;
 pub mod competition;
+pub mod external_id;
+pub mod federation;
 pub mod ranking;
 pub mod ris;
+pub mod stats;
+pub mod submission;
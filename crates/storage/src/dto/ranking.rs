@@ -32,6 +32,9 @@ pub struct GlobalRankingFilter {
     pub pagination: super::common::PaginationParams,
     pub gender: Option<String>,
     pub country: Option<String>,
+    /// Name of an age division, e.g. "Junior": only the results of athletes whose
+    /// age at the competition is in the division of the competition's federation
+    pub age_division: Option<String>,
     #[serde(default)]
     pub movement: Movement,
 }
@@ -0,0 +1,80 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Attempt statistics for a single competition
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CompetitionStatsResponse {
+    pub competition_id: Uuid,
+    pub name: String,
+    pub slug: String,
+    #[serde(flatten)]
+    pub stats: AttemptStats,
+}
+
+/// Attempt statistics aggregated over every competition
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct GlobalStatsResponse {
+    pub total_competitions: i64,
+    #[serde(flatten)]
+    pub stats: AttemptStats,
+}
+
+/// Figures shared by the competition and global statistics endpoints
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AttemptStats {
+    pub total_participants: i64,
+    pub total_attempts: i64,
+    pub successful_attempts: i64,
+    pub success_rate: f64,
+    /// Average weight increase between two consecutive attempts of the same lift
+    pub average_jump: Option<Decimal>,
+    /// Number of participants who missed all attempts on at least one movement
+    pub bomb_outs: i64,
+    pub movements: Vec<MovementStats>,
+    pub attempt_numbers: Vec<AttemptNumberStats>,
+    pub no_rep_reasons: Vec<NoRepReasonCount>,
+    pub top_lifters: Vec<TopLifter>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MovementStats {
+    pub movement_name: String,
+    pub total_attempts: i64,
+    pub successful_attempts: i64,
+    pub success_rate: f64,
+    pub average_jump: Option<Decimal>,
+    pub bomb_outs: i64,
+    pub attempt_numbers: Vec<AttemptNumberStats>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AttemptNumberStats {
+    pub attempt_number: i16,
+    pub total_attempts: i64,
+    pub successful_attempts: i64,
+    pub success_rate: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NoRepReasonCount {
+    pub reason: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TopLifter {
+    pub athlete_id: Uuid,
+    pub first_name: String,
+    pub last_name: String,
+    pub slug: String,
+    pub country: String,
+    pub gender: String,
+    pub category_name: String,
+    pub competition_name: String,
+    pub competition_slug: String,
+    pub bodyweight: Option<Decimal>,
+    pub total: Decimal,
+    pub ris_score: Decimal,
+}
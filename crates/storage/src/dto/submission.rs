@@ -0,0 +1,41 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::SubmissionStatus;
+
+#[derive(Debug, Deserialize, Validate, IntoParams)]
+pub struct CreateSubmissionParams {
+    /// Name or contact of the contributor, shown to moderators
+    #[validate(length(max = 255))]
+    pub submitted_by: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SubmissionListParams {
+    /// Only submissions with this status, `pending` by default
+    pub status: Option<SubmissionStatus>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Validate, ToSchema)]
+pub struct ReviewSubmissionRequest {
+    /// Note for the contributor, e.g. why the file was rejected
+    #[validate(length(max = 2000))]
+    pub comment: Option<String>,
+}
+
+/// A submission without its file, report and diff, as listed in the queue
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SubmissionSummary {
+    pub submission_id: Uuid,
+    pub status: String,
+    pub competition_slug: String,
+    pub competition_name: String,
+    pub content_hash: String,
+    pub submitted_by: Option<String>,
+    pub submitted_at: NaiveDateTime,
+    pub reviewed_at: Option<NaiveDateTime>,
+    pub review_comment: Option<String>,
+}
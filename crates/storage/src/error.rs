@@ -13,6 +13,15 @@ pub enum StorageError {
 
     #[error("Constraint violation: {0}")]
     ConstraintViolation(String),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Export error: {0}")]
+    Export(String),
+
+    #[error("Import error: {0}")]
+    Import(String),
 }
 
 pub type Result<T> = std::result::Result<T, StorageError>;
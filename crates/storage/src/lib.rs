@@ -1,8 +1,10 @@
 use sqlx::postgres::{PgPool, PgPoolOptions};
 
+pub mod canonical;
 pub mod dto;
 pub mod error;
 pub mod models;
+pub mod normalize;
 pub mod repository;
 pub mod services;
 
@@ -25,6 +27,7 @@ impl Database {
 
     pub async fn run_migrations(&self) -> Result<()> {
         sqlx::migrate!("./migrations").run(&self.pool).await?;
+        services::data_migrations::run(&self.pool).await?;
         Ok(())
     }
 
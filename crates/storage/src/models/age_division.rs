@@ -0,0 +1,72 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// An age division of a federation (sub-junior, junior, masters...), by age at the
+/// competition date with both bounds inclusive; a missing bound is open
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct AgeDivision {
+    pub age_division_id: Uuid,
+    pub federation_id: Uuid,
+    pub name: String,
+    pub min_age: Option<i16>,
+    pub max_age: Option<i16>,
+    pub created_at: NaiveDateTime,
+}
+
+impl AgeDivision {
+    pub fn contains(&self, age: i32) -> bool {
+        self.min_age.is_none_or(|min| age >= i32::from(min))
+            && self.max_age.is_none_or(|max| age <= i32::from(max))
+    }
+
+    fn width(&self) -> i32 {
+        i32::from(self.max_age.unwrap_or(i16::MAX)) - i32::from(self.min_age.unwrap_or(0))
+    }
+}
+
+/// Division shown for an athlete of the age: the narrowest of the divisions
+/// containing it, so "Masters 50+" rather than "Masters 40+"
+pub fn division_for(divisions: &[AgeDivision], age: i32) -> Option<&AgeDivision> {
+    divisions
+        .iter()
+        .filter(|division| division.contains(age))
+        .min_by_key(|division| (division.width(), std::cmp::Reverse(division.min_age)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn division(name: &str, min_age: Option<i16>, max_age: Option<i16>) -> AgeDivision {
+        AgeDivision {
+            age_division_id: Uuid::new_v4(),
+            federation_id: Uuid::nil(),
+            name: name.to_string(),
+            min_age,
+            max_age,
+            created_at: NaiveDateTime::default(),
+        }
+    }
+
+    #[test]
+    fn test_division_for() {
+        let divisions = [
+            division("Sub-junior", Some(14), Some(18)),
+            division("Junior", Some(19), Some(23)),
+            division("Masters 40+", Some(40), None),
+            division("Masters 50+", Some(50), None),
+        ];
+        let name = |age| division_for(&divisions, age).map(|d| d.name.as_str());
+
+        assert_eq!(name(13), None);
+        assert_eq!(name(14), Some("Sub-junior"));
+        assert_eq!(name(18), Some("Sub-junior"));
+        assert_eq!(name(19), Some("Junior"));
+        assert_eq!(name(30), None);
+        assert_eq!(name(45), Some("Masters 40+"));
+        assert_eq!(name(50), Some("Masters 50+"));
+    }
+}
@@ -3,6 +3,8 @@ use sqlx::FromRow;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
+use super::Birth;
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Athlete {
     pub athlete_id: Uuid,
@@ -17,4 +19,14 @@ pub struct Athlete {
     #[sqlx(default)]
     #[schema(value_type = Vec<String>)]
     pub slug_history: sqlx::types::Json<Vec<String>>,
+    /// Not published, only the year is
+    #[serde(skip_serializing)]
+    pub birth_date: Option<chrono::NaiveDate>,
+    pub birth_year: Option<i16>,
+}
+
+impl Athlete {
+    pub fn birth(&self) -> Birth {
+        Birth::new(self.birth_date, self.birth_year).unwrap_or_default()
+    }
 }
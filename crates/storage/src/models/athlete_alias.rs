@@ -0,0 +1,15 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Another spelling of an athlete's name, stored normalized like athlete names
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct AthleteAlias {
+    pub alias_id: Uuid,
+    pub athlete_id: Uuid,
+    pub first_name: String,
+    pub last_name: String,
+    pub created_at: NaiveDateTime,
+}
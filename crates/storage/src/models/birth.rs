@@ -0,0 +1,99 @@
+use chrono::{Datelike, NaiveDate};
+
+/// What is known of an athlete's birth: the date, or only the year when that is all
+/// a source gives. The year is always set with the date.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Birth {
+    date: Option<NaiveDate>,
+    year: Option<i16>,
+}
+
+impl Birth {
+    /// Fails when both are given and the year is not the one of the date
+    pub fn new(date: Option<NaiveDate>, year: Option<i16>) -> Result<Self, String> {
+        match (date, year) {
+            (Some(date), Some(year)) if date.year() != i32::from(year) => Err(format!(
+                "birth_year {} does not match birth_date {}",
+                year, date
+            )),
+            (Some(date), _) => Ok(Self {
+                date: Some(date),
+                year: i16::try_from(date.year()).ok(),
+            }),
+            (None, year) => Ok(Self { date: None, year }),
+        }
+    }
+
+    pub fn date(&self) -> Option<NaiveDate> {
+        self.date
+    }
+
+    pub fn year(&self) -> Option<i16> {
+        self.year
+    }
+
+    /// What is known once `other` is learnt: a date replaces what was known, and so
+    /// does a year other than the known one. The same year keeps the known date.
+    pub fn updated(self, other: Birth) -> Birth {
+        if other.date.is_some() || (other.year.is_some() && other.year != self.year) {
+            other
+        } else {
+            self
+        }
+    }
+
+    /// Age on a date, as computed by the `athlete_age` SQL function: exact with a
+    /// birth date, the age reached during the year with only a birth year
+    pub fn age_on(&self, on: NaiveDate) -> Option<i32> {
+        match (self.date, self.year) {
+            (Some(date), _) => on.years_since(date).map(|years| years as i32),
+            (None, Some(year)) => Some(on.year() - i32::from(year)),
+            (None, None) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_birth_year_follows_the_date() {
+        let birth = Birth::new(Some(date(2005, 6, 15)), None).unwrap();
+        assert_eq!(birth.year(), Some(2005));
+
+        assert!(Birth::new(Some(date(2005, 6, 15)), Some(2005)).is_ok());
+        let err = Birth::new(Some(date(2005, 6, 15)), Some(2004)).unwrap_err();
+        assert!(err.contains("does not match"));
+    }
+
+    #[test]
+    fn test_updated() {
+        let known = Birth::new(Some(date(2005, 6, 15)), None).unwrap();
+        let same_year = Birth::new(None, Some(2005)).unwrap();
+        let other_year = Birth::new(None, Some(2004)).unwrap();
+        let other_date = Birth::new(Some(date(2005, 7, 1)), None).unwrap();
+
+        assert_eq!(known.updated(Birth::default()), known);
+        assert_eq!(known.updated(same_year), known);
+        assert_eq!(known.updated(other_year), other_year);
+        assert_eq!(known.updated(other_date), other_date);
+        assert_eq!(Birth::default().updated(same_year), same_year);
+    }
+
+    #[test]
+    fn test_age_on() {
+        let birth = Birth::new(Some(date(2005, 6, 15)), None).unwrap();
+        assert_eq!(birth.age_on(date(2023, 6, 14)), Some(17));
+        assert_eq!(birth.age_on(date(2023, 6, 15)), Some(18));
+
+        let year_only = Birth::new(None, Some(2005)).unwrap();
+        assert_eq!(year_only.age_on(date(2023, 1, 1)), Some(18));
+
+        assert_eq!(Birth::default().age_on(date(2023, 1, 1)), None);
+    }
+}
@@ -10,6 +10,7 @@ pub struct CompetitionParticipant {
     pub category_id: Uuid,
     pub athlete_id: Uuid,
     pub bodyweight: Option<Decimal>,
+    pub lot_number: Option<i32>,
     pub rank: Option<i32>,
     pub is_disqualified: bool,
     pub created_at: Option<chrono::NaiveDateTime>,
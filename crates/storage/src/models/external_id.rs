@@ -0,0 +1,14 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+/// Identifier of an athlete or a competition in one of the sources it is imported
+/// from, e.g. a LiftControl registration or contest id
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ExternalId {
+    /// Source type, as in the canonical format (`liftcontrol`, `pdf`, ...)
+    pub source: String,
+    pub external_id: String,
+    pub created_at: NaiveDateTime,
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use sqlx::types::Json;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
@@ -10,4 +11,37 @@ pub struct Federation {
     pub rulebook_id: Option<Uuid>,
     pub country: Option<String>,
     pub abbreviation: Option<String>,
+    /// Rules telling apart athletes of a category with the same total, in order
+    #[schema(value_type = Vec<TieBreak>)]
+    pub tie_breaks: Json<Vec<TieBreak>>,
+}
+
+/// A rule telling apart two athletes of a category with the same total
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TieBreak {
+    /// The lighter athlete at the weigh-in places first
+    Bodyweight,
+    /// The athlete who reached the total first places first: in an earlier
+    /// movement, then at an earlier attempt, then with the lighter attempt
+    EarlierAchievement,
+    /// The lower lot number places first
+    LotNumber,
+}
+
+impl TieBreak {
+    /// Rules of a federation until it sets its own
+    pub const DEFAULT: [TieBreak; 3] = [
+        TieBreak::Bodyweight,
+        TieBreak::EarlierAchievement,
+        TieBreak::LotNumber,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TieBreak::Bodyweight => "bodyweight",
+            TieBreak::EarlierAchievement => "earlier_achievement",
+            TieBreak::LotNumber => "lot_number",
+        }
+    }
 }
@@ -0,0 +1,24 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// One canonical file imported into the database
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ImportBatch {
+    pub import_batch_id: Uuid,
+    pub source_type: String,
+    pub source_url: Option<String>,
+    pub file_path: Option<String>,
+    /// SHA-256 of the canonical file content, hex encoded
+    pub content_hash: String,
+    pub format_version: String,
+    pub extractor: String,
+    pub extracted_at: DateTime<Utc>,
+    pub importer_version: String,
+    pub category_count: i32,
+    pub participant_count: i32,
+    pub attempt_count: i32,
+    pub imported_at: NaiveDateTime,
+}
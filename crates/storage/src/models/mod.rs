@@ -1,11 +1,16 @@
+pub mod age_division;
 pub mod athlete;
+pub mod athlete_alias;
 pub mod athlete_social;
 pub mod attempt;
+pub mod birth;
 pub mod category;
 pub mod competition;
 pub mod competition_movement;
 pub mod competition_participant;
+pub mod external_id;
 pub mod federation;
+pub mod import_batch;
 pub mod lift;
 pub mod movement;
 pub mod normalized_name;
@@ -14,15 +19,21 @@ pub mod ris_formula;
 pub mod ris_score;
 pub mod rulebook;
 pub mod social;
+pub mod submission;
 
+pub use age_division::*;
 pub use athlete::*;
+pub use athlete_alias::*;
 pub use athlete_social::*;
 pub use attempt::*;
+pub use birth::*;
 pub use category::*;
 pub use competition::*;
 pub use competition_movement::*;
 pub use competition_participant::*;
+pub use external_id::*;
 pub use federation::*;
+pub use import_batch::*;
 pub use lift::*;
 pub use movement::*;
 pub use normalized_name::*;
@@ -31,3 +42,4 @@ pub use ris_formula::*;
 pub use ris_score::*;
 pub use rulebook::*;
 pub use social::*;
+pub use submission::*;
@@ -1,10 +1,13 @@
+use crate::normalize;
+
 /// A wrapper that normalizes athlete names for consistent database storage.
-/// Applies trimming and case normalization to prevent duplicates from inconsistent
-/// formatting (e.g., "JOHN SMITH", "john smith", "John Smith" are all normalized the same).
+/// Applies [`normalize::name_part`] to both parts to prevent duplicates from
+/// inconsistent formatting (e.g., "JEAN-PIERRE DUPONT", "jean-pierre dupont" and
+/// "Jean-Pierre Dupont" are all normalized the same).
 ///
 /// The actual first name and last name order is preserved as provided.
 /// Duplicate prevention is handled by database constraints.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct NormalizedAthleteName {
     /// The athlete's first name (normalized to title case)
     first_name: String,
@@ -14,7 +17,7 @@ pub struct NormalizedAthleteName {
 
 impl NormalizedAthleteName {
     /// Creates a new normalized athlete name from first and last name.
-    /// Applies normalization: trims whitespace and converts to title case.
+    /// Applies normalization: trims whitespace and converts each word to title case.
     ///
     /// # Examples
     ///
@@ -32,8 +35,8 @@ impl NormalizedAthleteName {
     /// assert_eq!(name2, name3);
     /// ```
     pub fn new(first_name: impl Into<String>, last_name: impl Into<String>) -> Self {
-        let first_name = normalize_name_part(first_name.into());
-        let last_name = normalize_name_part(last_name.into());
+        let first_name = normalize::name_part(&first_name.into());
+        let last_name = normalize::name_part(&last_name.into());
 
         Self {
             first_name,
@@ -57,21 +60,6 @@ impl NormalizedAthleteName {
     }
 }
 
-/// Normalizes a name part by trimming and converting to title case
-fn normalize_name_part(name: String) -> String {
-    let trimmed = name.trim();
-    if trimmed.is_empty() {
-        return String::new();
-    }
-
-    // Convert to title case: first letter uppercase, rest lowercase
-    let mut chars = trimmed.chars();
-    match chars.next() {
-        None => String::new(),
-        Some(first) => first.to_uppercase().collect::<String>() + &chars.as_str().to_lowercase(),
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -102,6 +90,13 @@ mod tests {
         assert_eq!(name.database_last_name(), "Smith");
     }
 
+    #[test]
+    fn test_normalization_compound_names() {
+        let name = NormalizedAthleteName::new("JEAN-PIERRE", "VAN DER BERG");
+        assert_eq!(name.database_first_name(), "Jean-Pierre");
+        assert_eq!(name.database_last_name(), "Van der Berg");
+    }
+
     #[test]
     fn test_different_names_not_equal() {
         let name1 = NormalizedAthleteName::new("John", "Smith");
@@ -0,0 +1,59 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// A canonical file uploaded through the API and its place in the moderation queue
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct Submission {
+    pub submission_id: Uuid,
+    /// `pending`, `approved` or `rejected`
+    pub status: String,
+    pub competition_slug: String,
+    pub competition_name: String,
+    /// The canonical JSON as uploaded
+    pub content: String,
+    /// SHA-256 of `content`, hex encoded
+    pub content_hash: String,
+    /// Validation report of the file at upload time
+    #[schema(value_type = Object)]
+    pub validation_report: serde_json::Value,
+    /// What importing the file would change, computed when a moderator first opens
+    /// the submission
+    #[schema(value_type = Option<Object>)]
+    pub dry_run_diff: Option<serde_json::Value>,
+    pub submitted_by: Option<String>,
+    pub submitted_at: NaiveDateTime,
+    pub reviewed_at: Option<NaiveDateTime>,
+    pub review_comment: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SubmissionStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+impl SubmissionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SubmissionStatus::Pending => "pending",
+            SubmissionStatus::Approved => "approved",
+            SubmissionStatus::Rejected => "rejected",
+        }
+    }
+}
+
+/// A submission to store, already validated
+#[derive(Debug, Clone)]
+pub struct NewSubmission {
+    pub competition_slug: String,
+    pub competition_name: String,
+    pub content: String,
+    pub content_hash: String,
+    pub validation_report: serde_json::Value,
+    pub submitted_by: Option<String>,
+}
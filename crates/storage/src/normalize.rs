@@ -0,0 +1,243 @@
+//! Normalization of athlete names and generation of slugs, shared by the API, the
+//! canonical loader and the importers.
+//!
+//! The `athlete_name_normalization` migration re-slugged the athletes stored before
+//! with the `unaccent` extension, which spells letters like [`transliterate`], and
+//! the data migration of the same name re-normalized their names and aliases with
+//! [`name_part`].
+
+use unicode_normalization::UnicodeNormalization;
+
+/// Words written in lowercase inside a name: "Ludwig van Beethoven", "Maria de los
+/// Angeles". The first and last words of a name part are always capitalized, so a
+/// last name alone is "Van Dijk" or "De Gaulle".
+const PARTICLES: &[&str] = &[
+    "d", "da", "das", "de", "del", "della", "den", "der", "des", "di", "do", "dos", "du", "e",
+    "el", "la", "las", "le", "los", "ten", "ter", "van", "von", "y", "zu",
+];
+
+/// Characters after which a new capital starts inside a word: "Jean-Pierre", "O'Brien"
+const WORD_SEPARATORS: &[char] = &['-', '\'', '’', '.'];
+
+/// Trims and title-cases one part of a name (a first name or a last name). Spaces
+/// are collapsed, each segment of a hyphenated or apostrophed word is capitalized
+/// (`JEAN-PIERRE` → `Jean-Pierre`, `o'brien` → `O'Brien`, `mcdonald` → `McDonald`)
+/// and particles inside the name stay lowercase (`GISCARD D'ESTAING` →
+/// `Giscard d'Estaing`, `van der berg` → `Van der Berg`).
+///
+/// Only the letters matter, not their case: normalizing a normalized name changes
+/// nothing.
+pub fn name_part(value: &str) -> String {
+    let words = value.split_whitespace().collect::<Vec<_>>();
+    let last = words.len().saturating_sub(1);
+
+    words
+        .iter()
+        .enumerate()
+        .map(|(i, word)| {
+            let word = word.to_lowercase();
+            if i > 0 && i < last && PARTICLES.contains(&word.as_str()) {
+                word
+            } else {
+                capitalize_word(&word, i > 0)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Capitalizes each segment of a lowercase word. An elided particle (`d'`) stays
+/// lowercase when the word is not the first one of the name.
+fn capitalize_word(word: &str, inside_name: bool) -> String {
+    let mut result = String::with_capacity(word.len());
+    let mut segment = String::new();
+
+    for c in word.chars() {
+        if WORD_SEPARATORS.contains(&c) {
+            let particle = inside_name && result.is_empty() && segment == "d" && c != '-';
+            if particle {
+                result.push_str(&segment);
+            } else {
+                result.push_str(&capitalize_segment(&segment));
+            }
+            result.push(c);
+            segment.clear();
+        } else {
+            segment.push(c);
+        }
+    }
+    result.push_str(&capitalize_segment(&segment));
+
+    result
+}
+
+fn capitalize_segment(segment: &str) -> String {
+    let mut chars = segment.chars();
+    let Some(first) = chars.next() else {
+        return String::new();
+    };
+    let rest = chars.as_str();
+
+    let mut capitalized = first.to_uppercase().collect::<String>();
+    match rest.strip_prefix('c') {
+        // Scottish and Irish names: McDonald, McGregor
+        Some(name) if first == 'm' && !name.is_empty() => {
+            capitalized.push('c');
+            capitalized.push_str(&capitalize_segment(name));
+        }
+        _ => capitalized.push_str(rest),
+    }
+    capitalized
+}
+
+/// Transliterates to ASCII: accents are removed (`Éloïse` → `Eloise`) and letters
+/// without a decomposition are spelled out (`ß` → `ss`, `Ø` → `O`, `Þ` → `TH`).
+/// Characters without an ASCII spelling are dropped.
+pub fn transliterate(value: &str) -> String {
+    let mut ascii = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        if c.is_ascii() {
+            ascii.push(c);
+        } else if let Some(spelling) = spelling(c) {
+            ascii.push_str(spelling);
+        } else {
+            ascii.extend(c.nfkd().filter(char::is_ascii));
+        }
+    }
+
+    ascii
+}
+
+/// ASCII spelling of the letters that do not decompose into a letter and accents
+fn spelling(c: char) -> Option<&'static str> {
+    let spelling = match c {
+        'ß' => "ss",
+        'ẞ' => "SS",
+        'æ' => "ae",
+        'Æ' => "AE",
+        'œ' => "oe",
+        'Œ' => "OE",
+        'ø' => "o",
+        'Ø' => "O",
+        'ł' => "l",
+        'Ł' => "L",
+        'đ' | 'ð' => "d",
+        'Đ' | 'Ð' => "D",
+        'þ' => "th",
+        'Þ' => "TH",
+        'ı' => "i",
+        'ħ' => "h",
+        'Ħ' => "H",
+        '’' | '‘' => "'",
+        _ => return None,
+    };
+    Some(spelling)
+}
+
+/// Lowercase ASCII words of the value joined by dashes: `Éloïse O'Brien` →
+/// `eloise-o-brien`. Empty when the value has no letter or digit.
+pub fn slugify(value: &str) -> String {
+    transliterate(value)
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Slug of an athlete before it is made unique, `athlete` when the name has no
+/// letter or digit
+pub fn athlete_slug(first_name: &str, last_name: &str) -> String {
+    let slug = slugify(&format!("{} {}", first_name, last_name));
+    if slug.is_empty() {
+        "athlete".to_string()
+    } else {
+        slug
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_name_part() {
+        let cases = [
+            ("john", "John"),
+            ("  JOHN  ", "John"),
+            ("jean   pierre", "Jean Pierre"),
+            ("JEAN-PIERRE", "Jean-Pierre"),
+            ("marie-hélène", "Marie-Hélène"),
+            ("o'brien", "O'Brien"),
+            ("O’NEILL", "O’Neill"),
+            ("mcdonald", "McDonald"),
+            ("MC", "Mc"),
+            ("d'amico", "D'Amico"),
+            ("giscard d'estaing", "Giscard d'Estaing"),
+            ("VAN DER BERG", "Van der Berg"),
+            ("van dijk", "Van Dijk"),
+            ("de la fontaine", "De la Fontaine"),
+            ("maria de los angeles", "Maria de los Angeles"),
+            ("ortega y gasset", "Ortega y Gasset"),
+            ("ÉLOÏSE", "Éloïse"),
+            ("ÇAĞLAR", "Çağlar"),
+            ("j.p.", "J.P."),
+            ("le roux", "Le Roux"),
+            ("", ""),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(name_part(input), expected, "name_part({:?})", input);
+            assert_eq!(name_part(expected), expected, "name_part({:?})", expected);
+        }
+    }
+
+    #[test]
+    fn test_transliterate() {
+        let cases = [
+            ("Éloïse", "Eloise"),
+            ("Müller", "Muller"),
+            ("Straße", "Strasse"),
+            ("Øster Åsa", "Oster Asa"),
+            ("Łukasz Đorđe", "Lukasz Dorde"),
+            ("Æther Œuvre", "AEther OEuvre"),
+            ("Þór", "THor"),
+            ("Çağlar Ñoño", "Caglar Nono"),
+            ("O’Neill", "O'Neill"),
+            ("ﬁnn", "finn"),
+            ("Иван", ""),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(transliterate(input), expected, "transliterate({:?})", input);
+        }
+    }
+
+    #[test]
+    fn test_slugify() {
+        let cases = [
+            ("Coupe de Lyon 2024", "coupe-de-lyon-2024"),
+            ("  Streetlifting -- Open ", "streetlifting-open"),
+            ("Jean Pierre Dupont", "jean-pierre-dupont"),
+            ("Éloïse O'Brien", "eloise-o-brien"),
+            ("Giscard d'Estaing", "giscard-d-estaing"),
+            ("Søren Kierkegaard", "soren-kierkegaard"),
+            ("Иван", ""),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(slugify(input), expected, "slugify({:?})", input);
+        }
+    }
+
+    #[test]
+    fn test_athlete_slug() {
+        assert_eq!(athlete_slug("Jean-Pierre", "Dupont"), "jean-pierre-dupont");
+        assert_eq!(
+            athlete_slug("Maria", "de los Angeles"),
+            "maria-de-los-angeles"
+        );
+        assert_eq!(athlete_slug("Иван", "Иванов"), "athlete");
+    }
+}
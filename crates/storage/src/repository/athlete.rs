@@ -3,11 +3,12 @@ use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::dto::athlete::{
-    AthleteCompetitionSummary, AthleteDetailResponse, CreateAthleteRequest, PersonalRecord,
-    UpdateAthleteRequest,
+    AthleteCompetitionSummary, AthleteDetailResponse, CreateAliasRequest, CreateAthleteRequest,
+    PersonalRecord, UpdateAthleteRequest,
 };
 use crate::error::{Result, StorageError};
-use crate::models::Athlete;
+use crate::models::{Athlete, AthleteAlias, NormalizedAthleteName};
+use crate::normalize;
 
 pub struct AthleteRepository<'a> {
     pool: &'a PgPool,
@@ -25,7 +26,8 @@ impl<'a> AthleteRepository<'a> {
             r#"
             SELECT athlete_id, first_name, last_name, gender, created_at,
                    nationality, country, profile_picture_url, slug,
-                   COALESCE(slug_history, '[]'::jsonb) as "slug_history!: sqlx::types::Json<Vec<String>>"
+                   COALESCE(slug_history, '[]'::jsonb) as "slug_history!: sqlx::types::Json<Vec<String>>",
+                   birth_date, birth_year
             FROM athletes
             ORDER BY last_name, first_name
             "#
@@ -44,7 +46,8 @@ impl<'a> AthleteRepository<'a> {
             r#"
             SELECT athlete_id, first_name, last_name, gender, created_at,
                    nationality, country, profile_picture_url, slug,
-                   COALESCE(slug_history, '[]'::jsonb) as "slug_history!: sqlx::types::Json<Vec<String>>"
+                   COALESCE(slug_history, '[]'::jsonb) as "slug_history!: sqlx::types::Json<Vec<String>>",
+                   birth_date, birth_year
             FROM athletes
             WHERE slug = $1
             "#,
@@ -63,7 +66,8 @@ impl<'a> AthleteRepository<'a> {
             r#"
             SELECT athlete_id, first_name, last_name, gender, created_at,
                    nationality, country, profile_picture_url, slug,
-                   COALESCE(slug_history, '[]'::jsonb) as "slug_history!: sqlx::types::Json<Vec<String>>"
+                   COALESCE(slug_history, '[]'::jsonb) as "slug_history!: sqlx::types::Json<Vec<String>>",
+                   birth_date, birth_year
             FROM athletes
             WHERE slug_history @> to_jsonb($1::text)
             "#,
@@ -83,7 +87,8 @@ impl<'a> AthleteRepository<'a> {
             r#"
             SELECT athlete_id, first_name, last_name, gender, created_at,
                    nationality, country, profile_picture_url, slug,
-                   COALESCE(slug_history, '[]'::jsonb) as "slug_history!: sqlx::types::Json<Vec<String>>"
+                   COALESCE(slug_history, '[]'::jsonb) as "slug_history!: sqlx::types::Json<Vec<String>>",
+                   birth_date, birth_year
             FROM athletes
             WHERE athlete_id = $1
             "#,
@@ -137,15 +142,17 @@ impl<'a> AthleteRepository<'a> {
             r#"
             SELECT DISTINCT ON (l.movement_name)
                 l.movement_name,
-                l.max_weight,
+                at.weight as max_weight,
+                at.kind = 'record' as "is_record_attempt!",
                 c.name as competition_name,
                 c.slug as competition_slug,
                 c.start_date as date
-            FROM lifts l
+            FROM attempts at
+            JOIN lifts l ON l.lift_id = at.lift_id
             JOIN competition_participants cp ON l.participant_id = cp.participant_id
             JOIN competitions c ON cp.competition_id = c.competition_id
-            WHERE cp.athlete_id = $1
-            ORDER BY l.movement_name, l.max_weight DESC
+            WHERE cp.athlete_id = $1 AND at.is_successful
+            ORDER BY l.movement_name, at.weight DESC, at.kind = 'record', c.start_date
             "#,
             athlete.athlete_id
         )
@@ -173,6 +180,7 @@ impl<'a> AthleteRepository<'a> {
             nationality: athlete.nationality,
             country: athlete.country,
             profile_picture_url: athlete.profile_picture_url,
+            birth_year: athlete.birth_year,
             created_at: athlete.created_at,
             competitions,
             personal_records,
@@ -182,21 +190,7 @@ impl<'a> AthleteRepository<'a> {
 
     /// Generate unique slug from first and last name
     pub async fn generate_unique_slug(&self, first_name: &str, last_name: &str) -> Result<String> {
-        let base_slug = format!("{}-{}", first_name, last_name)
-            .to_lowercase()
-            .chars()
-            .filter(|c| c.is_alphanumeric() || *c == '-')
-            .collect::<String>()
-            .split('-')
-            .filter(|s| !s.is_empty())
-            .collect::<Vec<&str>>()
-            .join("-");
-
-        let base_slug = if base_slug.is_empty() {
-            "athlete".to_string()
-        } else {
-            base_slug
-        };
+        let base_slug = normalize::athlete_slug(first_name, last_name);
 
         let mut final_slug = base_slug.clone();
         let mut counter = 2;
@@ -218,26 +212,31 @@ impl<'a> AthleteRepository<'a> {
 
     /// Create a new athlete
     pub async fn create(&self, req: &CreateAthleteRequest) -> Result<Athlete> {
-        let slug = self
-            .generate_unique_slug(&req.first_name, &req.last_name)
-            .await?;
+        let name = NormalizedAthleteName::new(&req.first_name, &req.last_name);
+        let (first_name, last_name) = name.as_database_tuple();
+        let birth = req.birth().map_err(StorageError::ConstraintViolation)?;
+        let slug = self.generate_unique_slug(first_name, last_name).await?;
 
         let athlete = sqlx::query_as!(
             Athlete,
             r#"
-            INSERT INTO athletes (first_name, last_name, gender, nationality, country, profile_picture_url, slug)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            INSERT INTO athletes (first_name, last_name, gender, nationality, country, profile_picture_url, slug,
+                                  birth_date, birth_year)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
             RETURNING athlete_id, first_name, last_name, gender, created_at,
                       nationality, country, profile_picture_url, slug,
-                      COALESCE(slug_history, '[]'::jsonb) as "slug_history!: sqlx::types::Json<Vec<String>>"
+                      COALESCE(slug_history, '[]'::jsonb) as "slug_history!: sqlx::types::Json<Vec<String>>",
+                      birth_date, birth_year
             "#,
-            req.first_name,
-            req.last_name,
+            first_name,
+            last_name,
             req.gender,
             req.nationality,
             req.country,
             req.profile_picture_url,
-            slug
+            slug,
+            birth.date(),
+            birth.year()
         )
         .fetch_one(self.pool)
         .await?;
@@ -252,8 +251,11 @@ impl<'a> AthleteRepository<'a> {
         existing: &Athlete,
         req: &UpdateAthleteRequest,
     ) -> Result<Athlete> {
-        let first_name = req.first_name.as_ref().unwrap_or(&existing.first_name);
-        let last_name = req.last_name.as_ref().unwrap_or(&existing.last_name);
+        let name = NormalizedAthleteName::new(
+            req.first_name.as_ref().unwrap_or(&existing.first_name),
+            req.last_name.as_ref().unwrap_or(&existing.last_name),
+        );
+        let (first_name, last_name) = name.as_database_tuple();
         let gender = req.gender.as_ref().unwrap_or(&existing.gender);
         let nationality = req.nationality.as_ref().or(existing.nationality.as_ref());
         let country = req.country.as_ref().unwrap_or(&existing.country);
@@ -261,9 +263,15 @@ impl<'a> AthleteRepository<'a> {
             .profile_picture_url
             .as_ref()
             .or(existing.profile_picture_url.as_ref());
+        let birth = existing
+            .birth()
+            .updated(req.birth().map_err(StorageError::ConstraintViolation)?);
 
-        // Check if name changed - if so, generate new slug and store old one
-        let (slug, slug_history) = if req.first_name.is_some() || req.last_name.is_some() {
+        // Check if the slug of the name changed - if so, generate new slug and store old
+        // one. A fix of case or spacing keeps the slug.
+        let slug_changed = normalize::athlete_slug(first_name, last_name)
+            != normalize::athlete_slug(&existing.first_name, &existing.last_name);
+        let (slug, slug_history) = if slug_changed {
             let new_slug = self.generate_unique_slug(first_name, last_name).await?;
             let mut history = existing.slug_history.0.clone();
             history.push(existing.slug.clone());
@@ -283,11 +291,14 @@ impl<'a> AthleteRepository<'a> {
                 country = $6,
                 profile_picture_url = $7,
                 slug = $8,
-                slug_history = $9
+                slug_history = $9,
+                birth_date = $10,
+                birth_year = $11
             WHERE athlete_id = $1
             RETURNING athlete_id, first_name, last_name, gender, created_at,
                       nationality, country, profile_picture_url, slug,
-                      COALESCE(slug_history, '[]'::jsonb) as "slug_history!: sqlx::types::Json<Vec<String>>"
+                      COALESCE(slug_history, '[]'::jsonb) as "slug_history!: sqlx::types::Json<Vec<String>>",
+                      birth_date, birth_year
             "#,
             id,
             first_name,
@@ -297,7 +308,9 @@ impl<'a> AthleteRepository<'a> {
             country,
             profile_picture_url,
             slug,
-            slug_history as _
+            slug_history as _,
+            birth.date(),
+            birth.year()
         )
         .fetch_optional(self.pool)
         .await?
@@ -318,4 +331,239 @@ impl<'a> AthleteRepository<'a> {
 
         Ok(())
     }
+
+    /// Athletes whose name or one of whose aliases contains the query, first name
+    /// first or last name first
+    pub async fn search(&self, query: &str) -> Result<Vec<Athlete>> {
+        let pattern = format!("%{}%", escape_like(query.trim()));
+
+        let athletes = sqlx::query_as!(
+            Athlete,
+            r#"
+            SELECT athlete_id, first_name, last_name, gender, created_at,
+                   nationality, country, profile_picture_url, slug,
+                   COALESCE(slug_history, '[]'::jsonb) as "slug_history!: sqlx::types::Json<Vec<String>>",
+                   birth_date, birth_year
+            FROM athletes a
+            WHERE a.first_name || ' ' || a.last_name ILIKE $1
+               OR a.last_name || ' ' || a.first_name ILIKE $1
+               OR EXISTS (
+                   SELECT 1 FROM athlete_aliases al
+                   WHERE al.athlete_id = a.athlete_id
+                     AND (al.first_name || ' ' || al.last_name ILIKE $1
+                          OR al.last_name || ' ' || al.first_name ILIKE $1)
+               )
+            ORDER BY last_name, first_name
+            LIMIT 50
+            "#,
+            pattern
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(athletes)
+    }
+
+    pub async fn list_aliases(&self, athlete_id: Uuid) -> Result<Vec<AthleteAlias>> {
+        let aliases = sqlx::query_as!(
+            AthleteAlias,
+            r#"
+            SELECT alias_id, athlete_id, first_name, last_name, created_at
+            FROM athlete_aliases
+            WHERE athlete_id = $1
+            ORDER BY last_name, first_name
+            "#,
+            athlete_id
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(aliases)
+    }
+
+    /// Adds another spelling of the athlete's name, normalized like imported names
+    pub async fn add_alias(
+        &self,
+        athlete: &Athlete,
+        req: &CreateAliasRequest,
+    ) -> Result<AthleteAlias> {
+        let name = NormalizedAthleteName::new(&req.first_name, &req.last_name);
+        let (first_name, last_name) = name.as_database_tuple();
+        if first_name == athlete.first_name && last_name == athlete.last_name {
+            return Err(StorageError::ConstraintViolation(
+                "An alias cannot be the athlete's name".to_string(),
+            ));
+        }
+
+        sqlx::query_as!(
+            AthleteAlias,
+            r#"
+            INSERT INTO athlete_aliases (athlete_id, first_name, last_name)
+            VALUES ($1, $2, $3)
+            RETURNING alias_id, athlete_id, first_name, last_name, created_at
+            "#,
+            athlete.athlete_id,
+            first_name,
+            last_name
+        )
+        .fetch_one(self.pool)
+        .await
+        .map_err(|e| match StorageError::from(e) {
+            error if error.is_unique_violation() => {
+                StorageError::ConstraintViolation("The athlete already has this alias".to_string())
+            }
+            error => error,
+        })
+    }
+
+    pub async fn delete_alias(&self, athlete_id: Uuid, alias_id: Uuid) -> Result<()> {
+        let result = sqlx::query!(
+            r#"DELETE FROM athlete_aliases WHERE alias_id = $1 AND athlete_id = $2"#,
+            alias_id,
+            athlete_id
+        )
+        .execute(self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(StorageError::NotFound);
+        }
+
+        Ok(())
+    }
+
+    /// Moves the results, records, socials, source ids and aliases of a duplicate to
+    /// the athlete and deletes the duplicate. Its name becomes an alias, its slug
+    /// keeps redirecting and its birth date is kept when the athlete has none. Fails
+    /// when both took part in the same competition category.
+    pub async fn merge(&self, athlete: &Athlete, duplicate: &Athlete) -> Result<Athlete> {
+        if athlete.athlete_id == duplicate.athlete_id {
+            return Err(StorageError::ConstraintViolation(
+                "An athlete cannot be merged into itself".to_string(),
+            ));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let shared = sqlx::query_scalar!(
+            r#"
+            SELECT c.name
+            FROM competition_participants kept
+            JOIN competition_participants dup
+              ON dup.competition_id = kept.competition_id AND dup.category_id = kept.category_id
+            JOIN competitions c ON c.competition_id = kept.competition_id
+            WHERE kept.athlete_id = $1 AND dup.athlete_id = $2
+            LIMIT 1
+            "#,
+            athlete.athlete_id,
+            duplicate.athlete_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        if let Some(competition) = shared {
+            return Err(StorageError::ConstraintViolation(format!(
+                "Both athletes took part in the same category of {}",
+                competition
+            )));
+        }
+
+        sqlx::query!(
+            r#"UPDATE competition_participants SET athlete_id = $1 WHERE athlete_id = $2"#,
+            athlete.athlete_id,
+            duplicate.athlete_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"UPDATE records SET athlete_id = $1 WHERE athlete_id = $2"#,
+            athlete.athlete_id,
+            duplicate.athlete_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        // A social network the athlete already has keeps the athlete's handle
+        sqlx::query!(
+            r#"
+            UPDATE athlete_socials SET athlete_id = $1
+            WHERE athlete_id = $2
+              AND social_id NOT IN (SELECT social_id FROM athlete_socials WHERE athlete_id = $1)
+            "#,
+            athlete.athlete_id,
+            duplicate.athlete_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"UPDATE athlete_external_ids SET athlete_id = $1 WHERE athlete_id = $2"#,
+            athlete.athlete_id,
+            duplicate.athlete_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO athlete_aliases (athlete_id, first_name, last_name)
+            SELECT $1::uuid, first_name, last_name FROM athlete_aliases WHERE athlete_id = $2
+            UNION
+            SELECT $1::uuid, first_name, last_name FROM athletes WHERE athlete_id = $2
+            EXCEPT
+            SELECT athlete_id, first_name, last_name FROM athletes WHERE athlete_id = $1
+            ON CONFLICT (athlete_id, last_name, first_name) DO NOTHING
+            "#,
+            athlete.athlete_id,
+            duplicate.athlete_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let mut slug_history = athlete.slug_history.0.clone();
+        slug_history.push(duplicate.slug.clone());
+        slug_history.extend(duplicate.slug_history.0.iter().cloned());
+
+        // What the athlete has wins, the duplicate's date completes the same year
+        let birth = duplicate.birth().updated(athlete.birth());
+
+        sqlx::query!(
+            r#"DELETE FROM athletes WHERE athlete_id = $1"#,
+            duplicate.athlete_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let merged = sqlx::query_as!(
+            Athlete,
+            r#"
+            UPDATE athletes
+            SET slug_history = $2,
+                birth_date = $3,
+                birth_year = $4
+            WHERE athlete_id = $1
+            RETURNING athlete_id, first_name, last_name, gender, created_at,
+                      nationality, country, profile_picture_url, slug,
+                      COALESCE(slug_history, '[]'::jsonb) as "slug_history!: sqlx::types::Json<Vec<String>>",
+                      birth_date, birth_year
+            "#,
+            athlete.athlete_id,
+            sqlx::types::Json(slug_history) as _,
+            birth.date(),
+            birth.year()
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(merged)
+    }
+}
+
+/// Escapes the wildcards of a `LIKE` pattern
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
 }
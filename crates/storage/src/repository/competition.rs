@@ -1,5 +1,6 @@
 use rust_decimal::Decimal;
 use sqlx::PgPool;
+use sqlx::types::Json;
 use std::collections::HashMap;
 use uuid::Uuid;
 
@@ -9,7 +10,12 @@ use crate::dto::competition::{
     ParticipantDetail,
 };
 use crate::error::{Result, StorageError};
-use crate::models::{Athlete, Category, Competition, CompetitionMovement, Federation, Lift};
+use crate::models::{
+    Athlete, Category, Competition, CompetitionMovement, Federation, Lift, TieBreak, division_for,
+};
+use crate::repository::federation::FederationRepository;
+use crate::repository::import_batch::ImportBatchRepository;
+use crate::services::placings;
 
 pub struct CompetitionRepository<'a> {
     pool: &'a PgPool,
@@ -43,9 +49,10 @@ impl<'a> CompetitionRepository<'a> {
         for comp in competitions {
             let federation = sqlx::query_as!(
                 Federation,
-                "SELECT federation_id, name, rulebook_id, country, abbreviation
-                 FROM federations
-                 WHERE federation_id = $1",
+                r#"SELECT federation_id, name, rulebook_id, country, abbreviation,
+                     tie_breaks as "tie_breaks: Json<Vec<TieBreak>>"
+                   FROM federations
+                   WHERE federation_id = $1"#,
                 comp.federation_id
             )
             .fetch_one(self.pool)
@@ -140,62 +147,25 @@ impl<'a> CompetitionRepository<'a> {
         self.get_detailed_competition(competition).await
     }
 
-    /// Compute category rankings for all participants in a competition
-    async fn compute_category_rankings(&self, competition_id: Uuid) -> Result<HashMap<Uuid, i32>> {
-        let rankings = sqlx::query!(
-            r#"
-            WITH participant_totals AS (
-                SELECT
-                    cp.participant_id,
-                    cp.category_id,
-                    cp.bodyweight,
-                    COALESCE(SUM(l.max_weight), 0) as total
-                FROM competition_participants cp
-                LEFT JOIN lifts l ON l.participant_id = cp.participant_id
-                WHERE cp.competition_id = $1
-                GROUP BY cp.participant_id, cp.category_id, cp.bodyweight
-            )
-            SELECT
-                participant_id,
-                ROW_NUMBER() OVER (
-                    PARTITION BY category_id
-                    ORDER BY
-                        CASE WHEN total = 0 THEN 1 ELSE 0 END,
-                        total DESC,
-                        bodyweight ASC NULLS LAST
-                )::int as "rank!"
-            FROM participant_totals
-            "#,
-            competition_id
-        )
-        .fetch_all(self.pool)
-        .await?;
-
-        Ok(rankings
-            .into_iter()
-            .map(|r| (r.participant_id, r.rank))
-            .collect())
-    }
-
     async fn get_detailed_competition(
         &self,
         competition: Competition,
     ) -> Result<CompetitionDetailResponse> {
-        // Compute category rankings for all participants
-        let ranking_map = self
-            .compute_category_rankings(competition.competition_id)
-            .await?;
-
         let federation = sqlx::query_as!(
             Federation,
-            "SELECT federation_id, name, rulebook_id, country, abbreviation
-             FROM federations
-             WHERE federation_id = $1",
+            r#"SELECT federation_id, name, rulebook_id, country, abbreviation,
+                 tie_breaks as "tie_breaks: Json<Vec<TieBreak>>"
+               FROM federations
+               WHERE federation_id = $1"#,
             competition.federation_id
         )
         .fetch_one(self.pool)
         .await?;
 
+        let age_divisions = FederationRepository::new(self.pool)
+            .list_age_divisions(federation.federation_id)
+            .await?;
+
         let categories = sqlx::query_as!(
             Category,
             "SELECT DISTINCT c.category_id, c.name, c.gender, c.weight_class_min, c.weight_class_max
@@ -214,10 +184,10 @@ impl<'a> CompetitionRepository<'a> {
 
             let participants = sqlx::query!(
                 "SELECT participant_id, competition_id, category_id, athlete_id, bodyweight, rank, is_disqualified,
-                        created_at, disqualified_reason, ris_score
+                        created_at, disqualified_reason, ris_score, lot_number
                  FROM competition_participants
                  WHERE competition_id = $1 AND category_id = $2
-                 ORDER BY rank NULLS LAST",
+                 ORDER BY rank NULLS LAST, lot_number NULLS LAST",
                 competition.competition_id,
                 category.category_id
             )
@@ -229,7 +199,8 @@ impl<'a> CompetitionRepository<'a> {
                     Athlete,
                     r#"SELECT athlete_id, first_name, last_name, gender, created_at,
                             nationality, country, profile_picture_url, slug,
-                            COALESCE(slug_history, '[]'::jsonb) as "slug_history!: sqlx::types::Json<Vec<String>>"
+                            COALESCE(slug_history, '[]'::jsonb) as "slug_history!: sqlx::types::Json<Vec<String>>",
+                            birth_date, birth_year
                      FROM athletes
                      WHERE athlete_id = $1"#,
                     participant.athlete_id
@@ -253,7 +224,8 @@ impl<'a> CompetitionRepository<'a> {
 
                 for lift in lifts {
                     let attempts = sqlx::query!(
-                        "SELECT attempt_number, weight, is_successful, passing_judges, no_rep_reason
+                        "SELECT attempt_number, weight, is_successful, passing_judges, no_rep_reason,
+                                kind = 'record' as \"is_record!\"
                          FROM attempts
                          WHERE lift_id = $1
                          ORDER BY attempt_number",
@@ -264,24 +236,34 @@ impl<'a> CompetitionRepository<'a> {
 
                     total += lift.max_weight;
 
-                    lift_details.push(LiftDetail {
-                        movement_name: lift.movement_name.clone(),
-                        best_weight: lift.max_weight,
-                        attempts: attempts
-                            .into_iter()
-                            .map(|a| AttemptInfo {
+                    // Record attempts do not count for the best weight and are shown apart
+                    let (record_attempts, attempts): (Vec<_>, Vec<_>) = attempts
+                        .into_iter()
+                        .map(|a| {
+                            let info = AttemptInfo {
                                 attempt_number: a.attempt_number,
                                 weight: a.weight,
                                 is_successful: a.is_successful,
                                 passing_judges: a.passing_judges,
                                 no_rep_reason: a.no_rep_reason,
-                            })
-                            .collect(),
+                            };
+                            (a.is_record, info)
+                        })
+                        .partition(|(is_record, _)| *is_record);
+
+                    lift_details.push(LiftDetail {
+                        movement_name: lift.movement_name.clone(),
+                        best_weight: lift.max_weight,
+                        attempts: attempts.into_iter().map(|(_, a)| a).collect(),
+                        record_attempts: record_attempts.into_iter().map(|(_, a)| a).collect(),
                     });
                 }
 
-                // Get computed category rank for this participant
-                let rank = ranking_map.get(&participant.participant_id).copied();
+                let age_division = competition
+                    .start_date
+                    .and_then(|date| athlete.birth().age_on(date))
+                    .and_then(|age| division_for(&age_divisions, age))
+                    .map(|division| division.name.clone());
 
                 let participant_detail = ParticipantDetail {
                     athlete: AthleteInfo {
@@ -293,8 +275,10 @@ impl<'a> CompetitionRepository<'a> {
                         country: athlete.country,
                         slug: athlete.slug,
                     },
+                    age_division,
                     bodyweight: participant.bodyweight,
-                    rank,
+                    lot_number: participant.lot_number,
+                    rank: participant.rank,
                     ris_score: participant.ris_score,
                     is_disqualified: participant.is_disqualified,
                     disqualified_reason: participant.disqualified_reason.clone(),
@@ -324,6 +308,10 @@ impl<'a> CompetitionRepository<'a> {
 
         category_details.sort_by(|a, b| a.category.name.cmp(&b.category.name));
 
+        let import_batches = ImportBatchRepository::new(self.pool)
+            .list_for_competition(competition.competition_id)
+            .await?;
+
         Ok(CompetitionDetailResponse {
             competition_id: competition.competition_id,
             name: competition.name,
@@ -341,6 +329,7 @@ impl<'a> CompetitionRepository<'a> {
                 country: federation.country,
             },
             categories: category_details,
+            import_batches,
         })
     }
 
@@ -399,6 +388,8 @@ impl<'a> CompetitionRepository<'a> {
         let end_date = req.end_date.or(existing.end_date);
         let number_of_judge = req.number_of_judge.or(existing.number_of_judge);
 
+        let mut tx = self.pool.begin().await?;
+
         let competition = sqlx::query_as!(
             Competition,
             r#"
@@ -430,13 +421,58 @@ impl<'a> CompetitionRepository<'a> {
             end_date,
             number_of_judge
         )
-        .fetch_optional(self.pool)
+        .fetch_optional(&mut *tx)
         .await?
         .ok_or(StorageError::NotFound)?;
 
+        // Placings are stored when the competition is completed, with the rules of
+        // its federation, and cleared when it is reopened
+        if competition.status != existing.status
+            || competition.federation_id != existing.federation_id
+        {
+            placings::update_placings(&mut tx, competition.competition_id).await?;
+        }
+
+        tx.commit().await?;
+
         Ok(competition)
     }
 
+    /// Recomputes the stored placings of a completed competition, after its
+    /// results or the tie-break rules of its federation changed
+    pub async fn recompute_placings(&self, competition: &Competition) -> Result<usize> {
+        if competition.status != placings::COMPLETED {
+            return Err(StorageError::ConstraintViolation(format!(
+                "Placings are only computed for completed competitions, '{}' is {}",
+                competition.slug, competition.status
+            )));
+        }
+
+        let mut conn = self.pool.acquire().await?;
+        placings::update_placings(&mut conn, competition.competition_id).await
+    }
+
+    /// Recomputes the stored placings of every completed competition, e.g. to fill
+    /// them in for competitions imported before placings were stored. Returns the
+    /// slug of each competition with the number of participants placed.
+    pub async fn recompute_all_placings(&self) -> Result<Vec<(String, usize)>> {
+        let competitions = sqlx::query!(
+            "SELECT competition_id, slug FROM competitions WHERE status = $1 ORDER BY start_date, slug",
+            placings::COMPLETED
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        let mut placed = Vec::with_capacity(competitions.len());
+        for competition in competitions {
+            let mut conn = self.pool.acquire().await?;
+            let count = placings::update_placings(&mut conn, competition.competition_id).await?;
+            placed.push((competition.slug, count));
+        }
+
+        Ok(placed)
+    }
+
     pub async fn delete(&self, id: Uuid) -> Result<()> {
         let result = sqlx::query!(
             r#"
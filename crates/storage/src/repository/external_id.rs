@@ -0,0 +1,163 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::{Result, StorageError};
+use crate::models::ExternalId;
+
+/// Links between athletes or competitions and their identifiers in the sources
+/// they are imported from. An identifier belongs to one athlete or competition.
+pub struct ExternalIdRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> ExternalIdRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list_for_athlete(&self, athlete_id: Uuid) -> Result<Vec<ExternalId>> {
+        let ids = sqlx::query_as!(
+            ExternalId,
+            r#"
+            SELECT source, external_id, created_at
+            FROM athlete_external_ids
+            WHERE athlete_id = $1
+            ORDER BY source, external_id
+            "#,
+            athlete_id
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(ids)
+    }
+
+    /// Fails with a constraint violation when the identifier is linked already
+    pub async fn link_athlete(
+        &self,
+        athlete_id: Uuid,
+        source: &str,
+        external_id: &str,
+    ) -> Result<ExternalId> {
+        sqlx::query_as!(
+            ExternalId,
+            r#"
+            INSERT INTO athlete_external_ids (source, external_id, athlete_id)
+            VALUES ($1, $2, $3)
+            RETURNING source, external_id, created_at
+            "#,
+            source,
+            external_id,
+            athlete_id
+        )
+        .fetch_one(self.pool)
+        .await
+        .map_err(|e| already_linked(e.into(), source, external_id, "an athlete"))
+    }
+
+    pub async fn unlink_athlete(
+        &self,
+        athlete_id: Uuid,
+        source: &str,
+        external_id: &str,
+    ) -> Result<()> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM athlete_external_ids
+            WHERE athlete_id = $1 AND source = $2 AND external_id = $3
+            "#,
+            athlete_id,
+            source,
+            external_id
+        )
+        .execute(self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(StorageError::NotFound);
+        }
+
+        Ok(())
+    }
+
+    pub async fn list_for_competition(&self, competition_id: Uuid) -> Result<Vec<ExternalId>> {
+        let ids = sqlx::query_as!(
+            ExternalId,
+            r#"
+            SELECT source, external_id, created_at
+            FROM competition_external_ids
+            WHERE competition_id = $1
+            ORDER BY source, external_id
+            "#,
+            competition_id
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(ids)
+    }
+
+    /// Fails with a constraint violation when the identifier is linked already
+    pub async fn link_competition(
+        &self,
+        competition_id: Uuid,
+        source: &str,
+        external_id: &str,
+    ) -> Result<ExternalId> {
+        sqlx::query_as!(
+            ExternalId,
+            r#"
+            INSERT INTO competition_external_ids (source, external_id, competition_id)
+            VALUES ($1, $2, $3)
+            RETURNING source, external_id, created_at
+            "#,
+            source,
+            external_id,
+            competition_id
+        )
+        .fetch_one(self.pool)
+        .await
+        .map_err(|e| already_linked(e.into(), source, external_id, "a competition"))
+    }
+
+    pub async fn unlink_competition(
+        &self,
+        competition_id: Uuid,
+        source: &str,
+        external_id: &str,
+    ) -> Result<()> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM competition_external_ids
+            WHERE competition_id = $1 AND source = $2 AND external_id = $3
+            "#,
+            competition_id,
+            source,
+            external_id
+        )
+        .execute(self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(StorageError::NotFound);
+        }
+
+        Ok(())
+    }
+}
+
+fn already_linked(
+    error: StorageError,
+    source: &str,
+    external_id: &str,
+    owner: &str,
+) -> StorageError {
+    if error.is_unique_violation() {
+        StorageError::ConstraintViolation(format!(
+            "{} id {} is already linked to {}",
+            source, external_id, owner
+        ))
+    } else {
+        error
+    }
+}
@@ -0,0 +1,127 @@
+use sqlx::PgPool;
+use sqlx::types::Json;
+use uuid::Uuid;
+
+use crate::dto::federation::AgeDivisionRequest;
+use crate::error::{Result, StorageError};
+use crate::models::{AgeDivision, Federation, TieBreak};
+
+pub struct FederationRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> FederationRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// List all federations
+    pub async fn list(&self) -> Result<Vec<Federation>> {
+        let federations = sqlx::query_as!(
+            Federation,
+            r#"
+            SELECT federation_id, name, rulebook_id, country, abbreviation,
+                   tie_breaks as "tie_breaks: Json<Vec<TieBreak>>"
+            FROM federations
+            ORDER BY name
+            "#
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(federations)
+    }
+
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Federation> {
+        sqlx::query_as!(
+            Federation,
+            r#"
+            SELECT federation_id, name, rulebook_id, country, abbreviation,
+                   tie_breaks as "tie_breaks: Json<Vec<TieBreak>>"
+            FROM federations
+            WHERE federation_id = $1
+            "#,
+            id
+        )
+        .fetch_optional(self.pool)
+        .await?
+        .ok_or(StorageError::NotFound)
+    }
+
+    /// Sets the tie-break rules of a federation. Placings already stored keep the
+    /// previous rules until they are recomputed.
+    pub async fn update_tie_breaks(
+        &self,
+        federation_id: Uuid,
+        tie_breaks: &[TieBreak],
+    ) -> Result<Federation> {
+        sqlx::query_as!(
+            Federation,
+            r#"
+            UPDATE federations
+            SET tie_breaks = $2
+            WHERE federation_id = $1
+            RETURNING federation_id, name, rulebook_id, country, abbreviation,
+                      tie_breaks as "tie_breaks: Json<Vec<TieBreak>>"
+            "#,
+            federation_id,
+            Json(tie_breaks) as _
+        )
+        .fetch_optional(self.pool)
+        .await?
+        .ok_or(StorageError::NotFound)
+    }
+
+    /// Age divisions of a federation, youngest first
+    pub async fn list_age_divisions(&self, federation_id: Uuid) -> Result<Vec<AgeDivision>> {
+        let divisions = sqlx::query_as!(
+            AgeDivision,
+            r#"
+            SELECT age_division_id, federation_id, name, min_age, max_age, created_at
+            FROM age_divisions
+            WHERE federation_id = $1
+            ORDER BY min_age NULLS FIRST, max_age NULLS LAST, name
+            "#,
+            federation_id
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(divisions)
+    }
+
+    /// Replaces all the age divisions of a federation
+    pub async fn replace_age_divisions(
+        &self,
+        federation_id: Uuid,
+        divisions: &[AgeDivisionRequest],
+    ) -> Result<Vec<AgeDivision>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            "DELETE FROM age_divisions WHERE federation_id = $1",
+            federation_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        for division in divisions {
+            sqlx::query!(
+                r#"
+                INSERT INTO age_divisions (federation_id, name, min_age, max_age)
+                VALUES ($1, $2, $3, $4)
+                "#,
+                federation_id,
+                division.name,
+                division.min_age,
+                division.max_age
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        self.list_age_divisions(federation_id).await
+    }
+}
@@ -0,0 +1,51 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::models::ImportBatch;
+
+pub struct ImportBatchRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> ImportBatchRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Whether a file with this content hash was already imported
+    pub async fn exists_with_hash(&self, content_hash: &str) -> Result<bool> {
+        let exists = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM import_batches WHERE content_hash = $1) as "exists!""#,
+            content_hash
+        )
+        .fetch_one(self.pool)
+        .await?;
+
+        Ok(exists)
+    }
+
+    /// Batches that produced the competition or any of its participants, oldest first
+    pub async fn list_for_competition(&self, competition_id: Uuid) -> Result<Vec<ImportBatch>> {
+        let batches = sqlx::query_as!(
+            ImportBatch,
+            r#"
+            SELECT ib.import_batch_id, ib.source_type, ib.source_url, ib.file_path, ib.content_hash,
+                   ib.format_version, ib.extractor, ib.extracted_at, ib.importer_version,
+                   ib.category_count, ib.participant_count, ib.attempt_count, ib.imported_at
+            FROM import_batches ib
+            WHERE ib.import_batch_id IN (
+                SELECT import_batch_id FROM competitions WHERE competition_id = $1
+                UNION
+                SELECT import_batch_id FROM competition_participants WHERE competition_id = $1
+            )
+            ORDER BY ib.imported_at, ib.import_batch_id
+            "#,
+            competition_id
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(batches)
+    }
+}
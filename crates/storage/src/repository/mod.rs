@@ -1,4 +1,9 @@
 pub mod athlete;
 pub mod competition;
+pub mod external_id;
+pub mod federation;
+pub mod import_batch;
 pub mod ranking;
 pub mod ris;
+pub mod stats;
+pub mod submission;
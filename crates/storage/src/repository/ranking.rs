@@ -1,6 +1,6 @@
 use chrono::NaiveDate;
 use rust_decimal::Decimal;
-use sqlx::{FromRow, PgPool, QueryBuilder};
+use sqlx::{FromRow, PgPool, Postgres, QueryBuilder};
 use uuid::Uuid;
 
 use crate::dto::ranking::{AthleteInfo, CompetitionInfo, GlobalRankingEntry, GlobalRankingFilter};
@@ -56,20 +56,13 @@ impl<'a> RankingRepository<'a> {
             SELECT COUNT(DISTINCT cp.participant_id)
             FROM competition_participants cp
             INNER JOIN athletes a ON cp.athlete_id = a.athlete_id
+            INNER JOIN competitions c ON cp.competition_id = c.competition_id
             INNER JOIN lifts l ON cp.participant_id = l.participant_id
             WHERE 1=1
             "#,
         );
 
-        if let Some(ref gender) = filter.gender {
-            query.push(" AND a.gender = ");
-            query.push_bind(gender);
-        }
-
-        if let Some(ref country) = filter.country {
-            query.push(" AND a.country = ");
-            query.push_bind(country);
-        }
+        push_filters(&mut query, filter);
 
         let count = query
             .build_query_scalar::<i64>()
@@ -117,15 +110,7 @@ impl<'a> RankingRepository<'a> {
             "#,
         );
 
-        if let Some(ref gender) = filter.gender {
-            query.push(" AND a.gender = ");
-            query.push_bind(gender);
-        }
-
-        if let Some(ref country) = filter.country {
-            query.push(" AND a.country = ");
-            query.push_bind(country);
-        }
+        push_filters(&mut query, filter);
 
         query.push(
             r#"
@@ -184,6 +169,33 @@ impl<'a> RankingRepository<'a> {
     }
 }
 
+/// Conditions on the athlete `a` and the competition `c` of a participant
+fn push_filters(query: &mut QueryBuilder<'_, Postgres>, filter: &GlobalRankingFilter) {
+    if let Some(ref gender) = filter.gender {
+        query.push(" AND a.gender = ");
+        query.push_bind(gender.clone());
+    }
+
+    if let Some(ref country) = filter.country {
+        query.push(" AND a.country = ");
+        query.push_bind(country.clone());
+    }
+
+    if let Some(ref age_division) = filter.age_division {
+        query.push(
+            r#"
+            AND EXISTS (
+                SELECT 1 FROM age_divisions d
+                WHERE d.federation_id = c.federation_id
+                  AND athlete_age(a.birth_date, a.birth_year, c.start_date)
+                      BETWEEN COALESCE(d.min_age, 0) AND COALESCE(d.max_age, 32767)
+                  AND d.name = "#,
+        );
+        query.push_bind(age_division.clone());
+        query.push(")");
+    }
+}
+
 fn decimal_to_f64(decimal: Decimal) -> f64 {
     decimal.to_string().parse().unwrap_or(0.0)
 }
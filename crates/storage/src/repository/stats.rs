@@ -0,0 +1,434 @@
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::dto::stats::{
+    AttemptNumberStats, AttemptStats, CompetitionStatsResponse, GlobalStatsResponse, MovementStats,
+    NoRepReasonCount, TopLifter,
+};
+use crate::error::Result;
+use crate::repository::competition::CompetitionRepository;
+
+const NO_REP_REASONS_LIMIT: usize = 10;
+const TOP_LIFTERS_LIMIT: i64 = 10;
+
+pub struct StatsRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> StatsRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Attempt statistics for a single competition
+    pub async fn competition_stats(&self, slug: &str) -> Result<CompetitionStatsResponse> {
+        let competition = CompetitionRepository::new(self.pool)
+            .find_by_slug(slug)
+            .await?;
+
+        let stats = self.attempt_stats(Some(competition.competition_id)).await?;
+
+        Ok(CompetitionStatsResponse {
+            competition_id: competition.competition_id,
+            name: competition.name,
+            slug: competition.slug,
+            stats,
+        })
+    }
+
+    /// Attempt statistics aggregated across every competition
+    pub async fn global_stats(&self) -> Result<GlobalStatsResponse> {
+        let total_competitions =
+            sqlx::query_scalar!(r#"SELECT COUNT(*) as "count!" FROM competitions"#)
+                .fetch_one(self.pool)
+                .await?;
+
+        let stats = self.attempt_stats(None).await?;
+
+        Ok(GlobalStatsResponse {
+            total_competitions,
+            stats,
+        })
+    }
+
+    /// Builds the statistics, restricted to one competition when `competition_id` is set
+    async fn attempt_stats(&self, competition_id: Option<Uuid>) -> Result<AttemptStats> {
+        let attempt_rows = sqlx::query_as!(
+            AttemptCount,
+            r#"
+            SELECT
+                l.movement_name,
+                a.attempt_number,
+                a.kind = 'record' as "is_record!",
+                COUNT(*) as "total!",
+                COUNT(*) FILTER (WHERE a.is_successful) as "successful!"
+            FROM attempts a
+            JOIN lifts l ON l.lift_id = a.lift_id
+            JOIN competition_participants cp ON cp.participant_id = l.participant_id
+            LEFT JOIN movements m ON m.name = l.movement_name
+            WHERE ($1::uuid IS NULL OR cp.competition_id = $1)
+            GROUP BY l.movement_name, m.display_order, a.attempt_number, a.kind
+            ORDER BY m.display_order NULLS LAST, l.movement_name, a.attempt_number
+            "#,
+            competition_id
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        let jump_rows = sqlx::query!(
+            r#"
+            SELECT
+                l.movement_name,
+                COUNT(*) as "jumps!",
+                COALESCE(SUM(next.weight - a.weight), 0) as "total_jump!: Decimal"
+            FROM attempts a
+            JOIN attempts next
+                ON next.lift_id = a.lift_id AND next.attempt_number = a.attempt_number + 1
+                AND next.kind = 'regular'
+            JOIN lifts l ON l.lift_id = a.lift_id
+            JOIN competition_participants cp ON cp.participant_id = l.participant_id
+            WHERE ($1::uuid IS NULL OR cp.competition_id = $1)
+            GROUP BY l.movement_name
+            "#,
+            competition_id
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        let bomb_out_rows = sqlx::query!(
+            r#"
+            WITH lift_outcomes AS (
+                SELECT l.participant_id, l.movement_name, BOOL_OR(a.is_successful AND a.kind = 'regular') as made
+                FROM lifts l
+                JOIN attempts a ON a.lift_id = l.lift_id
+                JOIN competition_participants cp ON cp.participant_id = l.participant_id
+                WHERE ($1::uuid IS NULL OR cp.competition_id = $1)
+                GROUP BY l.lift_id, l.participant_id, l.movement_name
+            )
+            SELECT movement_name, COUNT(*) as "bomb_outs!"
+            FROM lift_outcomes
+            WHERE NOT made
+            GROUP BY movement_name
+            "#,
+            competition_id
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        let bomb_outs = sqlx::query_scalar!(
+            r#"
+            WITH lift_outcomes AS (
+                SELECT l.participant_id, BOOL_OR(a.is_successful AND a.kind = 'regular') as made
+                FROM lifts l
+                JOIN attempts a ON a.lift_id = l.lift_id
+                JOIN competition_participants cp ON cp.participant_id = l.participant_id
+                WHERE ($1::uuid IS NULL OR cp.competition_id = $1)
+                GROUP BY l.lift_id, l.participant_id
+            )
+            SELECT COUNT(DISTINCT participant_id) as "count!"
+            FROM lift_outcomes
+            WHERE NOT made
+            "#,
+            competition_id
+        )
+        .fetch_one(self.pool)
+        .await?;
+
+        let total_participants = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) as "count!"
+            FROM competition_participants cp
+            WHERE ($1::uuid IS NULL OR cp.competition_id = $1)
+            "#,
+            competition_id
+        )
+        .fetch_one(self.pool)
+        .await?;
+
+        let no_rep_rows = sqlx::query_as!(
+            NoRepReasonRow,
+            r#"
+            SELECT
+                TRIM(a.no_rep_reason) as "reason!",
+                a.kind = 'record' as "is_record!",
+                COUNT(*) as "count!"
+            FROM attempts a
+            JOIN lifts l ON l.lift_id = a.lift_id
+            JOIN competition_participants cp ON cp.participant_id = l.participant_id
+            WHERE ($1::uuid IS NULL OR cp.competition_id = $1)
+              AND NOT a.is_successful
+              AND a.no_rep_reason IS NOT NULL
+              AND TRIM(a.no_rep_reason) <> ''
+            GROUP BY TRIM(a.no_rep_reason), a.kind
+            "#,
+            competition_id
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        let top_lifters = sqlx::query_as!(
+            TopLifter,
+            r#"
+            SELECT
+                a.athlete_id,
+                a.first_name,
+                a.last_name,
+                a.slug,
+                a.country,
+                a.gender,
+                cat.name as category_name,
+                c.name as competition_name,
+                c.slug as competition_slug,
+                cp.bodyweight,
+                COALESCE(SUM(l.max_weight), 0) as "total!: Decimal",
+                cp.ris_score as "ris_score!: Decimal"
+            FROM competition_participants cp
+            JOIN athletes a ON a.athlete_id = cp.athlete_id
+            JOIN categories cat ON cat.category_id = cp.category_id
+            JOIN competitions c ON c.competition_id = cp.competition_id
+            LEFT JOIN lifts l ON l.participant_id = cp.participant_id
+            WHERE ($1::uuid IS NULL OR cp.competition_id = $1)
+              AND cp.ris_score IS NOT NULL
+              AND NOT cp.is_disqualified
+            GROUP BY cp.participant_id, a.athlete_id, cat.name, c.name, c.slug
+            ORDER BY cp.ris_score DESC
+            LIMIT $2
+            "#,
+            competition_id,
+            TOP_LIFTERS_LIMIT
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        let jumps: HashMap<String, (i64, Decimal)> = jump_rows
+            .into_iter()
+            .map(|r| (r.movement_name, (r.jumps, r.total_jump)))
+            .collect();
+
+        let movement_bomb_outs: HashMap<String, i64> = bomb_out_rows
+            .into_iter()
+            .map(|r| (r.movement_name, r.bomb_outs))
+            .collect();
+
+        let (movements, attempt_numbers) =
+            tally_attempts(attempt_rows, &jumps, &movement_bomb_outs);
+        let no_rep_reasons = tally_no_rep_reasons(no_rep_rows, NO_REP_REASONS_LIMIT);
+
+        let total_attempts = movements.iter().map(|m| m.total_attempts).sum();
+        let successful_attempts = movements.iter().map(|m| m.successful_attempts).sum();
+        let (jump_count, jump_total) = jumps
+            .values()
+            .fold((0, Decimal::ZERO), |(count, total), (c, t)| {
+                (count + c, total + t)
+            });
+
+        Ok(AttemptStats {
+            total_participants,
+            total_attempts,
+            successful_attempts,
+            success_rate: success_rate(successful_attempts, total_attempts),
+            average_jump: average(jump_total, jump_count),
+            bomb_outs,
+            movements,
+            attempt_numbers,
+            no_rep_reasons,
+            top_lifters,
+        })
+    }
+}
+
+/// One line of the attempt counts, per movement, attempt number and attempt kind
+struct AttemptCount {
+    movement_name: String,
+    attempt_number: i16,
+    is_record: bool,
+    total: i64,
+    successful: i64,
+}
+
+/// Per movement and per attempt number figures. Record attempts are left out: they
+/// are a fourth attempt outside the competition, counted neither in the totals nor
+/// in the success rates.
+fn tally_attempts(
+    rows: Vec<AttemptCount>,
+    jumps: &HashMap<String, (i64, Decimal)>,
+    movement_bomb_outs: &HashMap<String, i64>,
+) -> (Vec<MovementStats>, Vec<AttemptNumberStats>) {
+    let mut movements: Vec<MovementStats> = Vec::new();
+    let mut attempt_numbers: Vec<AttemptNumberStats> = Vec::new();
+
+    for row in rows.into_iter().filter(|r| !r.is_record) {
+        if movements.last().map(|m| &m.movement_name) != Some(&row.movement_name) {
+            let (jump_count, jump_total) =
+                jumps.get(&row.movement_name).copied().unwrap_or_default();
+
+            movements.push(MovementStats {
+                movement_name: row.movement_name.clone(),
+                total_attempts: 0,
+                successful_attempts: 0,
+                success_rate: 0.0,
+                average_jump: average(jump_total, jump_count),
+                bomb_outs: movement_bomb_outs
+                    .get(&row.movement_name)
+                    .copied()
+                    .unwrap_or(0),
+                attempt_numbers: Vec::new(),
+            });
+        }
+
+        if let Some(movement) = movements.last_mut() {
+            movement.total_attempts += row.total;
+            movement.successful_attempts += row.successful;
+            movement.attempt_numbers.push(AttemptNumberStats {
+                attempt_number: row.attempt_number,
+                total_attempts: row.total,
+                successful_attempts: row.successful,
+                success_rate: success_rate(row.successful, row.total),
+            });
+        }
+
+        match attempt_numbers
+            .iter_mut()
+            .find(|a| a.attempt_number == row.attempt_number)
+        {
+            Some(stats) => {
+                stats.total_attempts += row.total;
+                stats.successful_attempts += row.successful;
+            }
+            None => attempt_numbers.push(AttemptNumberStats {
+                attempt_number: row.attempt_number,
+                total_attempts: row.total,
+                successful_attempts: row.successful,
+                success_rate: 0.0,
+            }),
+        }
+    }
+
+    for movement in &mut movements {
+        movement.success_rate = success_rate(movement.successful_attempts, movement.total_attempts);
+    }
+
+    attempt_numbers.sort_by_key(|a| a.attempt_number);
+    for stats in &mut attempt_numbers {
+        stats.success_rate = success_rate(stats.successful_attempts, stats.total_attempts);
+    }
+
+    (movements, attempt_numbers)
+}
+
+/// One line of the no-rep reason counts, per reason and attempt kind
+struct NoRepReasonRow {
+    reason: String,
+    is_record: bool,
+    count: i64,
+}
+
+/// The most frequent no-rep reasons of regular attempts, like the other figures
+/// record attempts are left out
+fn tally_no_rep_reasons(rows: Vec<NoRepReasonRow>, limit: usize) -> Vec<NoRepReasonCount> {
+    let mut reasons: Vec<NoRepReasonCount> = rows
+        .into_iter()
+        .filter(|r| !r.is_record)
+        .map(|r| NoRepReasonCount {
+            reason: r.reason,
+            count: r.count,
+        })
+        .collect();
+
+    reasons.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.reason.cmp(&b.reason)));
+    reasons.truncate(limit);
+    reasons
+}
+
+/// Ratio of successful attempts rounded to 4 decimals, `0.0` when nothing was attempted
+fn success_rate(successful: i64, total: i64) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    ((successful as f64 / total as f64) * 10_000.0).round() / 10_000.0
+}
+
+fn average(total: Decimal, count: i64) -> Option<Decimal> {
+    if count == 0 {
+        return None;
+    }
+    Some((total / Decimal::from(count)).round_dp(2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(
+        movement_name: &str,
+        attempt_number: i16,
+        is_record: bool,
+        total: i64,
+        successful: i64,
+    ) -> AttemptCount {
+        AttemptCount {
+            movement_name: movement_name.to_string(),
+            attempt_number,
+            is_record,
+            total,
+            successful,
+        }
+    }
+
+    #[test]
+    fn test_record_attempts_are_left_out() {
+        let rows = vec![
+            count("Squat", 1, false, 4, 4),
+            count("Squat", 2, false, 4, 3),
+            count("Squat", 3, false, 4, 1),
+            count("Squat", 4, true, 2, 2),
+            count("Dips", 1, false, 4, 2),
+        ];
+
+        let (movements, attempt_numbers) = tally_attempts(rows, &HashMap::new(), &HashMap::new());
+
+        assert_eq!(movements.len(), 2);
+        let squat = &movements[0];
+        assert_eq!(squat.movement_name, "Squat");
+        assert_eq!(squat.total_attempts, 12);
+        assert_eq!(squat.successful_attempts, 8);
+        assert_eq!(squat.success_rate, 0.6667);
+        assert_eq!(squat.attempt_numbers.len(), 3);
+
+        assert_eq!(
+            attempt_numbers
+                .iter()
+                .map(|a| (a.attempt_number, a.total_attempts))
+                .collect::<Vec<_>>(),
+            vec![(1, 8), (2, 4), (3, 4)]
+        );
+    }
+
+    #[test]
+    fn test_failed_record_attempts_have_no_no_rep_reason() {
+        let row = |reason: &str, is_record, count| NoRepReasonRow {
+            reason: reason.to_string(),
+            is_record,
+            count,
+        };
+        let rows = vec![
+            row("Profondeur", false, 3),
+            row("Profondeur", true, 5),
+            row("Verrouillage", false, 3),
+            row("Rebond", true, 2),
+            row("Menton", false, 1),
+        ];
+
+        let reasons = tally_no_rep_reasons(rows, 2);
+
+        assert_eq!(
+            reasons
+                .iter()
+                .map(|r| (r.reason.as_str(), r.count))
+                .collect::<Vec<_>>(),
+            vec![("Profondeur", 3), ("Verrouillage", 3)]
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::canonical::loader::{CanonicalLoader, ImportOrigin};
+use crate::canonical::models::CanonicalFormat;
+use crate::dto::submission::SubmissionSummary;
+use crate::error::{Result, StorageError};
+use crate::models::{NewSubmission, Submission, SubmissionStatus};
+
+pub struct SubmissionRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> SubmissionRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Queues a submission as pending
+    pub async fn create(&self, submission: &NewSubmission) -> Result<Submission> {
+        let submission = sqlx::query_as!(
+            Submission,
+            r#"
+            INSERT INTO submissions (competition_slug, competition_name, content, content_hash,
+                                     validation_report, submitted_by)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING submission_id, status, competition_slug, competition_name, content,
+                      content_hash, validation_report, dry_run_diff, submitted_by,
+                      submitted_at, reviewed_at, review_comment
+            "#,
+            submission.competition_slug,
+            submission.competition_name,
+            submission.content,
+            submission.content_hash,
+            submission.validation_report,
+            submission.submitted_by
+        )
+        .fetch_one(self.pool)
+        .await
+        .map_err(|e| match StorageError::from(e) {
+            error if error.is_unique_violation() => StorageError::ConstraintViolation(
+                "This file is already waiting for review".to_string(),
+            ),
+            error => error,
+        })?;
+
+        Ok(submission)
+    }
+
+    /// Submissions with the given status, oldest first
+    pub async fn list(&self, status: SubmissionStatus) -> Result<Vec<SubmissionSummary>> {
+        let submissions = sqlx::query_as!(
+            SubmissionSummary,
+            r#"
+            SELECT submission_id, status, competition_slug, competition_name, content_hash,
+                   submitted_by, submitted_at, reviewed_at, review_comment
+            FROM submissions
+            WHERE status = $1
+            ORDER BY submitted_at, submission_id
+            "#,
+            status.as_str()
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(submissions)
+    }
+
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Submission> {
+        let submission = sqlx::query_as!(
+            Submission,
+            r#"
+            SELECT submission_id, status, competition_slug, competition_name, content,
+                   content_hash, validation_report, dry_run_diff, submitted_by,
+                   submitted_at, reviewed_at, review_comment
+            FROM submissions
+            WHERE submission_id = $1
+            "#,
+            id
+        )
+        .fetch_optional(self.pool)
+        .await?
+        .ok_or(StorageError::NotFound)?;
+
+        Ok(submission)
+    }
+
+    /// Stores the dry-run diff of a submission, unless one was stored meanwhile
+    pub async fn record_dry_run(&self, id: Uuid, diff: &serde_json::Value) -> Result<Submission> {
+        let submission = sqlx::query_as!(
+            Submission,
+            r#"
+            UPDATE submissions
+            SET dry_run_diff = COALESCE(dry_run_diff, $2)
+            WHERE submission_id = $1
+            RETURNING submission_id, status, competition_slug, competition_name, content,
+                      content_hash, validation_report, dry_run_diff, submitted_by,
+                      submitted_at, reviewed_at, review_comment
+            "#,
+            id,
+            diff
+        )
+        .fetch_optional(self.pool)
+        .await?
+        .ok_or(StorageError::NotFound)?;
+
+        Ok(submission)
+    }
+
+    /// Records the decision on a pending submission. Fails with a constraint
+    /// violation when it was already reviewed.
+    pub async fn review(
+        &self,
+        id: Uuid,
+        status: SubmissionStatus,
+        comment: Option<&str>,
+    ) -> Result<Submission> {
+        let mut conn = self.pool.acquire().await?;
+        self.claim(&mut conn, id, status, comment).await
+    }
+
+    /// Approves a pending submission and imports its file in the same transaction.
+    /// The submission is claimed first, so a concurrent review waits for the import
+    /// and then finds it reviewed; a failed import leaves it pending.
+    pub async fn approve(
+        &self,
+        id: Uuid,
+        comment: Option<&str>,
+        canonical: &CanonicalFormat,
+        origin: &ImportOrigin,
+    ) -> Result<Submission> {
+        let mut tx = self.pool.begin().await?;
+
+        let approved = self
+            .claim(&mut tx, id, SubmissionStatus::Approved, comment)
+            .await?;
+        CanonicalLoader::new(self.pool)
+            .import_in_transaction(canonical, origin, &mut tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(approved)
+    }
+
+    /// Moves a pending submission to `status`, locking it until `conn` commits
+    async fn claim(
+        &self,
+        conn: &mut PgConnection,
+        id: Uuid,
+        status: SubmissionStatus,
+        comment: Option<&str>,
+    ) -> Result<Submission> {
+        let reviewed = sqlx::query_as!(
+            Submission,
+            r#"
+            UPDATE submissions
+            SET status = $2, review_comment = $3, reviewed_at = CURRENT_TIMESTAMP
+            WHERE submission_id = $1 AND status = 'pending'
+            RETURNING submission_id, status, competition_slug, competition_name, content,
+                      content_hash, validation_report, dry_run_diff, submitted_by,
+                      submitted_at, reviewed_at, review_comment
+            "#,
+            id,
+            status.as_str(),
+            comment
+        )
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        match reviewed {
+            Some(submission) => Ok(submission),
+            None => {
+                let submission = self.find_by_id(id).await?;
+                Err(StorageError::ConstraintViolation(format!(
+                    "Submission was already {}",
+                    submission.status
+                )))
+            }
+        }
+    }
+}
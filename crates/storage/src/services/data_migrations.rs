@@ -0,0 +1,313 @@
+//! Data migrations written in Rust, for changes that reuse rules of this crate and
+//! cannot be expressed in SQL. They run after the SQL migrations, each one once and
+//! in its own transaction, and are recorded in `data_migrations`.
+
+use std::collections::{HashMap, HashSet};
+
+use sqlx::{PgConnection, PgPool};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::models::NormalizedAthleteName;
+use crate::services::placings;
+
+#[derive(Debug, Clone, Copy)]
+enum DataMigration {
+    /// Names stored before `normalize::name_part` capitalized each segment of a
+    /// word ("Jean-pierre" became "Jean-Pierre", "Van der berg" "Van der Berg")
+    AthleteNameNormalization,
+    /// Placings of the competitions completed before they were stored
+    PlacingsBackfill,
+}
+
+impl DataMigration {
+    /// Every data migration, in the order they are run
+    const ALL: [DataMigration; 2] = [
+        DataMigration::AthleteNameNormalization,
+        DataMigration::PlacingsBackfill,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            DataMigration::AthleteNameNormalization => "athlete_name_normalization",
+            DataMigration::PlacingsBackfill => "placings_backfill",
+        }
+    }
+
+    async fn apply(&self, conn: &mut PgConnection) -> Result<()> {
+        match self {
+            DataMigration::AthleteNameNormalization => normalize_athlete_names(conn).await,
+            DataMigration::PlacingsBackfill => backfill_placings(conn).await,
+        }
+    }
+}
+
+/// Runs the data migrations not recorded yet
+pub async fn run(pool: &PgPool) -> Result<()> {
+    for migration in DataMigration::ALL {
+        let mut tx = pool.begin().await?;
+
+        // Recorded first: another instance starting at the same time waits for this
+        // transaction, then skips the migration
+        let claimed = sqlx::query!(
+            "INSERT INTO data_migrations (name) VALUES ($1) ON CONFLICT (name) DO NOTHING",
+            migration.name()
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+            == 1;
+
+        if claimed {
+            migration.apply(&mut tx).await?;
+            tx.commit().await?;
+            info!("Applied data migration {}", migration.name());
+        }
+    }
+
+    Ok(())
+}
+
+struct StoredAlias {
+    alias_id: Uuid,
+    athlete_id: Uuid,
+    first_name: String,
+    last_name: String,
+}
+
+/// What becomes of a stored alias once normalized
+#[derive(Debug, PartialEq, Eq)]
+enum AliasChange {
+    Rename {
+        first_name: String,
+        last_name: String,
+    },
+    /// The normalized alias is the athlete's name or another of its aliases
+    Remove,
+}
+
+/// Normalizes the stored athlete names and aliases with the current rules, so that
+/// the exact comparisons of imports and aliases match them again. Slugs do not
+/// depend on letter case and are left as they are.
+async fn normalize_athlete_names(conn: &mut PgConnection) -> Result<()> {
+    let athletes = sqlx::query!("SELECT athlete_id, first_name, last_name FROM athletes")
+        .fetch_all(&mut *conn)
+        .await?;
+
+    let mut names = HashMap::with_capacity(athletes.len());
+    let (mut ids, mut first_names, mut last_names) = (Vec::new(), Vec::new(), Vec::new());
+    for athlete in athletes {
+        let name = NormalizedAthleteName::new(&athlete.first_name, &athlete.last_name);
+        let (first_name, last_name) = name.as_database_tuple();
+        if first_name != athlete.first_name || last_name != athlete.last_name {
+            ids.push(athlete.athlete_id);
+            first_names.push(first_name.to_string());
+            last_names.push(last_name.to_string());
+        }
+        names.insert(athlete.athlete_id, name);
+    }
+
+    sqlx::query!(
+        r#"
+        UPDATE athletes a
+        SET first_name = n.first_name, last_name = n.last_name
+        FROM UNNEST($1::uuid[], $2::text[], $3::text[]) AS n(athlete_id, first_name, last_name)
+        WHERE a.athlete_id = n.athlete_id
+        "#,
+        &ids,
+        &first_names,
+        &last_names
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    let aliases = sqlx::query_as!(
+        StoredAlias,
+        "SELECT alias_id, athlete_id, first_name, last_name FROM athlete_aliases ORDER BY created_at, alias_id"
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let changes = alias_changes(&names, aliases);
+    let removed = changes
+        .iter()
+        .filter(|(_, change)| *change == AliasChange::Remove)
+        .map(|(alias_id, _)| *alias_id)
+        .collect::<Vec<_>>();
+    let (mut alias_ids, mut first_names, mut last_names) = (Vec::new(), Vec::new(), Vec::new());
+    for (alias_id, change) in changes {
+        if let AliasChange::Rename {
+            first_name,
+            last_name,
+        } = change
+        {
+            alias_ids.push(alias_id);
+            first_names.push(first_name);
+            last_names.push(last_name);
+        }
+    }
+
+    sqlx::query!(
+        "DELETE FROM athlete_aliases WHERE alias_id = ANY($1::uuid[])",
+        &removed
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        UPDATE athlete_aliases al
+        SET first_name = n.first_name, last_name = n.last_name
+        FROM UNNEST($1::uuid[], $2::text[], $3::text[]) AS n(alias_id, first_name, last_name)
+        WHERE al.alias_id = n.alias_id
+        "#,
+        &alias_ids,
+        &first_names,
+        &last_names
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    info!(
+        "Normalized {} athlete names and {} aliases, removed {} duplicate aliases",
+        ids.len(),
+        alias_ids.len(),
+        removed.len()
+    );
+
+    Ok(())
+}
+
+/// Computes the placings of every completed competition
+async fn backfill_placings(conn: &mut PgConnection) -> Result<()> {
+    let competition_ids = sqlx::query_scalar!(
+        "SELECT competition_id FROM competitions WHERE status = $1",
+        placings::COMPLETED
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut placed = 0;
+    for competition_id in &competition_ids {
+        placed += placings::update_placings(conn, *competition_id).await?;
+    }
+
+    info!(
+        "Placed {} participants of {} completed competitions",
+        placed,
+        competition_ids.len()
+    );
+
+    Ok(())
+}
+
+/// The aliases whose normalized spelling differs from the stored one. The first
+/// alias of an athlete with a spelling keeps it, later ones and aliases spelled
+/// like the athlete's name are removed.
+fn alias_changes(
+    names: &HashMap<Uuid, NormalizedAthleteName>,
+    aliases: Vec<StoredAlias>,
+) -> Vec<(Uuid, AliasChange)> {
+    let normalized = aliases
+        .into_iter()
+        .map(|alias| {
+            let name = NormalizedAthleteName::new(&alias.first_name, &alias.last_name);
+            let unchanged = name.database_first_name() == alias.first_name
+                && name.database_last_name() == alias.last_name;
+            (alias, name, unchanged)
+        })
+        .collect::<Vec<_>>();
+
+    // Spellings already taken: athlete names and the aliases left unchanged
+    let mut taken: HashSet<(Uuid, NormalizedAthleteName)> = names
+        .iter()
+        .map(|(athlete_id, name)| (*athlete_id, name.clone()))
+        .collect();
+    let mut changes = Vec::new();
+    for (alias, name, _) in normalized.iter().filter(|(_, _, unchanged)| *unchanged) {
+        if !taken.insert((alias.athlete_id, name.clone())) {
+            changes.push((alias.alias_id, AliasChange::Remove));
+        }
+    }
+
+    for (alias, name, _) in normalized
+        .into_iter()
+        .filter(|(_, _, unchanged)| !unchanged)
+    {
+        let change = if taken.insert((alias.athlete_id, name.clone())) {
+            let (first_name, last_name) = name.as_database_tuple();
+            AliasChange::Rename {
+                first_name: first_name.to_string(),
+                last_name: last_name.to_string(),
+            }
+        } else {
+            AliasChange::Remove
+        };
+        changes.push((alias.alias_id, change));
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alias(athlete_id: Uuid, first_name: &str, last_name: &str) -> StoredAlias {
+        StoredAlias {
+            alias_id: Uuid::new_v4(),
+            athlete_id,
+            first_name: first_name.to_string(),
+            last_name: last_name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_baseline_names_match_imported_names() {
+        // Spellings stored before segments of a word were capitalized
+        for (stored, imported) in [
+            (("Jean-pierre", "O'brien"), ("JEAN-PIERRE", "O'BRIEN")),
+            (("Hugo", "Van der berg"), ("hugo", "van der berg")),
+        ] {
+            let normalized = NormalizedAthleteName::new(stored.0, stored.1);
+            assert_ne!(normalized.as_database_tuple(), stored);
+            assert_eq!(
+                normalized,
+                NormalizedAthleteName::new(imported.0, imported.1)
+            );
+        }
+    }
+
+    #[test]
+    fn test_alias_changes() {
+        let athlete_id = Uuid::new_v4();
+        let names = HashMap::from([(
+            athlete_id,
+            NormalizedAthleteName::new("Jean-pierre", "Dupont"),
+        )]);
+
+        let renamed = alias(athlete_id, "Jean-pierre", "Martin");
+        let spelled_twice = alias(athlete_id, "Jean-pierre", "Du pont");
+        let normalized = alias(athlete_id, "Jean-Pierre", "Du Pont");
+        let own_name = alias(athlete_id, "Jean-pierre", "Dupont");
+        let ids = [renamed.alias_id, spelled_twice.alias_id, own_name.alias_id];
+
+        let changes = alias_changes(&names, vec![renamed, spelled_twice, normalized, own_name]);
+
+        assert_eq!(
+            changes,
+            vec![
+                (
+                    ids[0],
+                    AliasChange::Rename {
+                        first_name: "Jean-Pierre".to_string(),
+                        last_name: "Martin".to_string(),
+                    }
+                ),
+                (ids[1], AliasChange::Remove),
+                (ids[2], AliasChange::Remove),
+            ]
+        );
+    }
+}
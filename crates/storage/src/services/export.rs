@@ -0,0 +1,833 @@
+//! Open-data bulk export of the database.
+//!
+//! The bundle is a ZIP archive holding one flat CSV per resource and a
+//! `datapackage.json` descriptor following the Frictionless Data Package spec.
+//! Rows are streamed from the database straight into the archive, so the export
+//! never holds a full table in memory.
+
+use std::borrow::Cow;
+
+use async_zip::tokio::write::ZipFileWriter;
+use async_zip::{Compression, ZipDateTime, ZipEntryBuilder};
+use chrono::{NaiveDate, Utc};
+use futures_util::{AsyncWriteExt as _, Stream, TryStreamExt};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::types::Json;
+use sqlx::{PgConnection, PgPool};
+use tokio::io::{AsyncWrite, AsyncWriteExt as _};
+use uuid::Uuid;
+
+use crate::error::{Result, StorageError};
+
+/// Version of the bundle layout, bumped whenever a resource schema changes
+pub const EXPORT_FORMAT_VERSION: &str = "1.0.0";
+
+const DESCRIPTOR_FILENAME: &str = "datapackage.json";
+
+/// Number of rows serialized before they are flushed into the archive
+const ROWS_PER_CHUNK: usize = 500;
+
+/// File name of a bundle produced on the given date
+pub fn bundle_filename(date: NaiveDate) -> String {
+    format!(
+        "openstreetlifting-export-v{}-{}.zip",
+        EXPORT_FORMAT_VERSION,
+        date.format("%Y-%m-%d")
+    )
+}
+
+/// Writes the full export bundle to `out` and returns its descriptor
+pub async fn write_bundle<W>(pool: &PgPool, out: W) -> Result<DataPackage>
+where
+    W: AsyncWrite + Unpin,
+{
+    let mut zip = ZipFileWriter::with_tokio(out);
+
+    // Every resource is read from the same snapshot, so that the references between
+    // them hold even when an import runs during the export
+    let mut tx = pool.begin().await?;
+    sqlx::query!("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+        .execute(&mut *tx)
+        .await?;
+
+    let movements = fetch_movements(&mut tx).await?;
+    let results = fetch_results(&mut tx).map_ok(|row| row.into_record(&movements));
+
+    let resources = vec![
+        write_resource(&mut zip, &results_spec(&movements), results).await?,
+        write_resource(&mut zip, &ATTEMPTS, fetch_attempts(&mut tx)).await?,
+        write_resource(&mut zip, &ATHLETES, fetch_athletes(&mut tx)).await?,
+        write_resource(&mut zip, &COMPETITIONS, fetch_competitions(&mut tx)).await?,
+        write_resource(&mut zip, &FEDERATIONS, fetch_federations(&mut tx)).await?,
+    ];
+    tx.commit().await?;
+
+    let package = DataPackage {
+        profile: "tabular-data-package",
+        name: "openstreetlifting",
+        title: "OpenStreetlifting open data export",
+        version: EXPORT_FORMAT_VERSION,
+        created: Utc::now().to_rfc3339(),
+        resources,
+    };
+
+    let descriptor = serde_json::to_vec_pretty(&package)
+        .map_err(|e| StorageError::Export(format!("Failed to serialize descriptor: {}", e)))?;
+    let entry = zip_entry(DESCRIPTOR_FILENAME.to_string());
+    zip.write_entry_whole(entry, &descriptor)
+        .await
+        .map_err(zip_error)?;
+
+    let mut out = zip.close().await.map_err(zip_error)?.into_inner();
+    out.shutdown().await?;
+
+    Ok(package)
+}
+
+/// Streams every row of a resource into its own archive entry
+async fn write_resource<W, T, S>(
+    zip: &mut ZipFileWriter<W>,
+    spec: &ResourceSpec,
+    mut rows: S,
+) -> Result<DataResource>
+where
+    W: AsyncWrite + Unpin,
+    T: Serialize,
+    S: Stream<Item = std::result::Result<T, sqlx::Error>> + Unpin,
+{
+    let path = format!("{}.csv", spec.name);
+    let entry = zip_entry(path.clone());
+    let mut writer = zip.write_entry_stream(entry).await.map_err(zip_error)?;
+
+    let mut hasher = Sha256::new();
+    let mut bytes = 0u64;
+    let mut row_count = 0u64;
+
+    let header = spec
+        .fields
+        .iter()
+        .map(|f| f.name.as_ref())
+        .collect::<Vec<_>>()
+        .join(",")
+        + "\n";
+    hasher.update(header.as_bytes());
+    bytes += header.len() as u64;
+    writer.write_all(header.as_bytes()).await?;
+
+    let mut chunk = Vec::with_capacity(ROWS_PER_CHUNK);
+    loop {
+        let row = rows.try_next().await?;
+        let finished = row.is_none();
+
+        if let Some(row) = row {
+            chunk.push(row);
+            row_count += 1;
+        }
+
+        if chunk.len() >= ROWS_PER_CHUNK || (finished && !chunk.is_empty()) {
+            let data = serialize_rows(&chunk)?;
+            hasher.update(&data);
+            bytes += data.len() as u64;
+            writer.write_all(&data).await?;
+            chunk.clear();
+        }
+
+        if finished {
+            break;
+        }
+    }
+
+    writer.close().await.map_err(zip_error)?;
+
+    Ok(DataResource {
+        name: spec.name,
+        path,
+        profile: "tabular-data-resource",
+        format: "csv",
+        mediatype: "text/csv",
+        encoding: "utf-8",
+        bytes,
+        rows: row_count,
+        hash: format!("sha256:{:x}", hasher.finalize()),
+        schema: TableSchema {
+            fields: spec
+                .fields
+                .iter()
+                .map(|f| SchemaField {
+                    name: f.name.clone(),
+                    r#type: f.r#type,
+                    description: f.description.clone(),
+                })
+                .collect(),
+            primary_key: spec.primary_key,
+            foreign_keys: spec
+                .foreign_keys
+                .iter()
+                .map(|(field, resource, reference)| ForeignKey {
+                    fields: field,
+                    reference: ForeignKeyReference {
+                        resource,
+                        fields: reference,
+                    },
+                })
+                .collect(),
+        },
+    })
+}
+
+fn serialize_rows<T: Serialize>(rows: &[T]) -> Result<Vec<u8>> {
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(Vec::new());
+
+    for row in rows {
+        writer
+            .serialize(row)
+            .map_err(|e| StorageError::Export(format!("Failed to write CSV row: {}", e)))?;
+    }
+
+    writer
+        .into_inner()
+        .map_err(|e| StorageError::Export(format!("Failed to flush CSV rows: {}", e)))
+}
+
+fn zip_entry(path: String) -> ZipEntryBuilder {
+    ZipEntryBuilder::new(path.into(), Compression::Deflate)
+        .unix_permissions(0o644)
+        .last_modification_date(ZipDateTime::from_chrono(&Utc::now()))
+}
+
+fn zip_error(error: async_zip::error::ZipError) -> StorageError {
+    StorageError::Export(format!("Failed to write archive: {}", error))
+}
+
+/// Data package descriptor written as `datapackage.json`
+#[derive(Debug, Clone, Serialize)]
+pub struct DataPackage {
+    pub profile: &'static str,
+    pub name: &'static str,
+    pub title: &'static str,
+    pub version: &'static str,
+    pub created: String,
+    pub resources: Vec<DataResource>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DataResource {
+    pub name: &'static str,
+    pub path: String,
+    pub profile: &'static str,
+    pub format: &'static str,
+    pub mediatype: &'static str,
+    pub encoding: &'static str,
+    pub bytes: u64,
+    pub rows: u64,
+    pub hash: String,
+    pub schema: TableSchema,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TableSchema {
+    pub fields: Vec<SchemaField>,
+    #[serde(rename = "primaryKey")]
+    pub primary_key: &'static str,
+    #[serde(rename = "foreignKeys", skip_serializing_if = "Vec::is_empty")]
+    pub foreign_keys: Vec<ForeignKey>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaField {
+    pub name: Cow<'static, str>,
+    #[serde(rename = "type")]
+    pub r#type: &'static str,
+    pub description: Cow<'static, str>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ForeignKey {
+    pub fields: &'static str,
+    pub reference: ForeignKeyReference,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ForeignKeyReference {
+    pub resource: &'static str,
+    pub fields: &'static str,
+}
+
+#[derive(Clone)]
+struct FieldSpec {
+    name: Cow<'static, str>,
+    r#type: &'static str,
+    description: Cow<'static, str>,
+}
+
+struct ResourceSpec {
+    name: &'static str,
+    fields: Cow<'static, [FieldSpec]>,
+    primary_key: &'static str,
+    /// (field, referenced resource, referenced field)
+    foreign_keys: &'static [(&'static str, &'static str, &'static str)],
+}
+
+macro_rules! field {
+    ($name:literal, $type:literal, $description:literal) => {
+        FieldSpec {
+            name: Cow::Borrowed($name),
+            r#type: $type,
+            description: Cow::Borrowed($description),
+        }
+    };
+}
+
+/// Fields of `results.csv` before the attempt columns of the movements
+static RESULT_FIELDS: &[FieldSpec] = &[
+    field!(
+        "participant_id",
+        "string",
+        "Unique participation identifier"
+    ),
+    field!("competition_id", "string", "Competition identifier"),
+    field!("competition_slug", "string", "Competition slug"),
+    field!("competition_date", "date", "First day of the competition"),
+    field!("federation", "string", "Sanctioning federation name"),
+    field!("category", "string", "Category the athlete competed in"),
+    field!("athlete_id", "string", "Athlete identifier"),
+    field!("athlete_slug", "string", "Athlete slug"),
+    field!("first_name", "string", "Athlete first name"),
+    field!("last_name", "string", "Athlete last name"),
+    field!("gender", "string", "M, F or MX"),
+    field!("country", "string", "Athlete country"),
+    field!("bodyweight", "number", "Bodyweight at weigh-in in kg"),
+    field!(
+        "is_disqualified",
+        "boolean",
+        "Whether the athlete was disqualified"
+    ),
+    field!(
+        "disqualified_reason",
+        "string",
+        "Reason given for the disqualification"
+    ),
+];
+
+/// Fields of `results.csv` after the attempt columns of the movements
+static RESULT_SCORE_FIELDS: &[FieldSpec] = &[
+    field!(
+        "total",
+        "number",
+        "Sum of the best successful attempts in kg"
+    ),
+    field!(
+        "ris_score",
+        "number",
+        "RIS score computed for this performance"
+    ),
+];
+
+/// `results.csv` holds one row per participation, with the three regular attempts
+/// and the best lift of each movement of the `movements` table in its own columns
+fn results_spec(movements: &[MovementColumns]) -> ResourceSpec {
+    let mut fields = RESULT_FIELDS.to_vec();
+    for movement in movements {
+        for (attempt_number, ordinal) in [(1, "First"), (2, "Second"), (3, "Third")] {
+            fields.push(FieldSpec {
+                name: format!("{}_{}", movement.key, attempt_number).into(),
+                r#type: "number",
+                description: format!(
+                    "{} {} attempt in kg, negative when missed",
+                    ordinal, movement.name
+                )
+                .into(),
+            });
+        }
+        fields.push(FieldSpec {
+            name: format!("{}_best", movement.key).into(),
+            r#type: "number",
+            description: format!("Best successful {} attempt in kg", movement.name).into(),
+        });
+    }
+    fields.extend_from_slice(RESULT_SCORE_FIELDS);
+
+    ResourceSpec {
+        name: "results",
+        fields: fields.into(),
+        primary_key: "participant_id",
+        foreign_keys: &[
+            ("competition_id", "competitions", "competition_id"),
+            ("athlete_id", "athletes", "athlete_id"),
+        ],
+    }
+}
+
+static ATTEMPTS: ResourceSpec = ResourceSpec {
+    name: "attempts",
+    fields: Cow::Borrowed(&[
+        field!("attempt_id", "string", "Unique attempt identifier"),
+        field!(
+            "participant_id",
+            "string",
+            "Participation the attempt belongs to"
+        ),
+        field!("movement", "string", "Movement name"),
+        field!(
+            "attempt_number",
+            "integer",
+            "1 to 3, or 4 for a record attempt"
+        ),
+        field!(
+            "kind",
+            "string",
+            "regular, or record for a fourth attempt left out of the total"
+        ),
+        field!("weight", "number", "Attempted weight in kg"),
+        field!(
+            "is_successful",
+            "boolean",
+            "Whether the attempt was validated"
+        ),
+    ]),
+    primary_key: "attempt_id",
+    foreign_keys: &[("participant_id", "results", "participant_id")],
+};
+
+static ATHLETES: ResourceSpec = ResourceSpec {
+    name: "athletes",
+    fields: Cow::Borrowed(&[
+        field!("athlete_id", "string", "Athlete identifier"),
+        field!("slug", "string", "Athlete slug"),
+        field!("first_name", "string", "First name"),
+        field!("last_name", "string", "Last name"),
+        field!("gender", "string", "M, F or MX"),
+        field!("country", "string", "Country the athlete represents"),
+        field!("nationality", "string", "Nationality"),
+    ]),
+    primary_key: "athlete_id",
+    foreign_keys: &[],
+};
+
+static COMPETITIONS: ResourceSpec = ResourceSpec {
+    name: "competitions",
+    fields: Cow::Borrowed(&[
+        field!("competition_id", "string", "Competition identifier"),
+        field!("slug", "string", "Competition slug"),
+        field!("name", "string", "Competition name"),
+        field!(
+            "status",
+            "string",
+            "draft, upcoming, live, completed or cancelled"
+        ),
+        field!(
+            "federation_id",
+            "string",
+            "Sanctioning federation identifier"
+        ),
+        field!("venue", "string", "Venue name"),
+        field!("city", "string", "City"),
+        field!("country", "string", "Country"),
+        field!("start_date", "date", "First day of the competition"),
+        field!("end_date", "date", "Last day of the competition"),
+        field!("number_of_judges", "integer", "Number of judges, 1 or 3"),
+    ]),
+    primary_key: "competition_id",
+    foreign_keys: &[("federation_id", "federations", "federation_id")],
+};
+
+static FEDERATIONS: ResourceSpec = ResourceSpec {
+    name: "federations",
+    fields: Cow::Borrowed(&[
+        field!("federation_id", "string", "Federation identifier"),
+        field!("name", "string", "Federation name"),
+        field!("abbreviation", "string", "Federation abbreviation"),
+        field!("country", "string", "Federation country"),
+    ]),
+    primary_key: "federation_id",
+    foreign_keys: &[],
+};
+
+/// A movement of the `movements` table, with the prefix of its columns in
+/// `results.csv`: its name lowercased without separators (`Muscle-up` gives `muscleup`)
+struct MovementColumns {
+    name: String,
+    key: String,
+}
+
+impl MovementColumns {
+    fn new(name: String) -> Self {
+        let key = name
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+        Self { name, key }
+    }
+}
+
+/// A regular attempt of a participation, its weight negative when missed
+#[derive(Deserialize)]
+struct SignedAttempt {
+    movement: String,
+    attempt_number: i16,
+    weight: Decimal,
+}
+
+#[derive(Deserialize)]
+struct LiftBest {
+    movement: String,
+    best: Decimal,
+}
+
+struct ResultRow {
+    participant_id: Uuid,
+    competition_id: Uuid,
+    competition_slug: String,
+    competition_date: NaiveDate,
+    federation: String,
+    category: String,
+    athlete_id: Uuid,
+    athlete_slug: String,
+    first_name: String,
+    last_name: String,
+    gender: String,
+    country: String,
+    bodyweight: Option<Decimal>,
+    is_disqualified: bool,
+    disqualified_reason: Option<String>,
+    attempts: Json<Vec<SignedAttempt>>,
+    bests: Json<Vec<LiftBest>>,
+    total: Decimal,
+    ris_score: Option<Decimal>,
+}
+
+impl ResultRow {
+    /// The CSV record of the row, in the order of [`results_spec`]
+    fn into_record(self, movements: &[MovementColumns]) -> Vec<String> {
+        let mut record = vec![
+            self.participant_id.to_string(),
+            self.competition_id.to_string(),
+            self.competition_slug,
+            self.competition_date.to_string(),
+            self.federation,
+            self.category,
+            self.athlete_id.to_string(),
+            self.athlete_slug,
+            self.first_name,
+            self.last_name,
+            self.gender,
+            self.country,
+            cell(self.bodyweight),
+            self.is_disqualified.to_string(),
+            self.disqualified_reason.unwrap_or_default(),
+        ];
+
+        for movement in movements {
+            for attempt_number in 1..=3 {
+                let weight = self
+                    .attempts
+                    .iter()
+                    .find(|a| a.movement == movement.name && a.attempt_number == attempt_number)
+                    .map(|a| a.weight);
+                record.push(cell(weight));
+            }
+            let best = self
+                .bests
+                .iter()
+                .find(|l| l.movement == movement.name)
+                .map(|l| l.best);
+            record.push(cell(best));
+        }
+
+        record.push(self.total.to_string());
+        record.push(cell(self.ris_score));
+        record
+    }
+}
+
+fn cell(value: Option<Decimal>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+#[derive(Serialize)]
+struct AttemptRow {
+    attempt_id: Uuid,
+    participant_id: Uuid,
+    movement: String,
+    attempt_number: i16,
+    kind: String,
+    weight: Decimal,
+    is_successful: bool,
+}
+
+#[derive(Serialize)]
+struct AthleteRow {
+    athlete_id: Uuid,
+    slug: String,
+    first_name: String,
+    last_name: String,
+    gender: String,
+    country: String,
+    nationality: Option<String>,
+}
+
+#[derive(Serialize)]
+struct CompetitionRow {
+    competition_id: Uuid,
+    slug: String,
+    name: String,
+    status: String,
+    federation_id: Uuid,
+    venue: Option<String>,
+    city: Option<String>,
+    country: Option<String>,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    number_of_judges: Option<i16>,
+}
+
+#[derive(Serialize)]
+struct FederationRow {
+    federation_id: Uuid,
+    name: String,
+    abbreviation: Option<String>,
+    country: Option<String>,
+}
+
+async fn fetch_movements(conn: &mut PgConnection) -> Result<Vec<MovementColumns>> {
+    let names = sqlx::query_scalar!("SELECT name FROM movements ORDER BY display_order, name")
+        .fetch_all(conn)
+        .await?;
+
+    Ok(names.into_iter().map(MovementColumns::new).collect())
+}
+
+fn fetch_results(
+    conn: &mut PgConnection,
+) -> impl Stream<Item = std::result::Result<ResultRow, sqlx::Error>> + Unpin + '_ {
+    sqlx::query_as!(
+        ResultRow,
+        r#"
+        SELECT
+            cp.participant_id,
+            c.competition_id,
+            c.slug as competition_slug,
+            c.start_date as competition_date,
+            f.name as federation,
+            cat.name as category,
+            a.athlete_id,
+            a.slug as athlete_slug,
+            a.first_name,
+            a.last_name,
+            a.gender,
+            a.country,
+            cp.bodyweight,
+            cp.is_disqualified,
+            cp.disqualified_reason,
+            (
+                SELECT COALESCE(jsonb_agg(jsonb_build_object(
+                    'movement', l.movement_name,
+                    'attempt_number', at.attempt_number,
+                    'weight', (CASE WHEN at.is_successful THEN at.weight ELSE -at.weight END)::text
+                )), '[]'::jsonb)
+                FROM lifts l
+                JOIN attempts at ON at.lift_id = l.lift_id
+                WHERE l.participant_id = cp.participant_id AND at.kind = 'regular'
+            ) as "attempts!: Json<Vec<SignedAttempt>>",
+            (
+                SELECT COALESCE(jsonb_agg(jsonb_build_object(
+                    'movement', l.movement_name,
+                    'best', l.max_weight::text
+                )), '[]'::jsonb)
+                FROM lifts l
+                WHERE l.participant_id = cp.participant_id
+            ) as "bests!: Json<Vec<LiftBest>>",
+            (SELECT COALESCE(SUM(tl.max_weight), 0) FROM lifts tl WHERE tl.participant_id = cp.participant_id) as "total!: Decimal",
+            cp.ris_score
+        FROM competition_participants cp
+        JOIN competitions c ON c.competition_id = cp.competition_id
+        JOIN federations f ON f.federation_id = c.federation_id
+        JOIN categories cat ON cat.category_id = cp.category_id
+        JOIN athletes a ON a.athlete_id = cp.athlete_id
+        ORDER BY c.start_date, c.slug, cat.name, a.last_name, a.first_name
+        "#
+    )
+    .fetch(conn)
+}
+
+fn fetch_attempts(
+    conn: &mut PgConnection,
+) -> impl Stream<Item = std::result::Result<AttemptRow, sqlx::Error>> + Unpin + '_ {
+    sqlx::query_as!(
+        AttemptRow,
+        r#"
+        SELECT
+            at.attempt_id,
+            cp.participant_id,
+            l.movement_name as movement,
+            at.attempt_number,
+            at.kind,
+            at.weight,
+            at.is_successful
+        FROM attempts at
+        JOIN lifts l ON l.lift_id = at.lift_id
+        JOIN competition_participants cp ON cp.participant_id = l.participant_id
+        JOIN competitions c ON c.competition_id = cp.competition_id
+        LEFT JOIN competition_movements cm
+            ON cm.competition_id = c.competition_id AND cm.movement_name = l.movement_name
+        ORDER BY c.start_date, c.slug, cp.participant_id, cm.display_order NULLS LAST,
+                 l.movement_name, at.attempt_number
+        "#
+    )
+    .fetch(conn)
+}
+
+fn fetch_athletes(
+    conn: &mut PgConnection,
+) -> impl Stream<Item = std::result::Result<AthleteRow, sqlx::Error>> + Unpin + '_ {
+    sqlx::query_as!(
+        AthleteRow,
+        r#"
+        SELECT athlete_id, slug, first_name, last_name, gender, country, nationality
+        FROM athletes
+        ORDER BY last_name, first_name, slug
+        "#
+    )
+    .fetch(conn)
+}
+
+fn fetch_competitions(
+    conn: &mut PgConnection,
+) -> impl Stream<Item = std::result::Result<CompetitionRow, sqlx::Error>> + Unpin + '_ {
+    sqlx::query_as!(
+        CompetitionRow,
+        r#"
+        SELECT competition_id, slug, name, status, federation_id, venue, city, country,
+               start_date, end_date, number_of_judge as number_of_judges
+        FROM competitions
+        ORDER BY start_date, slug
+        "#
+    )
+    .fetch(conn)
+}
+
+fn fetch_federations(
+    conn: &mut PgConnection,
+) -> impl Stream<Item = std::result::Result<FederationRow, sqlx::Error>> + Unpin + '_ {
+    sqlx::query_as!(
+        FederationRow,
+        r#"
+        SELECT federation_id, name, abbreviation, country
+        FROM federations
+        ORDER BY name
+        "#
+    )
+    .fetch(conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_movement_column_keys() {
+        let keys = ["Muscle-up", "Pull-up", "Dips", "Squat"]
+            .map(|name| MovementColumns::new(name.to_string()).key);
+
+        assert_eq!(keys, ["muscleup", "pullup", "dips", "squat"]);
+    }
+
+    #[test]
+    fn test_result_record_matches_header() {
+        let movements = ["Muscle-up", "Squat"].map(|name| MovementColumns::new(name.to_string()));
+        let attempt = |movement: &str, attempt_number, weight| SignedAttempt {
+            movement: movement.to_string(),
+            attempt_number,
+            weight: Decimal::from(weight),
+        };
+        let row = ResultRow {
+            participant_id: Uuid::nil(),
+            competition_id: Uuid::nil(),
+            competition_slug: String::new(),
+            competition_date: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+            federation: String::new(),
+            category: String::new(),
+            athlete_id: Uuid::nil(),
+            athlete_slug: String::new(),
+            first_name: String::new(),
+            last_name: String::new(),
+            gender: String::new(),
+            country: String::new(),
+            bodyweight: None,
+            is_disqualified: false,
+            disqualified_reason: None,
+            attempts: Json(vec![
+                attempt("Squat", 1, 140),
+                attempt("Squat", 2, -150),
+                attempt("Squat", 3, 150),
+            ]),
+            bests: Json(vec![LiftBest {
+                movement: "Squat".to_string(),
+                best: Decimal::from(150),
+            }]),
+            total: Decimal::from(150),
+            ris_score: None,
+        };
+
+        let spec = results_spec(&movements);
+        let record = row.into_record(&movements);
+        assert_eq!(record.len(), spec.fields.len());
+
+        let value = |name: &str| {
+            let index = spec.fields.iter().position(|f| f.name == name).unwrap();
+            record[index].as_str()
+        };
+        assert_eq!(value("muscleup_1"), "");
+        assert_eq!(value("muscleup_best"), "");
+        assert_eq!(value("squat_2"), "-150");
+        assert_eq!(value("squat_best"), "150");
+        assert_eq!(value("total"), "150");
+    }
+
+    #[test]
+    fn test_attempt_header_matches_row_fields() {
+        let row = AttemptRow {
+            attempt_id: Uuid::nil(),
+            participant_id: Uuid::nil(),
+            movement: String::new(),
+            attempt_number: 1,
+            kind: String::new(),
+            weight: Decimal::ZERO,
+            is_successful: true,
+        };
+
+        assert_eq!(csv_header(&row), spec_header(&ATTEMPTS));
+    }
+
+    fn csv_header<T: Serialize>(row: &T) -> String {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer.serialize(row).unwrap();
+        let csv = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+        csv.lines().next().unwrap().to_string()
+    }
+
+    fn spec_header(spec: &ResourceSpec) -> String {
+        spec.fields
+            .iter()
+            .map(|f| f.name.as_ref())
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    #[test]
+    fn test_bundle_filename_is_versioned() {
+        let date = NaiveDate::from_ymd_opt(2025, 11, 2).unwrap();
+        assert_eq!(
+            bundle_filename(date),
+            format!(
+                "openstreetlifting-export-v{}-2025-11-02.zip",
+                EXPORT_FORMAT_VERSION
+            )
+        );
+    }
+}
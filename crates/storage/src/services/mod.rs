@@ -1 +1,4 @@
+pub mod data_migrations;
+pub mod export;
+pub mod placings;
 pub mod ris_computation;
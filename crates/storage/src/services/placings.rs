@@ -0,0 +1,431 @@
+//! Official placings of a competition. They are computed once, when the
+//! competition is completed, with the tie-break rules of its federation, and
+//! stored in `competition_participants.rank`.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use rust_decimal::Decimal;
+use sqlx::PgConnection;
+use sqlx::types::Json;
+use uuid::Uuid;
+
+use crate::error::{Result, StorageError};
+use crate::models::TieBreak;
+
+/// Status of a competition whose results are final
+pub const COMPLETED: &str = "completed";
+
+/// An attempt, as far as placings are concerned
+#[derive(Debug, Clone)]
+pub struct ContenderAttempt {
+    /// Position of the movement in the order of the competition
+    pub movement: usize,
+    pub attempt_number: i16,
+    pub weight: Decimal,
+    pub is_successful: bool,
+}
+
+/// A participant, as far as placings are concerned
+#[derive(Debug, Clone)]
+pub struct Contender {
+    pub participant_id: Uuid,
+    pub category_id: Uuid,
+    pub bodyweight: Option<Decimal>,
+    pub lot_number: Option<i32>,
+    pub is_disqualified: bool,
+    pub attempts: Vec<ContenderAttempt>,
+}
+
+struct Standing {
+    participant_id: Uuid,
+    total: Decimal,
+    bodyweight: Option<Decimal>,
+    lot_number: Option<i32>,
+    /// Movement, attempt number and weight of the attempt completing the total
+    achievement: (usize, i16, Decimal),
+}
+
+impl Contender {
+    fn best_weights(&self) -> HashMap<usize, Decimal> {
+        let mut best = HashMap::new();
+        for attempt in self.attempts.iter().filter(|a| a.is_successful) {
+            let weight = best.entry(attempt.movement).or_insert(attempt.weight);
+            *weight = (*weight).max(attempt.weight);
+        }
+        best
+    }
+
+    /// None for the disqualified and the bombed out, who have no total or miss
+    /// one of the required movements
+    fn standing(&self, required: &[usize]) -> Option<Standing> {
+        if self.is_disqualified {
+            return None;
+        }
+
+        let best = self.best_weights();
+        let total: Decimal = best.values().copied().sum();
+        if total.is_zero() || required.iter().any(|movement| !best.contains_key(movement)) {
+            return None;
+        }
+
+        let last_movement = best.keys().copied().max()?;
+        let achievement = self
+            .attempts
+            .iter()
+            .filter(|a| a.is_successful && a.movement == last_movement)
+            .filter(|a| a.weight == best[&last_movement])
+            .map(|a| (a.movement, a.attempt_number, a.weight))
+            .min()?;
+
+        Some(Standing {
+            participant_id: self.participant_id,
+            total,
+            bodyweight: self.bodyweight,
+            lot_number: self.lot_number,
+            achievement,
+        })
+    }
+}
+
+/// Known values first, in ascending order
+fn known_first<T: Ord>(a: Option<T>, b: Option<T>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+fn compare(a: &Standing, b: &Standing, tie_breaks: &[TieBreak]) -> Ordering {
+    tie_breaks
+        .iter()
+        .fold(b.total.cmp(&a.total), |ordering, tie_break| {
+            ordering.then_with(|| match tie_break {
+                TieBreak::Bodyweight => known_first(a.bodyweight, b.bodyweight),
+                TieBreak::EarlierAchievement => a.achievement.cmp(&b.achievement),
+                TieBreak::LotNumber => known_first(a.lot_number, b.lot_number),
+            })
+        })
+}
+
+/// Placing of each placed participant within their category: highest total
+/// first, then by the tie-break rules in order. Athletes the rules cannot tell
+/// apart share a placing, and the next one is skipped.
+pub fn compute_placings(
+    contenders: &[Contender],
+    required: &[usize],
+    tie_breaks: &[TieBreak],
+) -> HashMap<Uuid, i32> {
+    let mut categories: HashMap<Uuid, Vec<Standing>> = HashMap::new();
+    for contender in contenders {
+        if let Some(standing) = contender.standing(required) {
+            categories
+                .entry(contender.category_id)
+                .or_default()
+                .push(standing);
+        }
+    }
+
+    let mut placings = HashMap::new();
+    for mut standings in categories.into_values() {
+        standings.sort_by(|a, b| compare(a, b, tie_breaks));
+
+        let mut placing = 0;
+        for (index, standing) in standings.iter().enumerate() {
+            if index == 0 || compare(&standings[index - 1], standing, tie_breaks).is_ne() {
+                placing = index as i32 + 1;
+            }
+            placings.insert(standing.participant_id, placing);
+        }
+    }
+
+    placings
+}
+
+/// Stores the placings of a competition when it is completed, and clears them
+/// otherwise. Returns the number of participants placed.
+pub async fn update_placings(conn: &mut PgConnection, competition_id: Uuid) -> Result<usize> {
+    let competition = sqlx::query!(
+        r#"
+        SELECT c.status, f.tie_breaks as "tie_breaks: Json<Vec<TieBreak>>"
+        FROM competitions c
+        JOIN federations f ON f.federation_id = c.federation_id
+        WHERE c.competition_id = $1
+        "#,
+        competition_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(StorageError::NotFound)?;
+
+    sqlx::query!(
+        "UPDATE competition_participants SET rank = NULL WHERE competition_id = $1",
+        competition_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    if competition.status != COMPLETED {
+        return Ok(0);
+    }
+
+    let movements = sqlx::query!(
+        r#"
+        SELECT movement_name, is_required
+        FROM competition_movements
+        WHERE competition_id = $1
+        ORDER BY display_order NULLS LAST, movement_name
+        "#,
+        competition_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    // Movements outside the competition program, if any, come after it, each
+    // with its own index
+    let mut movement_indexes: HashMap<String, usize> = movements
+        .iter()
+        .enumerate()
+        .map(|(index, m)| (m.movement_name.clone(), index))
+        .collect();
+    let required: Vec<usize> = movements
+        .iter()
+        .enumerate()
+        .filter(|(_, m)| m.is_required)
+        .map(|(index, _)| index)
+        .collect();
+
+    let mut contenders: HashMap<Uuid, Contender> = sqlx::query!(
+        r#"
+        SELECT participant_id, category_id, bodyweight, lot_number, is_disqualified
+        FROM competition_participants
+        WHERE competition_id = $1
+        "#,
+        competition_id
+    )
+    .fetch_all(&mut *conn)
+    .await?
+    .into_iter()
+    .map(|p| {
+        (
+            p.participant_id,
+            Contender {
+                participant_id: p.participant_id,
+                category_id: p.category_id,
+                bodyweight: p.bodyweight,
+                lot_number: p.lot_number,
+                is_disqualified: p.is_disqualified,
+                attempts: Vec::new(),
+            },
+        )
+    })
+    .collect();
+
+    let attempts = sqlx::query!(
+        r#"
+        SELECT l.participant_id, l.movement_name, a.attempt_number, a.weight, a.is_successful
+        FROM attempts a
+        JOIN lifts l ON l.lift_id = a.lift_id
+        JOIN competition_participants cp ON cp.participant_id = l.participant_id
+        WHERE cp.competition_id = $1 AND a.kind = 'regular'
+        ORDER BY l.movement_name
+        "#,
+        competition_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for attempt in attempts {
+        let next_index = movement_indexes.len();
+        let movement = *movement_indexes
+            .entry(attempt.movement_name)
+            .or_insert(next_index);
+
+        if let Some(contender) = contenders.get_mut(&attempt.participant_id) {
+            contender.attempts.push(ContenderAttempt {
+                movement,
+                attempt_number: attempt.attempt_number,
+                weight: attempt.weight,
+                is_successful: attempt.is_successful,
+            });
+        }
+    }
+
+    let contenders: Vec<Contender> = contenders.into_values().collect();
+    let placings = compute_placings(&contenders, &required, &competition.tie_breaks);
+    let (participant_ids, ranks): (Vec<Uuid>, Vec<i32>) = placings.into_iter().unzip();
+
+    sqlx::query!(
+        r#"
+        UPDATE competition_participants cp
+        SET rank = p.rank
+        FROM UNNEST($2::uuid[], $3::int[]) AS p(participant_id, rank)
+        WHERE cp.competition_id = $1 AND cp.participant_id = p.participant_id
+        "#,
+        competition_id,
+        &participant_ids,
+        &ranks
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(participant_ids.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SQUAT: usize = 0;
+    const DIPS: usize = 1;
+
+    fn kg(weight: i64) -> Decimal {
+        Decimal::from(weight)
+    }
+
+    fn contender(
+        category: u128,
+        bodyweight: Option<Decimal>,
+        lot_number: Option<i32>,
+        attempts: &[(usize, i16, Decimal, bool)],
+    ) -> Contender {
+        Contender {
+            participant_id: Uuid::new_v4(),
+            category_id: Uuid::from_u128(category),
+            bodyweight,
+            lot_number,
+            is_disqualified: false,
+            attempts: attempts
+                .iter()
+                .map(
+                    |&(movement, attempt_number, weight, is_successful)| ContenderAttempt {
+                        movement,
+                        attempt_number,
+                        weight,
+                        is_successful,
+                    },
+                )
+                .collect(),
+        }
+    }
+
+    fn placings_of(contenders: &[Contender], tie_breaks: &[TieBreak]) -> Vec<Option<i32>> {
+        let placings = compute_placings(contenders, &[SQUAT, DIPS], tie_breaks);
+        contenders
+            .iter()
+            .map(|c| placings.get(&c.participant_id).copied())
+            .collect()
+    }
+
+    #[test]
+    fn test_highest_total_first_within_each_category() {
+        let contenders = [
+            contender(
+                1,
+                None,
+                None,
+                &[(SQUAT, 1, kg(100), true), (DIPS, 1, kg(50), true)],
+            ),
+            contender(
+                1,
+                None,
+                None,
+                &[(SQUAT, 1, kg(120), true), (DIPS, 1, kg(50), true)],
+            ),
+            contender(
+                2,
+                None,
+                None,
+                &[(SQUAT, 1, kg(90), true), (DIPS, 1, kg(40), true)],
+            ),
+        ];
+
+        assert_eq!(
+            placings_of(&contenders, &TieBreak::DEFAULT),
+            [Some(2), Some(1), Some(1)]
+        );
+    }
+
+    #[test]
+    fn test_disqualified_and_bombed_out_are_not_placed() {
+        let mut disqualified = contender(
+            1,
+            None,
+            None,
+            &[(SQUAT, 1, kg(200), true), (DIPS, 1, kg(80), true)],
+        );
+        disqualified.is_disqualified = true;
+
+        let contenders = [
+            disqualified,
+            contender(
+                1,
+                None,
+                None,
+                &[(SQUAT, 1, kg(200), true), (DIPS, 1, kg(80), false)],
+            ),
+            contender(1, None, None, &[(SQUAT, 1, kg(200), false)]),
+            contender(
+                1,
+                None,
+                None,
+                &[(SQUAT, 1, kg(100), true), (DIPS, 1, kg(50), true)],
+            ),
+        ];
+
+        assert_eq!(
+            placings_of(&contenders, &TieBreak::DEFAULT),
+            [None, None, None, Some(1)]
+        );
+    }
+
+    #[test]
+    fn test_tie_breaks_in_federation_order() {
+        let lifts = [(SQUAT, 1, kg(100), true), (DIPS, 2, kg(50), true)];
+        let earlier = [(SQUAT, 1, kg(100), true), (DIPS, 1, kg(50), true)];
+        let contenders = [
+            contender(1, Some(kg(80)), Some(1), &lifts),
+            contender(1, Some(kg(75)), Some(4), &lifts),
+            contender(1, Some(kg(80)), Some(3), &earlier),
+            contender(1, None, Some(2), &earlier),
+        ];
+
+        assert_eq!(
+            placings_of(&contenders, &TieBreak::DEFAULT),
+            [Some(3), Some(1), Some(2), Some(4)]
+        );
+        assert_eq!(
+            placings_of(
+                &contenders,
+                &[TieBreak::EarlierAchievement, TieBreak::LotNumber]
+            ),
+            [Some(3), Some(4), Some(2), Some(1)]
+        );
+        assert_eq!(
+            placings_of(&contenders, &[TieBreak::LotNumber]),
+            [Some(1), Some(4), Some(3), Some(2)]
+        );
+    }
+
+    #[test]
+    fn test_athletes_still_tied_share_a_placing() {
+        let lifts = [(SQUAT, 1, kg(100), true), (DIPS, 1, kg(50), true)];
+        let contenders = [
+            contender(1, Some(kg(80)), None, &lifts),
+            contender(1, Some(kg(80)), None, &lifts),
+            contender(
+                1,
+                Some(kg(80)),
+                None,
+                &[(SQUAT, 1, kg(90), true), (DIPS, 1, kg(50), true)],
+            ),
+        ];
+
+        assert_eq!(
+            placings_of(&contenders, &TieBreak::DEFAULT),
+            [Some(1), Some(1), Some(3)]
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use actix_web::{HttpResponse, error::ResponseError, http::StatusCode};
+use importer::ImporterError;
 use serde_json::json;
 use std::fmt;
 use storage::error::StorageError;
@@ -99,6 +100,16 @@ impl From<StorageError> for WebError {
     }
 }
 
+impl From<ImporterError> for WebError {
+    fn from(error: ImporterError) -> Self {
+        match error {
+            ImporterError::StorageError(e) => Self::Storage(e),
+            ImporterError::DatabaseError(e) => Self::Storage(e.into()),
+            e => Self::InternalServerError(e.to_string()),
+        }
+    }
+}
+
 impl From<ValidationErrors> for WebError {
     fn from(error: ValidationErrors) -> Self {
         Self::Validation(error)
@@ -2,13 +2,17 @@ use actix_web::{HttpResponse, web};
 use storage::{
     Database,
     dto::athlete::{
-        AthleteDetailResponse, AthleteResponse, CreateAthleteRequest, UpdateAthleteRequest,
+        AthleteDetailResponse, AthleteResponse, AthleteSearchParams, CreateAliasRequest,
+        CreateAthleteRequest, MergeAthleteRequest, UpdateAthleteRequest,
     },
-    repository::athlete::AthleteRepository,
+    dto::external_id::LinkExternalIdRequest,
+    models::{AthleteAlias, ExternalId},
+    repository::{athlete::AthleteRepository, external_id::ExternalIdRepository},
 };
+use uuid::Uuid;
 use validator::Validate;
 
-use crate::error::WebResult;
+use crate::error::{WebError, WebResult};
 
 #[utoipa::path(
     get,
@@ -95,6 +99,8 @@ pub async fn create_athlete(
 
     req.validate()?;
 
+    req.birth().map_err(WebError::BadRequest)?;
+
     let repo = AthleteRepository::new(db.pool());
     let athlete = repo.create(&req).await?;
 
@@ -129,6 +135,8 @@ pub async fn update_athlete(
 
     update_req.validate()?;
 
+    update_req.birth().map_err(WebError::BadRequest)?;
+
     let repo = AthleteRepository::new(db.pool());
 
     let existing = repo.find_by_slug(&slug).await?;
@@ -167,3 +175,253 @@ pub async fn delete_athlete(
 
     Ok(HttpResponse::NoContent().finish())
 }
+
+#[utoipa::path(
+    get,
+    path = "/api/athletes/search",
+    params(AthleteSearchParams),
+    responses(
+        (status = 200, description = "Athletes whose name or an alias contains the query, at most 50", body = Vec<AthleteResponse>),
+        (status = 400, description = "Validation error")
+    ),
+    tag = "athletes"
+)]
+pub async fn search_athletes(
+    db: web::Data<Database>,
+    query: web::Query<AthleteSearchParams>,
+) -> WebResult<HttpResponse> {
+    let params = query.into_inner();
+    params.validate()?;
+
+    let athletes = AthleteRepository::new(db.pool()).search(&params.q).await?;
+    let response: Vec<AthleteResponse> = athletes.into_iter().map(AthleteResponse::from).collect();
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/athletes/{slug}/aliases",
+    params(
+        ("slug" = String, Path, description = "Athlete slug")
+    ),
+    responses(
+        (status = 200, description = "Other spellings of the athlete's name", body = Vec<AthleteAlias>),
+        (status = 404, description = "Athlete not found")
+    ),
+    tag = "athletes"
+)]
+pub async fn list_athlete_aliases(
+    db: web::Data<Database>,
+    path: web::Path<String>,
+) -> WebResult<HttpResponse> {
+    let slug = path.into_inner();
+    let repo = AthleteRepository::new(db.pool());
+    let athlete = repo.find_by_slug(&slug).await?;
+    let aliases = repo.list_aliases(athlete.athlete_id).await?;
+
+    Ok(HttpResponse::Ok().json(aliases))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/athletes/{slug}/aliases",
+    params(
+        ("slug" = String, Path, description = "Athlete slug")
+    ),
+    request_body = CreateAliasRequest,
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 201, description = "Alias added, matched like the name by later imports", body = AthleteAlias),
+        (status = 400, description = "Validation error"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Athlete not found"),
+        (status = 409, description = "The athlete already has this name or alias")
+    ),
+    tag = "athletes"
+)]
+pub async fn add_athlete_alias(
+    db: web::Data<Database>,
+    path: web::Path<String>,
+    payload: web::Json<CreateAliasRequest>,
+) -> WebResult<HttpResponse> {
+    let slug = path.into_inner();
+    let req = payload.into_inner();
+    req.validate()?;
+
+    let repo = AthleteRepository::new(db.pool());
+    let athlete = repo.find_by_slug(&slug).await?;
+    let alias = repo.add_alias(&athlete, &req).await?;
+
+    Ok(HttpResponse::Created().json(alias))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/athletes/{slug}/aliases/{alias_id}",
+    params(
+        ("slug" = String, Path, description = "Athlete slug"),
+        ("alias_id" = Uuid, Path, description = "Alias ID")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 204, description = "Alias deleted"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Athlete or alias not found")
+    ),
+    tag = "athletes"
+)]
+pub async fn delete_athlete_alias(
+    db: web::Data<Database>,
+    path: web::Path<(String, Uuid)>,
+) -> WebResult<HttpResponse> {
+    let (slug, alias_id) = path.into_inner();
+    let repo = AthleteRepository::new(db.pool());
+    let athlete = repo.find_by_slug(&slug).await?;
+    repo.delete_alias(athlete.athlete_id, alias_id).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/athletes/{slug}/merge",
+    params(
+        ("slug" = String, Path, description = "Slug of the athlete kept")
+    ),
+    request_body = MergeAthleteRequest,
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "Duplicate merged, its name kept as an alias and its slug redirecting", body = AthleteResponse),
+        (status = 400, description = "Validation error"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Athlete not found"),
+        (status = 409, description = "Same athlete, or both took part in the same competition category")
+    ),
+    tag = "athletes"
+)]
+pub async fn merge_athlete(
+    db: web::Data<Database>,
+    path: web::Path<String>,
+    payload: web::Json<MergeAthleteRequest>,
+) -> WebResult<HttpResponse> {
+    let slug = path.into_inner();
+    let req = payload.into_inner();
+    req.validate()?;
+
+    let repo = AthleteRepository::new(db.pool());
+    let athlete = repo.find_by_slug(&slug).await?;
+    let duplicate = repo.find_by_slug(&req.duplicate).await?;
+    let merged = repo.merge(&athlete, &duplicate).await?;
+    tracing::info!("Athlete {} merged into {}", duplicate.slug, merged.slug);
+
+    Ok(HttpResponse::Ok().json(AthleteResponse::from(merged)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/athletes/{slug}/external-ids",
+    params(
+        ("slug" = String, Path, description = "Athlete slug")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "Identifiers of the athlete in its sources", body = Vec<ExternalId>),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Athlete not found")
+    ),
+    tag = "athletes"
+)]
+pub async fn list_athlete_external_ids(
+    db: web::Data<Database>,
+    path: web::Path<String>,
+) -> WebResult<HttpResponse> {
+    let slug = path.into_inner();
+    let athlete = AthleteRepository::new(db.pool())
+        .find_by_slug(&slug)
+        .await?;
+    let ids = ExternalIdRepository::new(db.pool())
+        .list_for_athlete(athlete.athlete_id)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(ids))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/athletes/{slug}/external-ids",
+    params(
+        ("slug" = String, Path, description = "Athlete slug")
+    ),
+    request_body = LinkExternalIdRequest,
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 201, description = "Identifier linked, matched before names by later imports", body = ExternalId),
+        (status = 400, description = "Validation error"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Athlete not found"),
+        (status = 409, description = "Identifier already linked to an athlete")
+    ),
+    tag = "athletes"
+)]
+pub async fn link_athlete_external_id(
+    db: web::Data<Database>,
+    path: web::Path<String>,
+    payload: web::Json<LinkExternalIdRequest>,
+) -> WebResult<HttpResponse> {
+    let slug = path.into_inner();
+    let req = payload.into_inner();
+    req.validate()?;
+
+    let athlete = AthleteRepository::new(db.pool())
+        .find_by_slug(&slug)
+        .await?;
+    let id = ExternalIdRepository::new(db.pool())
+        .link_athlete(athlete.athlete_id, &req.source, &req.external_id)
+        .await?;
+
+    Ok(HttpResponse::Created().json(id))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/athletes/{slug}/external-ids/{source}/{external_id}",
+    params(
+        ("slug" = String, Path, description = "Athlete slug"),
+        ("source" = String, Path, description = "Source type, e.g. liftcontrol"),
+        ("external_id" = String, Path, description = "Identifier in the source")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 204, description = "Identifier unlinked"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Athlete or identifier not found")
+    ),
+    tag = "athletes"
+)]
+pub async fn unlink_athlete_external_id(
+    db: web::Data<Database>,
+    path: web::Path<(String, String, String)>,
+) -> WebResult<HttpResponse> {
+    let (slug, source, external_id) = path.into_inner();
+    let athlete = AthleteRepository::new(db.pool())
+        .find_by_slug(&slug)
+        .await?;
+    ExternalIdRepository::new(db.pool())
+        .unlink_athlete(athlete.athlete_id, &source, &external_id)
+        .await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
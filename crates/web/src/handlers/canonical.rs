@@ -0,0 +1,19 @@
+use actix_web::HttpResponse;
+use storage::canonical::models::{CANONICAL_FORMAT_VERSION, json_schema};
+
+use crate::error::WebResult;
+
+#[utoipa::path(
+    get,
+    path = "/api/canonical/schema",
+    responses(
+        (status = 200, description = "JSON Schema of the current canonical import format", body = Object, content_type = "application/schema+json")
+    ),
+    tag = "canonical"
+)]
+pub async fn get_canonical_schema() -> WebResult<HttpResponse> {
+    Ok(HttpResponse::Ok()
+        .content_type("application/schema+json")
+        .insert_header(("X-Canonical-Format-Version", CANONICAL_FORMAT_VERSION))
+        .json(json_schema()))
+}
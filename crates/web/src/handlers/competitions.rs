@@ -1,11 +1,14 @@
 use actix_web::{HttpResponse, web};
+use importer::canonical::dumper::CanonicalDumper;
 use storage::{
     Database,
     dto::competition::{
         CompetitionDetailResponse, CompetitionListResponse, CompetitionResponse,
         CreateCompetitionRequest, UpdateCompetitionRequest,
     },
-    repository::competition::CompetitionRepository,
+    dto::external_id::LinkExternalIdRequest,
+    models::ExternalId,
+    repository::{competition::CompetitionRepository, external_id::ExternalIdRepository},
 };
 use validator::Validate;
 
@@ -92,6 +95,30 @@ pub async fn get_competition_detailed(
     Ok(HttpResponse::Ok().json(competition))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/competitions/{slug}/canonical",
+    params(
+        ("slug" = String, Path, description = "Competition slug")
+    ),
+    responses(
+        (status = 200, description = "Competition rebuilt in the canonical import format, ready to be re-imported. Athletes include their birth year, as in the athlete API, but not their birth date", body = Object),
+        (status = 404, description = "Competition not found"),
+        (status = 409, description = "Competition has no country, which the canonical format requires")
+    ),
+    tag = "competitions"
+)]
+pub async fn get_competition_canonical(
+    db: web::Data<Database>,
+    path: web::Path<String>,
+) -> WebResult<HttpResponse> {
+    let slug = path.into_inner();
+    let dumper = CanonicalDumper::new(db.pool());
+    let canonical = dumper.dump_competition(&slug).await?;
+
+    Ok(HttpResponse::Ok().json(canonical))
+}
+
 #[utoipa::path(
     post,
     path = "/api/competitions",
@@ -164,6 +191,38 @@ pub async fn update_competition(
     Ok(HttpResponse::Ok().json(CompetitionResponse::from(updated)))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/competitions/{slug}/placings",
+    params(
+        ("slug" = String, Path, description = "Competition slug")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "Placings recomputed with the current tie-break rules of the federation", body = CompetitionDetailResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Competition not found"),
+        (status = 409, description = "Competition not completed")
+    ),
+    tag = "competitions"
+)]
+pub async fn recompute_competition_placings(
+    db: web::Data<Database>,
+    path: web::Path<String>,
+) -> WebResult<HttpResponse> {
+    let slug = path.into_inner();
+    let repo = CompetitionRepository::new(db.pool());
+    let competition = repo.find_by_slug(&slug).await?;
+
+    let placed = repo.recompute_placings(&competition).await?;
+    tracing::info!("Placings of {} recomputed ({} placed)", slug, placed);
+
+    let detail = repo.find_by_slug_detailed(&slug).await?;
+    Ok(HttpResponse::Ok().json(detail))
+}
+
 #[utoipa::path(
     delete,
     path = "/api/competitions/{slug}",
@@ -191,3 +250,105 @@ pub async fn delete_competition(
 
     Ok(HttpResponse::NoContent().finish())
 }
+
+#[utoipa::path(
+    get,
+    path = "/api/competitions/{slug}/external-ids",
+    params(
+        ("slug" = String, Path, description = "Competition slug")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "Identifiers of the competition in its sources", body = Vec<ExternalId>),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Competition not found")
+    ),
+    tag = "competitions"
+)]
+pub async fn list_competition_external_ids(
+    db: web::Data<Database>,
+    path: web::Path<String>,
+) -> WebResult<HttpResponse> {
+    let slug = path.into_inner();
+    let competition = CompetitionRepository::new(db.pool())
+        .find_by_slug(&slug)
+        .await?;
+    let ids = ExternalIdRepository::new(db.pool())
+        .list_for_competition(competition.competition_id)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(ids))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/competitions/{slug}/external-ids",
+    params(
+        ("slug" = String, Path, description = "Competition slug")
+    ),
+    request_body = LinkExternalIdRequest,
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 201, description = "Identifier linked, matched before names by later imports", body = ExternalId),
+        (status = 400, description = "Validation error"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Competition not found"),
+        (status = 409, description = "Identifier already linked to a competition")
+    ),
+    tag = "competitions"
+)]
+pub async fn link_competition_external_id(
+    db: web::Data<Database>,
+    path: web::Path<String>,
+    payload: web::Json<LinkExternalIdRequest>,
+) -> WebResult<HttpResponse> {
+    let slug = path.into_inner();
+    let req = payload.into_inner();
+    req.validate()?;
+
+    let competition = CompetitionRepository::new(db.pool())
+        .find_by_slug(&slug)
+        .await?;
+    let id = ExternalIdRepository::new(db.pool())
+        .link_competition(competition.competition_id, &req.source, &req.external_id)
+        .await?;
+
+    Ok(HttpResponse::Created().json(id))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/competitions/{slug}/external-ids/{source}/{external_id}",
+    params(
+        ("slug" = String, Path, description = "Competition slug"),
+        ("source" = String, Path, description = "Source type, e.g. liftcontrol"),
+        ("external_id" = String, Path, description = "Identifier in the source")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 204, description = "Identifier unlinked"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Competition or identifier not found")
+    ),
+    tag = "competitions"
+)]
+pub async fn unlink_competition_external_id(
+    db: web::Data<Database>,
+    path: web::Path<(String, String, String)>,
+) -> WebResult<HttpResponse> {
+    let (slug, source, external_id) = path.into_inner();
+    let competition = CompetitionRepository::new(db.pool())
+        .find_by_slug(&slug)
+        .await?;
+    ExternalIdRepository::new(db.pool())
+        .unlink_competition(competition.competition_id, &source, &external_id)
+        .await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,49 @@
+use actix_web::{HttpResponse, http::header, web};
+use futures_util::{StreamExt, future, stream};
+use std::io;
+use storage::{Database, services::export};
+use tokio_util::io::ReaderStream;
+
+use crate::error::WebResult;
+
+/// Size of the in-memory pipe between the bundle writer and the response body
+const EXPORT_BUFFER_SIZE: usize = 64 * 1024;
+
+#[utoipa::path(
+    get,
+    path = "/api/export",
+    responses(
+        (status = 200, description = "ZIP bundle with one CSV per resource and a datapackage.json descriptor", content_type = "application/zip")
+    ),
+    tag = "export"
+)]
+pub async fn export_database(db: web::Data<Database>) -> WebResult<HttpResponse> {
+    let (writer, reader) = tokio::io::duplex(EXPORT_BUFFER_SIZE);
+    let pool = db.pool().clone();
+
+    let bundle = actix_web::rt::spawn(async move { export::write_bundle(&pool, writer).await });
+
+    // The archive is streamed as it is written, after the 200 is sent: a failure
+    // ends the body with an error, so the download fails instead of stopping at a
+    // truncated archive
+    let outcome = stream::once(async move {
+        let error = match bundle.await {
+            Ok(Ok(_)) => return None,
+            Ok(Err(e)) => e.to_string(),
+            Err(e) => e.to_string(),
+        };
+        tracing::error!("Failed to write export bundle: {}", error);
+        Some(Err(io::Error::other(error)))
+    })
+    .filter_map(future::ready);
+
+    let filename = export::bundle_filename(chrono::Utc::now().date_naive());
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/zip")
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", filename),
+        ))
+        .streaming(ReaderStream::new(reader).chain(outcome)))
+}
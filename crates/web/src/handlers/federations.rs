@@ -0,0 +1,129 @@
+use actix_web::{HttpResponse, web};
+use storage::{
+    Database,
+    dto::federation::{ReplaceAgeDivisionsRequest, UpdateTieBreaksRequest},
+    models::{AgeDivision, Federation},
+    repository::federation::FederationRepository,
+};
+use uuid::Uuid;
+
+use crate::error::{WebError, WebResult};
+
+#[utoipa::path(
+    get,
+    path = "/api/federations",
+    responses(
+        (status = 200, description = "List all federations", body = Vec<Federation>)
+    ),
+    tag = "federations"
+)]
+pub async fn list_federations(db: web::Data<Database>) -> WebResult<HttpResponse> {
+    let repo = FederationRepository::new(db.pool());
+    let federations = repo.list().await?;
+
+    Ok(HttpResponse::Ok().json(federations))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/federations/{federation_id}/tie-breaks",
+    params(
+        ("federation_id" = Uuid, Path, description = "Federation ID")
+    ),
+    request_body = UpdateTieBreaksRequest,
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "Tie-break rules set; stored placings change when recomputed", body = Federation),
+        (status = 400, description = "Validation error"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Federation not found")
+    ),
+    tag = "federations"
+)]
+pub async fn update_tie_breaks(
+    db: web::Data<Database>,
+    path: web::Path<Uuid>,
+    payload: web::Json<UpdateTieBreaksRequest>,
+) -> WebResult<HttpResponse> {
+    let req = payload.into_inner();
+    req.validate_tie_breaks().map_err(WebError::BadRequest)?;
+
+    let repo = FederationRepository::new(db.pool());
+    let federation = repo
+        .update_tie_breaks(path.into_inner(), &req.tie_breaks)
+        .await?;
+
+    tracing::info!(
+        "Tie-breaks of {} set to {:?}",
+        federation.name,
+        federation.tie_breaks.0
+    );
+
+    Ok(HttpResponse::Ok().json(federation))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/federations/{federation_id}/age-divisions",
+    params(
+        ("federation_id" = Uuid, Path, description = "Federation ID")
+    ),
+    responses(
+        (status = 200, description = "Age divisions of the federation, youngest first", body = Vec<AgeDivision>),
+        (status = 404, description = "Federation not found")
+    ),
+    tag = "federations"
+)]
+pub async fn list_age_divisions(
+    db: web::Data<Database>,
+    path: web::Path<Uuid>,
+) -> WebResult<HttpResponse> {
+    let repo = FederationRepository::new(db.pool());
+    let federation = repo.find_by_id(path.into_inner()).await?;
+    let divisions = repo.list_age_divisions(federation.federation_id).await?;
+
+    Ok(HttpResponse::Ok().json(divisions))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/federations/{federation_id}/age-divisions",
+    params(
+        ("federation_id" = Uuid, Path, description = "Federation ID")
+    ),
+    request_body = ReplaceAgeDivisionsRequest,
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "Age divisions replaced", body = Vec<AgeDivision>),
+        (status = 400, description = "Validation error"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Federation not found")
+    ),
+    tag = "federations"
+)]
+pub async fn replace_age_divisions(
+    db: web::Data<Database>,
+    path: web::Path<Uuid>,
+    payload: web::Json<ReplaceAgeDivisionsRequest>,
+) -> WebResult<HttpResponse> {
+    let req = payload.into_inner();
+    req.validate_divisions().map_err(WebError::BadRequest)?;
+
+    let repo = FederationRepository::new(db.pool());
+    let federation = repo.find_by_id(path.into_inner()).await?;
+    let divisions = repo
+        .replace_age_divisions(federation.federation_id, &req.divisions)
+        .await?;
+
+    tracing::info!(
+        "Age divisions of {} replaced ({} division(s))",
+        federation.name,
+        divisions.len()
+    );
+
+    Ok(HttpResponse::Ok().json(divisions))
+}
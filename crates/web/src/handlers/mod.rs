@@ -1,4 +1,9 @@
 pub mod athletes;
+pub mod canonical;
 pub mod competitions;
+pub mod export;
+pub mod federations;
 pub mod ranking;
 pub mod ris;
+pub mod stats;
+pub mod submissions;
@@ -0,0 +1,46 @@
+use actix_web::{HttpResponse, web};
+use storage::{
+    Database,
+    dto::stats::{CompetitionStatsResponse, GlobalStatsResponse},
+    repository::stats::StatsRepository,
+};
+
+use crate::error::WebResult;
+
+#[utoipa::path(
+    get,
+    path = "/api/competitions/{slug}/stats",
+    params(
+        ("slug" = String, Path, description = "Competition slug")
+    ),
+    responses(
+        (status = 200, description = "Success rates, no-rep reasons, attempt jumps, bomb-outs and best lifters of the competition", body = CompetitionStatsResponse),
+        (status = 404, description = "Competition not found")
+    ),
+    tag = "stats"
+)]
+pub async fn get_competition_stats(
+    db: web::Data<Database>,
+    path: web::Path<String>,
+) -> WebResult<HttpResponse> {
+    let slug = path.into_inner();
+    let repo = StatsRepository::new(db.pool());
+    let stats = repo.competition_stats(&slug).await?;
+
+    Ok(HttpResponse::Ok().json(stats))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/stats/movements",
+    responses(
+        (status = 200, description = "Success rates, no-rep reasons, attempt jumps, bomb-outs and best lifters across all competitions", body = GlobalStatsResponse)
+    ),
+    tag = "stats"
+)]
+pub async fn get_movement_stats(db: web::Data<Database>) -> WebResult<HttpResponse> {
+    let repo = StatsRepository::new(db.pool());
+    let stats = repo.global_stats().await?;
+
+    Ok(HttpResponse::Ok().json(stats))
+}
@@ -0,0 +1,230 @@
+use actix_web::{HttpResponse, web};
+use importer::canonical::pipeline::PreparedImport;
+use serde_json::json;
+use storage::{
+    Database,
+    dto::submission::{
+        CreateSubmissionParams, ReviewSubmissionRequest, SubmissionListParams, SubmissionSummary,
+    },
+    error::StorageError,
+    models::{NewSubmission, Submission, SubmissionStatus},
+    repository::submission::SubmissionRepository,
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::error::{WebError, WebResult};
+
+#[utoipa::path(
+    post,
+    path = "/api/submissions",
+    params(CreateSubmissionParams),
+    request_body(content = Object, description = "Canonical JSON document", content_type = "application/json"),
+    responses(
+        (status = 201, description = "File validated and queued for review", body = Submission),
+        (status = 400, description = "Not a canonical document, or validation errors"),
+        (status = 409, description = "The same file is already waiting for review")
+    ),
+    tag = "submissions"
+)]
+pub async fn create_submission(
+    db: web::Data<Database>,
+    query: web::Query<CreateSubmissionParams>,
+    body: String,
+) -> WebResult<HttpResponse> {
+    let params = query.into_inner();
+    params.validate()?;
+
+    let prepared = PreparedImport::read(&body, None)
+        .map_err(|e| WebError::BadRequest(format!("Invalid canonical document: {}", e)))?;
+    if prepared.report.is_failure(false) {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Validation failed",
+            "details": prepared.report.issues
+        })));
+    }
+
+    let submission = NewSubmission {
+        competition_slug: prepared.canonical.competition.slug.clone(),
+        competition_name: prepared.canonical.competition.name.clone(),
+        content_hash: prepared.origin.content_hash.clone(),
+        validation_report: to_json(&prepared.report)?,
+        submitted_by: params.submitted_by,
+        content: body,
+    };
+    let created = SubmissionRepository::new(db.pool())
+        .create(&submission)
+        .await?;
+
+    Ok(HttpResponse::Created().json(created))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/submissions",
+    params(SubmissionListParams),
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "Submissions with the status, oldest first", body = Vec<SubmissionSummary>),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "submissions"
+)]
+pub async fn list_submissions(
+    db: web::Data<Database>,
+    query: web::Query<SubmissionListParams>,
+) -> WebResult<HttpResponse> {
+    let status = query.status.unwrap_or(SubmissionStatus::Pending);
+    let submissions = SubmissionRepository::new(db.pool()).list(status).await?;
+
+    Ok(HttpResponse::Ok().json(submissions))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/submissions/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Submission ID")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "Submission with its file, validation report and dry-run diff, the diff of a pending submission being computed on its first view", body = Submission),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Submission not found")
+    ),
+    tag = "submissions"
+)]
+pub async fn get_submission(
+    db: web::Data<Database>,
+    path: web::Path<Uuid>,
+) -> WebResult<HttpResponse> {
+    let id = path.into_inner();
+    let repo = SubmissionRepository::new(db.pool());
+    let submission = repo.find_by_id(id).await?;
+
+    // A dry run takes the locks of an import: it runs once, for a moderator, rather
+    // than on every anonymous upload
+    if submission.dry_run_diff.is_some() || submission.status != SubmissionStatus::Pending.as_str()
+    {
+        return Ok(HttpResponse::Ok().json(submission));
+    }
+
+    let prepared = PreparedImport::read(&submission.content, Some(format!("submission:{}", id)))?;
+    let diff = prepared.dry_run(db.pool()).await?;
+    let submission = repo.record_dry_run(id, &to_json(&diff)?).await?;
+
+    Ok(HttpResponse::Ok().json(submission))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/submissions/{id}/approve",
+    params(
+        ("id" = Uuid, Path, description = "Submission ID")
+    ),
+    request_body = ReviewSubmissionRequest,
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "File imported and submission approved", body = Submission),
+        (status = 400, description = "The file no longer passes validation"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Submission not found"),
+        (status = 409, description = "Submission already reviewed")
+    ),
+    tag = "submissions"
+)]
+pub async fn approve_submission(
+    db: web::Data<Database>,
+    path: web::Path<Uuid>,
+    payload: web::Json<ReviewSubmissionRequest>,
+) -> WebResult<HttpResponse> {
+    let id = path.into_inner();
+    let req = payload.into_inner();
+    req.validate()?;
+
+    let repo = SubmissionRepository::new(db.pool());
+    let submission = repo.find_by_id(id).await?;
+    ensure_pending(&submission)?;
+
+    // Validated again, the validator may have changed since the upload
+    let prepared = PreparedImport::read(&submission.content, Some(format!("submission:{}", id)))?;
+    prepared
+        .report
+        .ensure_valid(false)
+        .map_err(|e| WebError::BadRequest(e.to_string()))?;
+    let approved = repo
+        .approve(
+            id,
+            req.comment.as_deref(),
+            &prepared.canonical,
+            &prepared.origin,
+        )
+        .await?;
+    tracing::info!(
+        "Submission {} approved, {} imported",
+        id,
+        approved.competition_slug
+    );
+
+    Ok(HttpResponse::Ok().json(approved))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/submissions/{id}/reject",
+    params(
+        ("id" = Uuid, Path, description = "Submission ID")
+    ),
+    request_body = ReviewSubmissionRequest,
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "Submission rejected", body = Submission),
+        (status = 400, description = "Validation error"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Submission not found"),
+        (status = 409, description = "Submission already reviewed")
+    ),
+    tag = "submissions"
+)]
+pub async fn reject_submission(
+    db: web::Data<Database>,
+    path: web::Path<Uuid>,
+    payload: web::Json<ReviewSubmissionRequest>,
+) -> WebResult<HttpResponse> {
+    let req = payload.into_inner();
+    req.validate()?;
+
+    let rejected = SubmissionRepository::new(db.pool())
+        .review(
+            path.into_inner(),
+            SubmissionStatus::Rejected,
+            req.comment.as_deref(),
+        )
+        .await?;
+
+    Ok(HttpResponse::Ok().json(rejected))
+}
+
+fn ensure_pending(submission: &Submission) -> WebResult<()> {
+    if submission.status == SubmissionStatus::Pending.as_str() {
+        Ok(())
+    } else {
+        Err(StorageError::ConstraintViolation(format!(
+            "Submission was already {}",
+            submission.status
+        ))
+        .into())
+    }
+}
+
+fn to_json(value: &impl serde::Serialize) -> WebResult<serde_json::Value> {
+    serde_json::to_value(value).map_err(|e| WebError::InternalServerError(e.to_string()))
+}
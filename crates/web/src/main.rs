@@ -21,16 +21,42 @@ use middleware::auth::ApiKeys;
         handlers::competitions::list_competitions_detailed,
         handlers::competitions::get_competition,
         handlers::competitions::get_competition_detailed,
+        handlers::competitions::get_competition_canonical,
         handlers::competitions::create_competition,
         handlers::competitions::update_competition,
+        handlers::competitions::recompute_competition_placings,
         handlers::competitions::delete_competition,
+        handlers::competitions::list_competition_external_ids,
+        handlers::competitions::link_competition_external_id,
+        handlers::competitions::unlink_competition_external_id,
         handlers::athletes::list_athletes,
         handlers::athletes::get_athlete,
         handlers::athletes::get_athlete_detailed,
         handlers::athletes::create_athlete,
         handlers::athletes::update_athlete,
         handlers::athletes::delete_athlete,
+        handlers::athletes::search_athletes,
+        handlers::athletes::list_athlete_aliases,
+        handlers::athletes::add_athlete_alias,
+        handlers::athletes::delete_athlete_alias,
+        handlers::athletes::merge_athlete,
+        handlers::athletes::list_athlete_external_ids,
+        handlers::athletes::link_athlete_external_id,
+        handlers::athletes::unlink_athlete_external_id,
+        handlers::federations::list_federations,
+        handlers::federations::update_tie_breaks,
+        handlers::federations::list_age_divisions,
+        handlers::federations::replace_age_divisions,
         handlers::ranking::get_global_ranking,
+        handlers::stats::get_competition_stats,
+        handlers::stats::get_movement_stats,
+        handlers::export::export_database,
+        handlers::canonical::get_canonical_schema,
+        handlers::submissions::create_submission,
+        handlers::submissions::list_submissions,
+        handlers::submissions::get_submission,
+        handlers::submissions::approve_submission,
+        handlers::submissions::reject_submission,
     ),
     components(
         schemas(
@@ -53,14 +79,32 @@ use middleware::auth::ApiKeys;
             storage::dto::athlete::AthleteDetailResponse,
             storage::dto::athlete::AthleteCompetitionSummary,
             storage::dto::athlete::PersonalRecord,
+            storage::dto::athlete::CreateAliasRequest,
+            storage::dto::athlete::MergeAthleteRequest,
             storage::dto::common::PaginationMeta,
+            storage::dto::external_id::LinkExternalIdRequest,
+            storage::dto::federation::AgeDivisionRequest,
+            storage::dto::federation::ReplaceAgeDivisionsRequest,
+            storage::dto::federation::UpdateTieBreaksRequest,
             storage::dto::ranking::GlobalRankingEntry,
             storage::dto::ranking::AthleteInfo,
             storage::dto::ranking::CompetitionInfo,
+            storage::dto::stats::CompetitionStatsResponse,
+            storage::dto::stats::GlobalStatsResponse,
+            storage::dto::stats::AttemptStats,
+            storage::dto::stats::MovementStats,
+            storage::dto::stats::AttemptNumberStats,
+            storage::dto::stats::NoRepReasonCount,
+            storage::dto::stats::TopLifter,
+            storage::dto::submission::ReviewSubmissionRequest,
+            storage::dto::submission::SubmissionSummary,
             storage::models::Competition,
             storage::models::Athlete,
+            storage::models::AthleteAlias,
             storage::models::Category,
             storage::models::Federation,
+            storage::models::AgeDivision,
+            storage::models::TieBreak,
             storage::models::Movement,
             storage::models::Lift,
             storage::models::Attempt,
@@ -69,12 +113,21 @@ use middleware::auth::ApiKeys;
             storage::models::Social,
             storage::models::Rulebook,
             storage::models::AthleteSocial,
+            storage::models::ImportBatch,
+            storage::models::ExternalId,
+            storage::models::Submission,
+            storage::models::SubmissionStatus,
         )
     ),
     tags(
         (name = "competitions", description = "Public competition endpoints"),
         (name = "athletes", description = "Public athlete endpoints"),
+        (name = "federations", description = "Federations and their age divisions"),
         (name = "rankings", description = "Public ranking endpoints"),
+        (name = "stats", description = "Public statistics endpoints"),
+        (name = "export", description = "Open-data bulk export"),
+        (name = "canonical", description = "Canonical import format"),
+        (name = "submissions", description = "Canonical files uploaded for review"),
     ),
     modifiers(&SecurityAddon)
 )]
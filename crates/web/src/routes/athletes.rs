@@ -2,8 +2,10 @@ use actix_web::web;
 use actix_web_httpauth::middleware::HttpAuthentication;
 
 use crate::handlers::athletes::{
-    create_athlete, delete_athlete, get_athlete, get_athlete_detailed, list_athletes,
-    update_athlete,
+    add_athlete_alias, create_athlete, delete_athlete, delete_athlete_alias, get_athlete,
+    get_athlete_detailed, link_athlete_external_id, list_athlete_aliases,
+    list_athlete_external_ids, list_athletes, merge_athlete, search_athletes,
+    unlink_athlete_external_id, update_athlete,
 };
 use crate::middleware::auth::api_key_validator;
 
@@ -13,10 +15,39 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/athletes")
             .route("", web::get().to(list_athletes))
+            .route("/search", web::get().to(search_athletes))
             .route("/{slug}", web::get().to(get_athlete))
             .route("/{slug}/detailed", web::get().to(get_athlete_detailed))
+            .route("/{slug}/aliases", web::get().to(list_athlete_aliases))
             .route("", web::post().to(create_athlete).wrap(auth.clone()))
             .route("/{slug}", web::put().to(update_athlete).wrap(auth.clone()))
-            .route("/{slug}", web::delete().to(delete_athlete).wrap(auth)),
+            .route(
+                "/{slug}",
+                web::delete().to(delete_athlete).wrap(auth.clone()),
+            )
+            .route(
+                "/{slug}/aliases",
+                web::post().to(add_athlete_alias).wrap(auth.clone()),
+            )
+            .route(
+                "/{slug}/aliases/{alias_id}",
+                web::delete().to(delete_athlete_alias).wrap(auth.clone()),
+            )
+            .route(
+                "/{slug}/merge",
+                web::post().to(merge_athlete).wrap(auth.clone()),
+            )
+            .route(
+                "/{slug}/external-ids",
+                web::get().to(list_athlete_external_ids).wrap(auth.clone()),
+            )
+            .route(
+                "/{slug}/external-ids",
+                web::post().to(link_athlete_external_id).wrap(auth.clone()),
+            )
+            .route(
+                "/{slug}/external-ids/{source}/{external_id}",
+                web::delete().to(unlink_athlete_external_id).wrap(auth),
+            ),
     );
 }
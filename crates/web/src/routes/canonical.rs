@@ -0,0 +1,7 @@
+use actix_web::web;
+
+use crate::handlers::canonical::get_canonical_schema;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.route("/canonical/schema", web::get().to(get_canonical_schema));
+}
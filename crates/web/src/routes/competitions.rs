@@ -2,9 +2,12 @@ use actix_web::web;
 use actix_web_httpauth::middleware::HttpAuthentication;
 
 use crate::handlers::competitions::{
-    create_competition, delete_competition, get_competition, get_competition_detailed,
-    list_competitions, list_competitions_detailed, update_competition,
+    create_competition, delete_competition, get_competition, get_competition_canonical,
+    get_competition_detailed, link_competition_external_id, list_competition_external_ids,
+    list_competitions, list_competitions_detailed, recompute_competition_placings,
+    unlink_competition_external_id, update_competition,
 };
+use crate::handlers::stats::get_competition_stats;
 use crate::middleware::auth::api_key_validator;
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
@@ -16,11 +19,41 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
             .route("/detailed", web::get().to(list_competitions_detailed))
             .route("/{slug}", web::get().to(get_competition))
             .route("/{slug}/detailed", web::get().to(get_competition_detailed))
+            .route("/{slug}/stats", web::get().to(get_competition_stats))
+            .route(
+                "/{slug}/canonical",
+                web::get().to(get_competition_canonical),
+            )
             .route("", web::post().to(create_competition).wrap(auth.clone()))
             .route(
                 "/{slug}",
                 web::put().to(update_competition).wrap(auth.clone()),
             )
-            .route("/{slug}", web::delete().to(delete_competition).wrap(auth)),
+            .route(
+                "/{slug}",
+                web::delete().to(delete_competition).wrap(auth.clone()),
+            )
+            .route(
+                "/{slug}/placings",
+                web::post()
+                    .to(recompute_competition_placings)
+                    .wrap(auth.clone()),
+            )
+            .route(
+                "/{slug}/external-ids",
+                web::get()
+                    .to(list_competition_external_ids)
+                    .wrap(auth.clone()),
+            )
+            .route(
+                "/{slug}/external-ids",
+                web::post()
+                    .to(link_competition_external_id)
+                    .wrap(auth.clone()),
+            )
+            .route(
+                "/{slug}/external-ids/{source}/{external_id}",
+                web::delete().to(unlink_competition_external_id).wrap(auth),
+            ),
     );
 }
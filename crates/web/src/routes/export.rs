@@ -0,0 +1,7 @@
+use actix_web::web;
+
+use crate::handlers::export::export_database;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.route("/export", web::get().to(export_database));
+}
@@ -0,0 +1,28 @@
+use actix_web::web;
+use actix_web_httpauth::middleware::HttpAuthentication;
+
+use crate::handlers::federations::{
+    list_age_divisions, list_federations, replace_age_divisions, update_tie_breaks,
+};
+use crate::middleware::auth::api_key_validator;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    let auth = HttpAuthentication::bearer(api_key_validator);
+
+    cfg.service(
+        web::scope("/federations")
+            .route("", web::get().to(list_federations))
+            .route(
+                "/{federation_id}/tie-breaks",
+                web::put().to(update_tie_breaks).wrap(auth.clone()),
+            )
+            .route(
+                "/{federation_id}/age-divisions",
+                web::get().to(list_age_divisions),
+            )
+            .route(
+                "/{federation_id}/age-divisions",
+                web::put().to(replace_age_divisions).wrap(auth),
+            ),
+    );
+}
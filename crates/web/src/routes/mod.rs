@@ -1,16 +1,26 @@
 use actix_web::web;
 
 pub mod athletes;
+pub mod canonical;
 pub mod competitions;
+pub mod export;
+pub mod federations;
 pub mod ranking;
 pub mod ris;
+pub mod stats;
+pub mod submissions;
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/api")
             .configure(competitions::configure)
             .configure(athletes::configure)
+            .configure(federations::configure)
             .configure(ranking::configure)
-            .configure(ris::configure),
+            .configure(ris::configure)
+            .configure(stats::configure)
+            .configure(export::configure)
+            .configure(canonical::configure)
+            .configure(submissions::configure),
     );
 }
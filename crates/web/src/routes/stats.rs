@@ -0,0 +1,7 @@
+use actix_web::web;
+
+use crate::handlers::stats::get_movement_stats;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::scope("/stats").route("/movements", web::get().to(get_movement_stats)));
+}
@@ -0,0 +1,27 @@
+use actix_web::web;
+use actix_web_httpauth::middleware::HttpAuthentication;
+
+use crate::handlers::submissions::{
+    approve_submission, create_submission, get_submission, list_submissions, reject_submission,
+};
+use crate::middleware::auth::api_key_validator;
+
+/// Largest canonical file accepted, a full competition weighs a few hundred kB
+const MAX_SUBMISSION_SIZE: usize = 1024 * 1024;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    let auth = HttpAuthentication::bearer(api_key_validator);
+
+    cfg.service(
+        web::scope("/submissions")
+            .app_data(web::PayloadConfig::new(MAX_SUBMISSION_SIZE))
+            .route("", web::post().to(create_submission))
+            .route("", web::get().to(list_submissions).wrap(auth.clone()))
+            .route("/{id}", web::get().to(get_submission).wrap(auth.clone()))
+            .route(
+                "/{id}/approve",
+                web::post().to(approve_submission).wrap(auth.clone()),
+            )
+            .route("/{id}/reject", web::post().to(reject_submission).wrap(auth)),
+    );
+}